@@ -0,0 +1,100 @@
+//! Regenerate `src/stations.csv` from the aviationweather.gov station list,
+//! which covers every station that issues METARs or TAFs:
+//!
+//! ```sh
+//! curl -sSLO https://aviationweather.gov/data/cache/stations.cache.json.gz
+//! cargo run --example update_stations -- stations.cache.json.gz > src/stations.csv
+//! ```
+//!
+//! Cities and time zones are kept from the current table, since the station
+//! list has neither.
+
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use weather::units::FEET_PER_METER;
+
+const CURRENT_TABLE: &str = include_str!("../src/stations.csv");
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiStation {
+    icao_id: Option<String>,
+    iata_id: Option<String>,
+    faa_id: Option<String>,
+    site: Option<String>,
+    state: Option<String>,
+    country: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    /// Meters
+    elev: Option<f64>,
+}
+
+/// A field without the commas and padding the table format can't hold
+fn field(value: Option<&str>) -> String {
+    let value = value.unwrap_or("").replace(',', " ");
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("usage: update_stations STATIONS.JSON[.GZ]")?;
+    let mut file = BufReader::new(File::open(&path)?);
+    let mut text = String::new();
+    if path.ends_with(".gz") {
+        GzDecoder::new(file).read_to_string(&mut text)?;
+    } else {
+        file.read_to_string(&mut text)?;
+    }
+    let mut stations: Vec<ApiStation> = serde_json::from_str(&text)?;
+    stations.retain(|station| {
+        station.icao_id.as_deref().is_some_and(|id| id.len() == 4)
+            && station.site.is_some()
+            && station.country.is_some()
+            && station.lat.is_some()
+            && station.lon.is_some()
+    });
+    stations.sort_by(|a, b| a.icao_id.cmp(&b.icao_id));
+    stations.dedup_by(|a, b| a.icao_id == b.icao_id);
+
+    // icao -> (city, timezone)
+    let current: HashMap<&str, (&str, &str)> = CURRENT_TABLE
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            Some((*fields.first()?, (*fields.get(4)?, *fields.get(10)?)))
+        })
+        .collect();
+
+    for line in CURRENT_TABLE
+        .lines()
+        .take_while(|line| line.starts_with('#'))
+    {
+        println!("{}", line);
+    }
+    for station in &stations {
+        let icao = station.icao_id.as_deref().unwrap_or_default();
+        let (city, timezone) = current.get(icao).copied().unwrap_or_default();
+        println!(
+            "{},{},{},{},{},{},{},{:.4},{:.4},{:.0},{}",
+            icao,
+            field(station.iata_id.as_deref()),
+            field(station.faa_id.as_deref()),
+            field(station.site.as_deref()),
+            city,
+            field(station.state.as_deref()),
+            field(station.country.as_deref()),
+            station.lat.unwrap_or_default(),
+            station.lon.unwrap_or_default(),
+            station.elev.unwrap_or_default() * FEET_PER_METER,
+            timezone
+        );
+    }
+    eprintln!("{} stations", stations.len());
+    Ok(())
+}
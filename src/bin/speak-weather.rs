@@ -1,11 +1,18 @@
 use clap::{Args, Parser, Subcommand};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use weather::error::{exit_code, report_error};
+use weather::output::{OutputFormat, write_reports};
 use weather::{
-    fetch_weather_data,
+    MetarData, ObservationCache, Units, WeatherClient, WeatherError, parse_metar_text,
+    read_metar_input, resolve_station_id,
     tts::{
-        AnnouncementFormat, AudioFormat, Voice,
+        AnnouncementFormat, AnnouncementOptions, AnnouncementTemplate, AudioFormat,
+        InformationLetters, Language, Phraseology, Voice, combine_roundup,
         espeak::{EspeakTts, EspeakVoice},
-        execute_tts_output, generate_weather_announcement,
-        google_tts::GoogleTts,
+        execute_tts_output, generate_roundup_announcement, generate_taf_announcement,
+        generate_trend_announcement,
+        google_tts::{GoogleTts, GoogleVoice},
     },
 };
 
@@ -18,6 +25,14 @@ struct Cli {
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    #[command(flatten)]
+    Engine(Engine),
+    /// Announce the terminal aerodrome forecast (TAF) instead of current conditions
+    Taf(TafArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum Engine {
     /// Use eSpeak TTS engine
     Espeak(EspeakArgs),
     /// Use Google Cloud TTS engine
@@ -26,18 +41,88 @@ enum Commands {
     Text(TextArgs),
 }
 
+#[derive(Args, Debug)]
+struct TafArgs {
+    /// Number of hours of forecast to summarize
+    #[arg(long, default_value = "6")]
+    hours: u32,
+
+    #[command(subcommand)]
+    engine: Engine,
+}
+
+/// Which product to announce
+#[derive(Debug, Clone, Copy)]
+enum Product {
+    Metar,
+    Taf { hours: u32 },
+}
+
 #[derive(Args, Debug)]
 struct CommonArgs {
-    /// ICAO airport identifier (e.g., KJFK, EGLL, KSFO)
-    icao: String,
+    /// Airport identifiers, ICAO or IATA/FAA (e.g., KJFK, EGLL, SFO); several make a regional roundup.
+    /// Use - to announce METARs read from stdin
+    #[arg(required_unless_present_any = ["raw", "metar_file"])]
+    icao: Vec<String>,
+
+    /// Announce this raw METAR instead of fetching (repeatable)
+    #[arg(long, value_name = "METAR", conflicts_with = "icao")]
+    raw: Vec<String>,
+
+    /// Announce raw METARs from a file, one per line (- for stdin)
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["icao", "raw"])]
+    metar_file: Option<String>,
 
     /// Output format for announcement
     #[arg(short, long, value_enum, default_value = "speech")]
     format: AnnouncementFormat,
 
+    /// How to read identifiers and numbers (default: aviation for the
+    /// aviation format, plain otherwise)
+    #[arg(long, value_enum, value_name = "STYLE")]
+    phraseology: Option<Phraseology>,
+
+    /// File keeping the ATIS information letters between runs (default:
+    /// $XDG_STATE_HOME/weather/atis.json, but only for fetched reports)
+    #[arg(long, value_name = "PATH")]
+    atis_state: Option<PathBuf>,
+
+    /// Announce METARs with this template instead of the format's built-in one
+    #[arg(long, value_name = "PATH")]
+    template: Option<PathBuf>,
+
+    /// Language to announce in, with a voice to match
+    #[arg(long, value_enum, default_value = "en")]
+    lang: Language,
+
     /// Save output to file instead of speaking/printing
     #[arg(short, long)]
     output: Option<String>,
+
+    /// Hours of history to analyze for the trend format
+    #[arg(long, value_name = "HOURS", default_value = "3")]
+    history: u32,
+
+    /// Units to speak: imperial, metric or aviation, optionally with overrides
+    /// such as "metric,wind=kt" (default: Fahrenheit, knots, miles, inHg, feet)
+    #[arg(long, value_name = "UNITS", default_value = "default")]
+    units: Units,
+
+    /// Base URL of the aviationweather.gov data API or a mirror of it
+    #[arg(long, value_name = "URL")]
+    api_url: Option<String>,
+
+    /// Request timeout in seconds
+    #[arg(long, value_name = "SECONDS", default_value = "30")]
+    timeout: u64,
+
+    /// Cache responses in this directory, serving stale data if the network is down
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Seconds a cached response is used before checking for a newer one
+    #[arg(long, value_name = "SECONDS", default_value = "300")]
+    max_age: u64,
 }
 
 #[derive(Args, Debug)]
@@ -84,61 +169,332 @@ struct GoogleArgs {
 struct TextArgs {
     #[command(flatten)]
     common: CommonArgs,
+
+    /// Print the decoded reports, with derived values and WMO codes, as JSON
+    /// instead of an announcement
+    #[arg(long, conflicts_with = "format")]
+    json: bool,
 }
 
-fn create_espeak_voice(voice: Voice, speed: u32, pitch: u32, gap: u32) -> EspeakVoice {
-    let mut espeak_voice: EspeakVoice = voice.into();
+fn create_espeak_voice(
+    voice: Voice,
+    language: Language,
+    speed: u32,
+    pitch: u32,
+    gap: u32,
+) -> EspeakVoice {
+    let mut espeak_voice = EspeakVoice::from(voice).with_language(language);
     espeak_voice.speed = speed;
     espeak_voice.pitch = pitch;
     espeak_voice.gap = gap;
     espeak_voice
 }
 
-fn handle_espeak(args: EspeakArgs) -> Result<(), Box<dyn std::error::Error>> {
-    println!(
-        "Fetching weather for {}...\n",
-        args.common.icao.to_uppercase()
-    );
+/// Build one announcement per station, skipping (with a warning) stations that fail.
+/// Only fails if every station does.
+fn announce_each(
+    stations: &[String],
+    announce: impl Fn(&str) -> Result<String, WeatherError>,
+) -> Result<Vec<String>, WeatherError> {
+    let mut announcements = Vec::new();
+    let mut last_error = None;
+    for icao in stations {
+        match announce(icao) {
+            Ok(announcement) => announcements.push(announcement),
+            Err(e) => {
+                eprintln!("Warning: skipping {}: {}", icao.to_uppercase(), e);
+                last_error = Some(e);
+            }
+        }
+    }
+    match last_error {
+        Some(e) if announcements.is_empty() => Err(e),
+        _ => Ok(announcements),
+    }
+}
+
+/// Raw METAR text given with --raw, --metar-file or `-`, if any
+fn offline_input(common: &CommonArgs) -> Option<Result<String, WeatherError>> {
+    if !common.raw.is_empty() {
+        return Some(Ok(common.raw.join("\n")));
+    }
+    let path = common
+        .metar_file
+        .as_deref()
+        .or_else(|| (common.icao == ["-"]).then_some("-"))?;
+    Some(read_metar_input(path))
+}
+
+/// Decode METARs supplied as text, skipping (with a warning) those that fail.
+/// Only fails if every report does.
+fn decode_reports(text: &str) -> Result<Vec<MetarData>, Box<dyn std::error::Error>> {
+    let mut reports = Vec::new();
+    let mut last_error = None;
+    for result in parse_metar_text(text) {
+        match result {
+            Ok(metar) => reports.push(metar),
+            Err(e) => {
+                eprintln!("Warning: skipping report: {}", e);
+                last_error = Some(e);
+            }
+        }
+    }
+    if reports.is_empty() {
+        return Err(match last_error {
+            Some(e) => WeatherError::InvalidReport(e).into(),
+            None => "No METARs found in input".into(),
+        });
+    }
+    Ok(reports)
+}
+
+/// Announce METARs supplied as text. With the trend format, reports from the
+/// same station are treated as its history.
+fn offline_announcement(
+    text: &str,
+    common: &CommonArgs,
+    options: &mut AnnouncementOptions,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let format = &common.format;
+    let reports = decode_reports(text)?;
+    if matches!(format, AnnouncementFormat::Atis) {
+        // Replayed reports would advance a live broadcast's letters, so
+        // they are only kept in a file given explicitly
+        options.information_letters =
+            update_information_letters(common.atis_state.clone(), &reports);
+    }
+    if !matches!(format, AnnouncementFormat::Trend) {
+        return Ok(generate_roundup_announcement(&reports, format, options)?);
+    }
+    let mut stations: Vec<(String, Vec<MetarData>)> = Vec::new();
+    for metar in reports {
+        match stations.iter_mut().find(|(icao, _)| *icao == metar.icao_id) {
+            Some((_, history)) => history.push(metar),
+            None => stations.push((metar.icao_id.clone(), vec![metar])),
+        }
+    }
+    let announcements = stations
+        .iter()
+        .map(|(_, history)| generate_trend_announcement(history, options))
+        .collect::<Result<Vec<String>, WeatherError>>()?;
+    Ok(combine_roundup(&announcements, options.language))
+}
+
+fn build_client(common: &CommonArgs) -> Result<WeatherClient, WeatherError> {
+    let mut builder = WeatherClient::builder()
+        .timeout(Some(std::time::Duration::from_secs(common.timeout)))
+        .on_warning(|warning| eprintln!("Warning: {}", warning));
+    if let Some(ref url) = common.api_url {
+        builder = builder.base_url(url);
+    }
+    if let Some(ref dir) = common.cache_dir {
+        builder = builder.cache(ObservationCache::new(
+            dir,
+            std::time::Duration::from_secs(common.max_age),
+        ));
+    }
+    builder.build()
+}
+
+/// The latest METAR for each station, skipping (with a warning) stations
+/// that fail or are missing from the response. Fails if none has a report.
+fn fetch_metars(client: &WeatherClient, icaos: &[String]) -> Result<Vec<MetarData>, WeatherError> {
+    let ids: Vec<&str> = icaos.iter().map(String::as_str).collect();
+    let mut results = client.fetch_weather_batch(&ids)?;
+    let mut reports = Vec::new();
+    let mut requested = HashSet::new();
+    let mut last_error = None;
+    for icao in icaos {
+        let id = icao.to_uppercase();
+        // Listed twice on the command line; announced once
+        if !requested.insert(id.clone()) {
+            continue;
+        }
+        match results
+            .remove(&id)
+            .unwrap_or_else(|| Err(WeatherError::NoData(id.clone())))
+        {
+            Ok(metar) => reports.push(metar),
+            Err(e) => {
+                eprintln!("Warning: skipping {}: {}", id, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    if reports.is_empty() {
+        return Err(last_error.unwrap_or_else(|| WeatherError::NoData(icaos.join(", "))));
+    }
+    Ok(reports)
+}
+
+fn station_ids(common: &CommonArgs) -> Vec<String> {
+    common
+        .icao
+        .iter()
+        .map(|id| resolve_station_id(id))
+        .collect()
+}
+
+/// The METARs to print with --json, decoded from raw input or fetched
+fn fetch_reports(
+    common: &CommonArgs,
+    product: Product,
+) -> Result<Vec<MetarData>, Box<dyn std::error::Error>> {
+    if !matches!(product, Product::Metar) {
+        return Err("JSON output is only supported for METARs".into());
+    }
+    if let Some(input) = offline_input(common) {
+        return decode_reports(&input?);
+    }
+    Ok(fetch_metars(&build_client(common)?, &station_ids(common))?)
+}
+
+/// Where ATIS letters are kept when --atis-state is not given
+fn default_atis_state() -> Option<PathBuf> {
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state"))
+        })?;
+    Some(state_home.join("weather").join("atis.json"))
+}
 
-    let metar = fetch_weather_data(&args.common.icao)?;
-    let announcement = generate_weather_announcement(&metar, &args.common.format);
+/// Advance the ATIS letters of stations whose observation changed since the
+/// last broadcast, and save them to `path` if there is one. Failing to read
+/// or save only warns, since the broadcast can go ahead with derived letters.
+fn update_information_letters(path: Option<PathBuf>, reports: &[MetarData]) -> InformationLetters {
+    let mut letters = match path.as_ref().map(InformationLetters::load) {
+        Some(Ok(letters)) => letters,
+        Some(Err(e)) => {
+            eprintln!("Warning: {}", e);
+            InformationLetters::default()
+        }
+        None => InformationLetters::default(),
+    };
+    for metar in reports {
+        letters.update(metar);
+    }
+    if let Some(path) = path
+        && let Err(e) = letters.save(&path)
+    {
+        eprintln!("Warning: {}", e);
+    }
+    letters
+}
+
+fn fetch_announcement(
+    common: &CommonArgs,
+    product: Product,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut options = AnnouncementOptions {
+        units: common.units,
+        phraseology: common.phraseology,
+        language: common.lang,
+        ..Default::default()
+    };
+    if let Some(path) = &common.template {
+        if !matches!(product, Product::Metar) {
+            return Err("Templates are only supported for METAR announcements".into());
+        }
+        options.template = Some(AnnouncementTemplate::read(path)?);
+    }
+    if let Some(input) = offline_input(common) {
+        if !matches!(product, Product::Metar) {
+            return Err("Raw input is only supported for METARs".into());
+        }
+        return offline_announcement(&input?, common, &mut options);
+    }
+    let icaos = station_ids(common);
+    let stations = icaos.join(", ");
+
+    let client = build_client(common)?;
+    let announcement = match product {
+        Product::Metar if matches!(common.format, AnnouncementFormat::Trend) => {
+            println!(
+                "Fetching {} hours of weather for {}...\n",
+                common.history, stations
+            );
+            let announcements = announce_each(&icaos, |icao| {
+                let history = client.fetch_weather_history(icao, common.history)?;
+                generate_trend_announcement(&history, &options)
+            })?;
+            combine_roundup(&announcements, options.language)
+        }
+        Product::Metar => {
+            println!("Fetching weather for {}...\n", stations);
+            let reports = fetch_metars(&client, &icaos)?;
+            if matches!(common.format, AnnouncementFormat::Atis) {
+                let path = common.atis_state.clone().or_else(default_atis_state);
+                options.information_letters = update_information_letters(path, &reports);
+            }
+            generate_roundup_announcement(&reports, &common.format, &options)?
+        }
+        Product::Taf { hours } => {
+            println!("Fetching forecast for {}...\n", stations);
+            let announcements = announce_each(&icaos, |icao| {
+                let taf = client.fetch_taf(icao)?;
+                Ok(generate_taf_announcement(
+                    &taf,
+                    &common.format,
+                    hours,
+                    &options,
+                ))
+            })?;
+            combine_roundup(&announcements, options.language)
+        }
+    };
+    Ok(announcement)
+}
+
+fn handle_espeak(args: EspeakArgs, product: Product) -> Result<(), Box<dyn std::error::Error>> {
+    let announcement = fetch_announcement(&args.common, product)?;
     println!("Announcement text: {}\n", announcement);
 
-    let voice = create_espeak_voice(args.voice, args.speed, args.pitch, args.gap);
+    let voice = create_espeak_voice(
+        args.voice,
+        args.common.lang,
+        args.speed,
+        args.pitch,
+        args.gap,
+    );
     let tts = EspeakTts::new(voice)?;
     execute_tts_output(&tts, &announcement, args.common.output, &args.audio_format)?;
 
     Ok(())
 }
 
-fn handle_google(args: GoogleArgs) -> Result<(), Box<dyn std::error::Error>> {
-    println!(
-        "Fetching weather for {}...\n",
-        args.common.icao.to_uppercase()
-    );
-
-    let metar = fetch_weather_data(&args.common.icao)?;
-    let announcement = generate_weather_announcement(&metar, &args.common.format);
+fn handle_google(args: GoogleArgs, product: Product) -> Result<(), Box<dyn std::error::Error>> {
+    let announcement = fetch_announcement(&args.common, product)?;
     println!("Announcement text: {}\n", announcement);
 
     // Get Google Cloud API key from environment
     let api_key = std::env::var("GOOGLE_CLOUD_API_KEY")
         .map_err(|_| "GOOGLE_CLOUD_API_KEY environment variable not set. Please set your Google Cloud TTS API key: export GOOGLE_CLOUD_API_KEY=your_api_key_here")?;
 
-    let tts = GoogleTts::new(api_key, args.voice.into());
+    let tts = GoogleTts::new(
+        api_key,
+        GoogleVoice::for_language(args.voice, args.common.lang),
+    );
     execute_tts_output(&tts, &announcement, args.common.output, &args.audio_format)?;
 
     Ok(())
 }
 
-fn handle_text(args: TextArgs) -> Result<(), Box<dyn std::error::Error>> {
-    println!(
-        "Fetching weather for {}...\n",
-        args.common.icao.to_uppercase()
-    );
+fn handle_text(args: TextArgs, product: Product) -> Result<(), Box<dyn std::error::Error>> {
+    if args.json {
+        let reports = fetch_reports(&args.common, product)?;
+        match args.common.output {
+            Some(output_path) => {
+                let file = std::fs::File::create(&output_path)?;
+                write_reports(&reports, OutputFormat::Json, file)?;
+                println!("JSON saved to: {}", output_path);
+            }
+            None => write_reports(&reports, OutputFormat::Json, std::io::stdout().lock())?,
+        }
+        return Ok(());
+    }
 
-    let metar = fetch_weather_data(&args.common.icao)?;
-    let announcement = generate_weather_announcement(&metar, &args.common.format);
+    let announcement = fetch_announcement(&args.common, product)?;
 
     if let Some(output_path) = args.common.output {
         std::fs::write(&output_path, &announcement)?;
@@ -150,17 +506,24 @@ fn handle_text(args: TextArgs) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+fn handle_engine(engine: Engine, product: Product) -> Result<(), Box<dyn std::error::Error>> {
+    match engine {
+        Engine::Espeak(args) => handle_espeak(args, product),
+        Engine::Google(args) => handle_google(args, product),
+        Engine::Text(args) => handle_text(args, product),
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
     let result = match cli.command {
-        Commands::Espeak(args) => handle_espeak(args),
-        Commands::Google(args) => handle_google(args),
-        Commands::Text(args) => handle_text(args),
+        Commands::Engine(engine) => handle_engine(engine, Product::Metar),
+        Commands::Taf(args) => handle_engine(args.engine, Product::Taf { hours: args.hours }),
     };
 
     if let Err(e) = result {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
+        report_error(e.as_ref());
+        std::process::exit(exit_code(e.as_ref()));
     }
 }
@@ -1,24 +1,638 @@
-use clap::Parser;
-use weather::{display_weather, fetch_weather_data};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::time::Duration;
+use weather::bulk::METARS_CSV_URL;
+use weather::error::{EXIT_INVALID_REPORT, report_error};
+use weather::location::compass_point;
+use weather::output::{OutputFormat, write_reports};
+use weather::xml::parse_metar_document;
+use weather::{
+    BoundingBox, BulkMetars, Coordinates, DisplayOptions, FlightCategory, MetarData, MetarQuery,
+    ObservationCache, ResponseFormat, Units, WeatherClient, WeatherError, display_history,
+    display_taf, display_weather, filter_by_flight_category, parse_metar_text, read_metar_input,
+    resolve_station_id,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Fetch aviation weather from aviationweather.gov", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    /// ICAO airport identifier (e.g., KJFK, EGLL, KSFO)
-    #[arg(value_name = "ICAO")]
-    icao: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Airport identifiers, ICAO or IATA/FAA (e.g., KJFK, EGLL, SFO), or - to decode METARs from stdin
+    #[arg(value_name = "ICAO", required_unless_present_any = ["raw", "metar_file", "near", "grid", "bbox", "state"])]
+    icao: Vec<String>,
+
+    /// Decode this raw METAR instead of fetching (repeatable)
+    #[arg(long, value_name = "METAR", conflicts_with = "icao")]
+    raw: Vec<String>,
+
+    /// Decode raw METARs from a file, one per line, or METARs from an API XML
+    /// or IWXXM document (- for stdin)
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["icao", "raw"])]
+    metar_file: Option<String>,
+
+    /// Show observations from the last N hours with trends
+    #[arg(long, value_name = "N", conflicts_with_all = ["raw", "metar_file"])]
+    history: Option<u32>,
+
+    /// Show weather from the nearest reporting station to LAT,LON (e.g., 37.33,-121.89)
+    #[arg(long, value_name = "LAT,LON", allow_hyphen_values = true,
+          conflicts_with_all = ["icao", "raw", "metar_file", "history", "grid", "bbox", "state"])]
+    near: Option<Coordinates>,
+
+    /// Show weather from the nearest reporting station to a Maidenhead grid locator (e.g., CM97)
+    #[arg(long, value_name = "LOCATOR", value_parser = Coordinates::from_maidenhead,
+          conflicts_with_all = ["icao", "raw", "metar_file", "history", "bbox", "state"])]
+    grid: Option<Coordinates>,
+
+    /// Number of nearby reporting stations to list with --near or --grid
+    #[arg(long, value_name = "N", default_value = "5", value_parser = clap::value_parser!(u16).range(1..))]
+    count: u16,
+
+    /// Tabulate every station in the box with these corners (e.g., 37,-123,38.5,-121)
+    #[arg(long, value_name = "LAT0,LON0,LAT1,LON1", allow_hyphen_values = true,
+          conflicts_with_all = ["icao", "raw", "metar_file", "history", "state"])]
+    bbox: Option<BoundingBox>,
+
+    /// Tabulate every station in a US state or Canadian province (e.g., CA)
+    #[arg(long, value_name = "CODE", value_parser = MetarQuery::state,
+          conflicts_with_all = ["icao", "raw", "metar_file", "history"])]
+    state: Option<MetarQuery>,
+
+    /// Tabulate every station within this many kilometers of the given station,
+    /// or of the --near or --grid position
+    #[arg(long, value_name = "KM", conflicts_with_all = ["raw", "metar_file", "history", "bbox", "state"])]
+    radius: Option<f64>,
+
+    /// Order of the table for --bbox, --state and --radius
+    #[arg(long, value_enum, value_name = "KEY", default_value = "category")]
+    sort: SortKey,
+
+    /// Answer from the bulk METAR file that aviationweather.gov publishes,
+    /// downloaded once, instead of per-station requests
+    #[arg(long, conflicts_with_all = ["raw", "metar_file", "history"])]
+    bulk: bool,
+
+    /// Like --bulk, but from this bulk METAR file or URL (CSV or XML, optionally gzipped)
+    #[arg(long, value_name = "PATH|URL", conflicts_with_all = ["raw", "metar_file", "history", "bulk"])]
+    bulk_file: Option<String>,
+
+    /// Only list stations in these flight categories (repeatable)
+    #[arg(long, value_enum, value_name = "CATEGORY")]
+    category: Vec<FlightCategory>,
+
+    /// Write the decoded reports, with derived values and WMO codes, as data
+    /// instead of text
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    output: Option<OutputFormat>,
+
+    /// Color flight categories with ANSI escape codes
+    #[arg(long)]
+    color: bool,
+
+    /// Units to show: imperial, metric or aviation, optionally with overrides
+    /// such as "metric,wind=kt" (default: Fahrenheit, knots, miles, inHg, feet)
+    #[arg(long, value_name = "UNITS", default_value = "default")]
+    units: Units,
+
+    /// Base URL of the aviationweather.gov data API or a mirror of it
+    #[arg(long, value_name = "URL", global = true)]
+    api_url: Option<String>,
+
+    /// Format to request from the API; reports are shown the same either way
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "json",
+        global = true
+    )]
+    format: ResponseFormat,
+
+    /// Request timeout in seconds
+    #[arg(long, value_name = "SECONDS", default_value = "30", global = true)]
+    timeout: u64,
+
+    /// Cache responses in this directory, serving stale data if the network is down
+    #[arg(long, value_name = "DIR", global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// Seconds a cached response is used before checking for a newer one
+    #[arg(long, value_name = "SECONDS", default_value = "300", global = true)]
+    max_age: u64,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SortKey {
+    /// Worst flight category first
+    Category,
+    /// Warmest first
+    Temperature,
+    Station,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Fetch the terminal aerodrome forecast (TAF)
+    Taf {
+        /// Airport identifiers, ICAO or IATA/FAA (e.g., KJFK, EGLL, SFO)
+        #[arg(value_name = "ICAO", required = true)]
+        icao: Vec<String>,
+
+        /// Only show forecast groups in effect during the next N hours
+        #[arg(long, value_name = "N")]
+        hours: Option<u32>,
+    },
+}
+
+fn print_station_header(icao: &str, index: usize, total: usize) {
+    if total > 1 {
+        if index > 0 {
+            println!();
+        }
+        println!("=== {} ===", icao.to_uppercase());
+    }
+}
+
+fn print_category_list(metars: &[&MetarData], options: &DisplayOptions) {
+    for metar in metars {
+        // Pad by the bare code's width so colored output lines up too
+        let category = match metar.flight_category() {
+            Some(c) => format!(
+                "{}{}",
+                c.display_code(options.color),
+                " ".repeat(4 - c.code().len())
+            ),
+            None => "    ".to_string(),
+        };
+        let ceiling = match metar.ceiling() {
+            Some(ft) => options.units.format_altitude(ft as f64),
+            None => "none".to_string(),
+        };
+        let visibility = match metar.visibility() {
+            Some(vis) => options.units.format_visibility(&vis),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<6} {}  ceiling {:<9} visibility {:<8} {}",
+            metar.icao_id,
+            category,
+            ceiling,
+            visibility,
+            metar.station_name().unwrap_or_default()
+        );
+    }
+}
+
+/// One line per station with its category, temperature, wind, visibility and ceiling
+fn print_summary(metars: &[&MetarData], options: &DisplayOptions) {
+    println!(
+        "{:<6} {:<4} {:>7} {:<27} {:<15} {:<9} NAME",
+        "ICAO", "CAT", "TEMP", "WIND", "VIS", "CEILING"
+    );
+    for metar in metars {
+        let category = match metar.flight_category() {
+            Some(c) => format!(
+                "{}{}",
+                c.display_code(options.color),
+                " ".repeat(4 - c.code().len())
+            ),
+            None => "    ".to_string(),
+        };
+        let temperature = metar
+            .temperature()
+            .map(|t| options.units.format_temperature(t))
+            .unwrap_or_else(|| "-".to_string());
+        let wind = metar
+            .wind()
+            .map(|w| options.units.format_wind(&w))
+            .unwrap_or_else(|| "-".to_string());
+        let visibility = metar
+            .visibility()
+            .map(|v| options.units.format_visibility(&v))
+            .unwrap_or_else(|| "-".to_string());
+        let ceiling = match metar.ceiling() {
+            Some(ft) => options.units.format_altitude(ft as f64),
+            None => "none".to_string(),
+        };
+        println!(
+            "{:<6} {} {:>7} {:<27} {:<15} {:<9} {}",
+            metar.icao_id,
+            category,
+            temperature,
+            wind,
+            visibility,
+            ceiling,
+            metar.station_name().unwrap_or_default()
+        );
+    }
+}
+
+/// The area given with --bbox, --state or --radius, if any
+fn area_query(args: &Args) -> Option<weather::Result<MetarQuery>> {
+    if let Some(bbox) = args.bbox {
+        return Some(Ok(MetarQuery::bounding_box(bbox)));
+    }
+    if let Some(ref state) = args.state {
+        return Some(Ok(state.clone()));
+    }
+    let radius = args.radius?;
+    Some(match args.near.or(args.grid) {
+        Some(center) => Ok(MetarQuery::radius(center, radius)),
+        None => MetarQuery::around_station(&args.icao[0], radius),
+    })
+}
+
+/// The bulk file given with --bulk or --bulk-file, downloaded or read
+fn load_bulk(client: &WeatherClient, args: &Args) -> Option<weather::Result<BulkMetars>> {
+    let source = args
+        .bulk_file
+        .as_deref()
+        .or(args.bulk.then_some(METARS_CSV_URL))?;
+    if source.starts_with("http://") || source.starts_with("https://") {
+        Some(client.fetch_bulk_metars(source))
+    } else {
+        Some(BulkMetars::read(source))
+    }
+}
+
+/// Fetch and tabulate every station in an area
+fn display_area(
+    client: &WeatherClient,
+    bulk: Option<&BulkMetars>,
+    query: &MetarQuery,
+    args: &Args,
+    options: &DisplayOptions,
+) -> Result<(), WeatherError> {
+    if args.output.is_none() {
+        println!("Fetching weather for {}...\n", query);
+    }
+    let reports = match bulk {
+        Some(bulk) => bulk.area(query),
+        None => client.fetch_area(query)?,
+    };
+    let mut metars: Vec<&MetarData> = if args.category.is_empty() {
+        reports.iter().collect()
+    } else {
+        filter_by_flight_category(&reports, &args.category)
+    };
+    match args.sort {
+        SortKey::Category => {
+            metars.sort_by_key(|m| (std::cmp::Reverse(m.flight_category()), m.icao_id.clone()))
+        }
+        SortKey::Temperature => metars.sort_by(|a, b| {
+            // Unknown temperatures last
+            let t = |m: &MetarData| m.temperature().unwrap_or(f64::NEG_INFINITY);
+            t(b).total_cmp(&t(a))
+        }),
+        SortKey::Station => metars.sort_by(|a, b| a.icao_id.cmp(&b.icao_id)),
+    }
+
+    if let Some(format) = args.output {
+        return write_output(metars, format);
+    }
+    if metars.is_empty() {
+        println!("No stations reporting");
+        return Ok(());
+    }
+    print_summary(&metars, options);
+    let plural = if metars.len() == 1 { "" } else { "s" };
+    println!("\n{} station{}", metars.len(), plural);
+    Ok(())
+}
+
+/// Raw METAR text given with --raw, --metar-file or `-`, if any
+fn offline_input(args: &Args) -> Option<weather::Result<String>> {
+    if !args.raw.is_empty() {
+        return Some(Ok(args.raw.join("\n")));
+    }
+    let path = args
+        .metar_file
+        .as_deref()
+        .or_else(|| (args.icao == ["-"]).then_some("-"))?;
+    Some(read_metar_input(path))
+}
+
+fn print_filtered(
+    metars: Vec<&MetarData>,
+    categories: &[FlightCategory],
+    options: &DisplayOptions,
+) {
+    let matching = filter_by_flight_category(metars, categories);
+    if matching.is_empty() {
+        println!("No stations in the requested flight categories");
+    }
+    print_category_list(&matching, options);
+}
+
+/// Decode METARs supplied as text or XML, reporting those that fail; returns
+/// the reports and the number that failed
+fn decode_offline(text: &str) -> (Vec<MetarData>, usize) {
+    let mut failures = 0;
+    let mut metars = Vec::new();
+    if text.trim_start().starts_with('<') {
+        match parse_metar_document(text) {
+            Ok(reports) => metars = reports,
+            Err(e) => {
+                report_error(&e);
+                failures += 1;
+            }
+        }
+    } else {
+        for result in parse_metar_text(text) {
+            match result {
+                Ok(metar) => metars.push(metar),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    failures += 1;
+                }
+            }
+        }
+    }
+    (metars, failures)
+}
+
+fn display_offline(metars: &[MetarData], categories: &[FlightCategory], options: &DisplayOptions) {
+    if !categories.is_empty() {
+        print_filtered(metars.iter().collect(), categories, options);
+    } else {
+        for (i, metar) in metars.iter().enumerate() {
+            print_station_header(&metar.icao_id, i, metars.len());
+            display_weather(metar, options);
+        }
+    }
+}
+
+/// Write reports to standard output for --output
+fn write_output<'a>(
+    metars: impl IntoIterator<Item = &'a MetarData>,
+    format: OutputFormat,
+) -> Result<(), WeatherError> {
+    write_reports(metars, format, std::io::stdout().lock())
+}
+
+/// Write the reports in `categories`, or all of them if none are given
+fn write_filtered(
+    metars: &[MetarData],
+    categories: &[FlightCategory],
+    format: OutputFormat,
+) -> Result<(), WeatherError> {
+    if categories.is_empty() {
+        write_output(metars, format)
+    } else {
+        write_output(filter_by_flight_category(metars, categories), format)
+    }
+}
+
+/// List the reporting stations nearest `position`, then show the report
+/// from the closest one
+fn display_nearest(
+    client: &WeatherClient,
+    bulk: Option<&BulkMetars>,
+    position: &Coordinates,
+    count: usize,
+    output: Option<OutputFormat>,
+    options: &DisplayOptions,
+) -> Result<(), WeatherError> {
+    let nearby = match bulk {
+        Some(bulk) => bulk.nearby_weather(position, count),
+        None => client.fetch_nearby_weather(position, count)?,
+    };
+    let Some((closest, metar)) = nearby.first() else {
+        return Err(WeatherError::NoData(format!(
+            "any station near {}",
+            position
+        )));
+    };
+    if let Some(format) = output {
+        return write_output([metar], format);
+    }
+
+    println!("Stations nearest {}:", position);
+    for (station, _) in &nearby {
+        println!(
+            "  {:<5} {:>9} {:<3}  {}",
+            station.icao,
+            options.units.format_distance(station.distance_km),
+            compass_point(station.bearing),
+            station.name.as_deref().unwrap_or_default()
+        );
+    }
+    println!();
+
+    println!(
+        "Closest report: {}, {} {}\n",
+        closest.icao,
+        options.units.format_distance(closest.distance_km),
+        compass_point(closest.bearing)
+    );
+    display_weather(metar, options);
+    Ok(())
+}
+
+/// Report an error and fold its exit code into `exit_code`, keeping the most
+/// specific one seen so far
+fn fail(exit_code: &mut i32, error: &WeatherError) {
+    report_error(error);
+    *exit_code = (*exit_code).max(error.exit_code());
 }
 
 fn main() {
-    let args = Args::parse();
+    let mut args = Args::parse();
+    let options = DisplayOptions {
+        color: args.color,
+        units: args.units,
+    };
 
-    println!("Fetching weather for {}...\n", args.icao.to_uppercase());
+    let mut exit_code = 0;
+    if let Some(input) = offline_input(&args) {
+        match input {
+            Ok(text) => {
+                let (metars, failures) = decode_offline(&text);
+                if failures > 0 {
+                    exit_code = EXIT_INVALID_REPORT;
+                }
+                match args.output {
+                    Some(format) => {
+                        if let Err(e) = write_filtered(&metars, &args.category, format) {
+                            fail(&mut exit_code, &e);
+                        }
+                    }
+                    None => display_offline(&metars, &args.category, &options),
+                }
+            }
+            Err(e) => fail(&mut exit_code, &e),
+        }
+        std::process::exit(exit_code);
+    }
+    args.icao = args.icao.iter().map(|id| resolve_station_id(id)).collect();
+    if args.radius.is_some() && args.icao.len() > 1 {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--radius takes a single station to search around",
+            )
+            .exit();
+    }
 
-    match fetch_weather_data(&args.icao) {
-        Ok(metar) => display_weather(&metar),
+    let mut builder = WeatherClient::builder()
+        .timeout(Some(Duration::from_secs(args.timeout)))
+        .format(args.format)
+        .on_warning(|warning| eprintln!("Warning: {}", warning));
+    if let Some(ref url) = args.api_url {
+        builder = builder.base_url(url);
+    }
+    if let Some(ref dir) = args.cache_dir {
+        builder = builder.cache(ObservationCache::new(
+            dir,
+            Duration::from_secs(args.max_age),
+        ));
+    }
+    let client = match builder.build() {
+        Ok(client) => client,
+        Err(e) => {
+            report_error(&e);
+            std::process::exit(e.exit_code());
+        }
+    };
+
+    let bulk = match load_bulk(&client, &args).transpose() {
+        Ok(bulk) => bulk,
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            report_error(&e);
+            std::process::exit(e.exit_code());
+        }
+    };
+
+    if let Some(query) = area_query(&args) {
+        let shown =
+            query.and_then(|query| display_area(&client, bulk.as_ref(), &query, &args, &options));
+        if let Err(e) = shown {
+            fail(&mut exit_code, &e);
+        }
+        std::process::exit(exit_code);
+    }
+    if let Some(position) = args.near.or(args.grid) {
+        if let Err(e) = display_nearest(
+            &client,
+            bulk.as_ref(),
+            &position,
+            args.count.into(),
+            args.output,
+            &options,
+        ) {
+            fail(&mut exit_code, &e);
+        }
+        std::process::exit(exit_code);
+    }
+
+    match args.command {
+        Some(Command::Taf { icao, hours }) => {
+            let icao: Vec<String> = icao.iter().map(|id| resolve_station_id(id)).collect();
+            println!(
+                "Fetching forecast for {}...\n",
+                icao.join(", ").to_uppercase()
+            );
+            for (i, station) in icao.iter().enumerate() {
+                print_station_header(station, i, icao.len());
+                match client.fetch_taf(station) {
+                    Ok(taf) => display_taf(&taf, hours),
+                    Err(e) => fail(&mut exit_code, &e),
+                }
+            }
+        }
+        None if args.history.is_some() => {
+            let hours = args.history.unwrap_or_default();
+            if let Some(format) = args.output {
+                let mut reports = Vec::new();
+                for station in &args.icao {
+                    match client.fetch_weather_history(station, hours) {
+                        Ok(history) => reports.extend(history),
+                        Err(e) => fail(&mut exit_code, &e),
+                    }
+                }
+                if let Err(e) = write_output(&reports, format) {
+                    fail(&mut exit_code, &e);
+                }
+                std::process::exit(exit_code);
+            }
+            println!(
+                "Fetching {} hours of weather for {}...\n",
+                hours,
+                args.icao.join(", ").to_uppercase()
+            );
+            for (i, station) in args.icao.iter().enumerate() {
+                print_station_header(station, i, args.icao.len());
+                match client.fetch_weather_history(station, hours) {
+                    Ok(history) => display_history(&history, &options),
+                    Err(e) => fail(&mut exit_code, &e),
+                }
+            }
+        }
+        None => {
+            if args.output.is_none() {
+                println!(
+                    "Fetching weather for {}...\n",
+                    args.icao.join(", ").to_uppercase()
+                );
+            }
+            let ids: Vec<&str> = args.icao.iter().map(String::as_str).collect();
+            let results = match bulk {
+                Some(ref bulk) => Ok(bulk.weather_batch(&ids)),
+                None => client.fetch_weather_batch(&ids),
+            };
+            let mut results = match results {
+                Ok(results) => results,
+                Err(e) => {
+                    report_error(&e);
+                    std::process::exit(e.exit_code());
+                }
+            };
+
+            if let Some(format) = args.output {
+                let mut metars = Vec::new();
+                for station in &args.icao {
+                    match results.remove(&station.to_uppercase()) {
+                        Some(Ok(metar)) => metars.push(metar),
+                        Some(Err(e)) => fail(&mut exit_code, &e),
+                        None => {}
+                    }
+                }
+                if let Err(e) = write_filtered(&metars, &args.category, format) {
+                    fail(&mut exit_code, &e);
+                }
+                std::process::exit(exit_code);
+            }
+
+            if !args.category.is_empty() {
+                let mut metars = Vec::new();
+                for (station, result) in &results {
+                    match result {
+                        Ok(metar) => metars.push(metar),
+                        Err(e) => {
+                            eprintln!("Warning: {}: {}", station, e);
+                            exit_code = exit_code.max(e.exit_code());
+                        }
+                    }
+                }
+                print_filtered(metars, &args.category, &options);
+                std::process::exit(exit_code);
+            }
+
+            for (i, station) in args.icao.iter().enumerate() {
+                print_station_header(station, i, args.icao.len());
+                match results.remove(&station.to_uppercase()) {
+                    Some(Ok(metar)) => display_weather(&metar, &options),
+                    Some(Err(e)) => fail(&mut exit_code, &e),
+                    // Listed twice on the command line; already shown
+                    None => println!("(see above)"),
+                }
+            }
         }
     }
+
+    std::process::exit(exit_code);
 }
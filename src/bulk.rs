@@ -0,0 +1,302 @@
+//! The bulk METAR cache files published by aviationweather.gov, which hold
+//! the latest report from every station and are the recommended way to get
+//! weather for large station sets.
+//!
+//! Both the CSV and the XML files are supported, gzipped or not.
+
+use crate::client::nearest_or_no_data;
+use crate::location::Coordinates;
+use crate::query::MetarQuery;
+use crate::stations::{NearbyStation, find_station, nearest_reports};
+use crate::xml::{Record, xml_records};
+use crate::{MetarData, Result, WeatherError};
+use flate2::read::MultiGzDecoder;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+
+pub const METARS_CSV_URL: &str = "https://aviationweather.gov/data/cache/metars.cache.csv.gz";
+pub const METARS_XML_URL: &str = "https://aviationweather.gov/data/cache/metars.cache.xml.gz";
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The latest report from each station in a bulk METAR file, for querying
+/// locally instead of through the API
+#[derive(Debug, Clone, Default)]
+pub struct BulkMetars {
+    reports: BTreeMap<String, MetarData>,
+}
+
+impl BulkMetars {
+    /// Parse a bulk file, detecting gzip compression and CSV or XML
+    pub fn parse(data: &[u8]) -> Result<BulkMetars> {
+        let text = if data.starts_with(&GZIP_MAGIC) {
+            let mut text = String::new();
+            MultiGzDecoder::new(data)
+                .read_to_string(&mut text)
+                .map_err(|e| WeatherError::invalid_data("gzip", e))?;
+            text
+        } else {
+            String::from_utf8(data.to_vec()).map_err(|e| WeatherError::invalid_data("bulk", e))?
+        };
+
+        let records = if text.trim_start().starts_with('<') {
+            xml_records(&text, "METAR")?
+        } else {
+            csv_records(&text)?
+        };
+        let mut bulk = BulkMetars::default();
+        for metar in records.into_iter().filter_map(Record::into_metar) {
+            bulk.insert(metar);
+        }
+        Ok(bulk)
+    }
+
+    /// Read a bulk file downloaded earlier
+    pub fn read(path: impl AsRef<Path>) -> Result<BulkMetars> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .map_err(|e| WeatherError::io(format!("Failed to read {}", path.display()), e))?;
+        BulkMetars::parse(&data)
+    }
+
+    /// Keep `metar` unless there is a newer report from the same station
+    fn insert(&mut self, metar: MetarData) {
+        let icao = metar.icao_id.to_uppercase();
+        match self.reports.get(&icao) {
+            Some(existing) if existing.obs_time >= metar.obs_time => {}
+            _ => {
+                self.reports.insert(icao, metar);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    /// Every report, ordered by station
+    pub fn iter(&self) -> impl Iterator<Item = &MetarData> {
+        self.reports.values()
+    }
+
+    /// The report for a station, like [`crate::fetch_weather_data`]
+    pub fn weather_data(&self, icao: &str) -> Result<MetarData> {
+        let icao = icao.trim().to_uppercase();
+        self.reports
+            .get(&icao)
+            .cloned()
+            .ok_or(WeatherError::NoData(icao))
+    }
+
+    /// Reports for several stations, like [`crate::fetch_weather_batch`]
+    pub fn weather_batch(&self, icaos: &[&str]) -> BTreeMap<String, Result<MetarData>> {
+        icaos
+            .iter()
+            .map(|icao| (icao.trim().to_uppercase(), self.weather_data(icao)))
+            .collect()
+    }
+
+    /// Reports from the `count` stations nearest to `position`, nearest
+    /// first, like [`WeatherClient::fetch_nearby_weather`](crate::WeatherClient::fetch_nearby_weather)
+    pub fn nearby_weather(
+        &self,
+        position: &Coordinates,
+        count: usize,
+    ) -> Vec<(NearbyStation, MetarData)> {
+        nearest_reports(position, self.iter(), count)
+    }
+
+    /// The report from the station nearest to `position`, like
+    /// [`crate::fetch_nearest_weather`]
+    pub fn nearest_weather(&self, position: &Coordinates) -> Result<(NearbyStation, MetarData)> {
+        nearest_or_no_data(position, self.nearby_weather(position, 1))
+    }
+
+    /// Reports from every station in an area, like [`crate::fetch_area`].
+    /// The bulk files don't say which state a station is in, so state
+    /// queries only find stations in the built-in database.
+    pub fn area(&self, query: &MetarQuery) -> Vec<MetarData> {
+        self.iter()
+            .filter(|metar| match query {
+                MetarQuery::State(code) => find_station(&metar.icao_id)
+                    .is_some_and(|station| station.region == Some(code.as_str())),
+                _ => metar.lat.is_some() && query.includes(metar),
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Rows of the CSV file, which has a few lines of status before its header
+fn csv_records(text: &str) -> Result<Vec<Record>> {
+    let start = text
+        .find("raw_text,")
+        .ok_or_else(|| WeatherError::invalid_data("CSV", "no header row"))?;
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(&text.as_bytes()[start..]);
+    let headers = reader
+        .headers()
+        .map_err(|e| WeatherError::invalid_data("CSV", e))?
+        .clone();
+
+    let mut records = Vec::new();
+    for row in reader.records() {
+        let row = row.map_err(|e| WeatherError::invalid_data("CSV", e))?;
+        let mut record = Record::default();
+        for (name, value) in headers.iter().zip(row.iter()) {
+            match name {
+                "sky_cover" if !value.is_empty() => record.sky.push((value.to_string(), None)),
+                "cloud_base_ft_agl" => {
+                    if let Some(layer) = record.sky.last_mut() {
+                        layer.1 = layer.1.or(value.parse().ok());
+                    }
+                }
+                _ => {
+                    record.fields.insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    // Lines in the layout of metars.cache.csv, status lines included
+    const CSV: &str = "No errors
+No warnings
+12 ms
+data source=metars
+4 results
+raw_text,station_id,observation_time,latitude,longitude,temp_c,dewpoint_c,wind_dir_degrees,wind_speed_kt,wind_gust_kt,visibility_statute_mi,altim_in_hg,sea_level_pressure_mb,corrected,auto,auto_station,maintenance_indicator_on,no_signal,lightning_sensor_off,freezing_rain_sensor_off,present_weather_sensor_off,wx_string,sky_cover,cloud_base_ft_agl,sky_cover,cloud_base_ft_agl,sky_cover,cloud_base_ft_agl,sky_cover,cloud_base_ft_agl,flight_category,three_hr_pressure_tendency_mb,maxT_c,minT_c,maxT24hr_c,minT24hr_c,precip_in,pcp3hr_in,pcp6hr_in,pcp24hr_in,snow_in,vert_vis_ft,metar_type,elevation_m
+KSJC 171753Z 31010G18KT 10SM FEW030 BKN250 22/08 A3001 RMK AO2,KSJC,2026-10-17T17:53:00Z,37.362,-121.929,22.0,8.0,310,10,18,10+,30.01,1016.2,,,TRUE,,,,,,,FEW,3000,BKN,25000,,,,,VFR,,,,,,,,,,,,METAR,18
+KSFO 171756Z VRB03KT 3SM BR OVC008 15/13 A3000,KSFO,2026-10-17T17:56:00Z,37.619,-122.375,15.0,13.0,VRB,3,,3,30.0,,,,TRUE,,,,,,BR,OVC,800,,,,,,,IFR,,,,,,,,,,,,METAR,3
+KSJC 171653Z 30008KT 10SM FEW030 20/08 A3001,KSJC,2026-10-17T16:53:00Z,37.362,-121.929,20.0,8.0,300,8,,10+,30.01,,,,TRUE,,,,,,,FEW,3000,,,,,,,VFR,,,,,,,,,,,,METAR,18
+KOAK 171753Z 28008KT 10SM CLR 17/09 A3001,KOAK,2026-10-17T17:53:00Z,37.721,-122.221,17.0,9.0,280,8,,10+,30.01,,,,TRUE,,,,,,,CLR,,,,,,,,VFR,,,,,,,,,,,,METAR,3
+";
+
+    // Lines in the layout of metars.cache.xml
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<response version="1.3"><request_index>1</request_index><data_source name="metars" /><request type="retrieve" /><errors /><warnings /><time_taken_ms>5</time_taken_ms>
+<data num_results="2">
+<METAR><raw_text>KSJC 171753Z 31010G18KT 10SM FEW030 BKN250 22/08 A3001</raw_text><station_id>KSJC</station_id><observation_time>2026-10-17T17:53:00Z</observation_time><latitude>37.362</latitude><longitude>-121.929</longitude><temp_c>22</temp_c><dewpoint_c>8</dewpoint_c><wind_dir_degrees>310</wind_dir_degrees><wind_speed_kt>10</wind_speed_kt><wind_gust_kt>18</wind_gust_kt><visibility_statute_mi>10+</visibility_statute_mi><altim_in_hg>30.01</altim_in_hg><quality_control_flags><auto_station>TRUE</auto_station></quality_control_flags><sky_condition sky_cover="FEW" cloud_base_ft_agl="3000" /><sky_condition sky_cover="BKN" cloud_base_ft_agl="25000" /><flight_category>VFR</flight_category><metar_type>METAR</metar_type><elevation_m>18</elevation_m></METAR>
+<METAR><raw_text>KOAK 171753Z 28008KT 1/2SM FG VV002 12/12 A3000</raw_text><station_id>KOAK</station_id><observation_time>2026-10-17T17:53:00Z</observation_time><latitude>37.72</latitude><longitude>-122.22</longitude><temp_c>12</temp_c><dewpoint_c>12</dewpoint_c><wind_dir_degrees>280</wind_dir_degrees><wind_speed_kt>8</wind_speed_kt><visibility_statute_mi>1/2</visibility_statute_mi><altim_in_hg>30.00</altim_in_hg><wx_string>FG</wx_string><sky_condition sky_cover="OVX" cloud_base_ft_agl="0" /><flight_category>LIFR</flight_category><vert_vis_ft>200</vert_vis_ft><metar_type>METAR</metar_type><elevation_m>3</elevation_m></METAR>
+</data>
+</response>
+"#;
+
+    fn gzip(text: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn clouds(metar: &MetarData) -> Vec<(&str, Option<u32>)> {
+        metar
+            .clouds
+            .iter()
+            .map(|layer| (layer.cover.as_str(), layer.base))
+            .collect()
+    }
+
+    #[test]
+    fn pairs_csv_sky_cover_with_cloud_bases() {
+        let records = csv_records(CSV).unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(
+            records[0].sky,
+            [
+                ("FEW".to_string(), Some(3000)),
+                ("BKN".to_string(), Some(25000))
+            ]
+        );
+        assert_eq!(records[1].sky, [("OVC".to_string(), Some(800))]);
+        assert_eq!(records[3].sky, [("CLR".to_string(), None)]);
+        assert_eq!(records[1].fields["wx_string"], "BR");
+
+        assert!(csv_records("station_id,temp_c\nKSJC,22\n").is_err());
+    }
+
+    #[test]
+    fn reads_plain_and_gzipped_csv() {
+        for data in [CSV.as_bytes().to_vec(), gzip(CSV)] {
+            let bulk = BulkMetars::parse(&data).unwrap();
+            assert_eq!(bulk.len(), 3);
+            let sfo = bulk.weather_data("ksfo").unwrap();
+            assert_eq!(sfo.raw_ob, "KSFO 171756Z VRB03KT 3SM BR OVC008 15/13 A3000");
+            assert_eq!(clouds(&sfo), [("OVC", Some(800))]);
+            assert_eq!(sfo.flt_cat.as_deref(), Some("IFR"));
+            assert_eq!((sfo.lat, sfo.lon), (Some(37.619), Some(-122.375)));
+        }
+    }
+
+    #[test]
+    fn reads_plain_and_gzipped_xml() {
+        for data in [XML.as_bytes().to_vec(), gzip(XML)] {
+            let bulk = BulkMetars::parse(&data).unwrap();
+            let icaos: Vec<&str> = bulk.iter().map(|metar| metar.icao_id.as_str()).collect();
+            assert_eq!(icaos, ["KOAK", "KSJC"]);
+            let oak = bulk.weather_data("KOAK").unwrap();
+            assert_eq!(clouds(&oak), [("OVX", Some(0))]);
+            assert_eq!(oak.vert_vis, Some(200));
+            assert_eq!(oak.wx_string.as_deref(), Some("FG"));
+        }
+    }
+
+    #[test]
+    fn rejects_corrupt_files() {
+        let mut truncated = gzip(CSV);
+        truncated.truncate(truncated.len() / 2);
+        assert!(BulkMetars::parse(&truncated).is_err());
+        assert!(BulkMetars::parse(&[0xff, 0xfe, 0x00]).is_err());
+    }
+
+    #[test]
+    fn keeps_the_newest_report_per_station() {
+        // The older KSJC report comes after the newer one in the file
+        let bulk = BulkMetars::parse(CSV.as_bytes()).unwrap();
+        let sjc = bulk.weather_data("KSJC").unwrap();
+        assert!(sjc.raw_ob.starts_with("KSJC 171753Z"));
+
+        let mut bulk = BulkMetars::default();
+        let report = |raw: &str, obs_time| MetarData {
+            icao_id: "KSJC".to_string(),
+            raw_ob: raw.to_string(),
+            obs_time: Some(obs_time),
+            ..Default::default()
+        };
+        bulk.insert(report("KSJC 171653Z", 1_792_256_380));
+        bulk.insert(report("KSJC 171753Z", 1_792_259_980));
+        bulk.insert(report("KSJC 171553Z", 1_792_252_780));
+        assert_eq!(bulk.len(), 1);
+        assert_eq!(bulk.weather_data("KSJC").unwrap().raw_ob, "KSJC 171753Z");
+    }
+
+    #[test]
+    fn reads_bulk_files_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metars.cache.xml.gz");
+        std::fs::write(&path, gzip(XML)).unwrap();
+        let bulk = BulkMetars::read(&path).unwrap();
+        assert_eq!(bulk.len(), 2);
+        assert!(matches!(
+            bulk.weather_data("KSFO"),
+            Err(WeatherError::NoData(icao)) if icao == "KSFO"
+        ));
+
+        let missing = BulkMetars::read(dir.path().join("missing.csv")).unwrap_err();
+        assert!(matches!(missing, WeatherError::Io { .. }));
+    }
+}
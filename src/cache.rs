@@ -0,0 +1,194 @@
+//! On-disk cache of API responses, keyed by product and station.
+
+use crate::{Result, WeatherError};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// A response body as stored on disk, with the validators needed to refresh it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CacheEntry {
+    /// When the body was last fetched or revalidated, in seconds since the Unix epoch
+    pub fetched_at: i64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+impl CacheEntry {
+    pub fn new(body: String, etag: Option<String>, last_modified: Option<String>) -> CacheEntry {
+        CacheEntry {
+            fetched_at: chrono::Utc::now().timestamp(),
+            etag,
+            last_modified,
+            body,
+        }
+    }
+
+    pub fn age(&self) -> Duration {
+        let seconds = chrono::Utc::now().timestamp() - self.fetched_at;
+        Duration::from_secs(seconds.max(0) as u64)
+    }
+
+    /// Human-readable age for warnings, e.g. "12 minutes"
+    pub fn describe_age(&self) -> String {
+        let minutes = self.age().as_secs() / 60;
+        match minutes {
+            0 => "less than a minute".to_string(),
+            1 => "1 minute".to_string(),
+            m if m < 120 => format!("{} minutes", m),
+            m => format!("{} hours", m / 60),
+        }
+    }
+}
+
+/// A directory of cached responses and how long they stay fresh
+#[derive(Debug, Clone)]
+pub struct ObservationCache {
+    dir: PathBuf,
+    max_age: Duration,
+}
+
+impl ObservationCache {
+    pub fn new(dir: impl Into<PathBuf>, max_age: Duration) -> ObservationCache {
+        ObservationCache {
+            dir: dir.into(),
+            max_age,
+        }
+    }
+
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    /// Entries younger than this are served without contacting the API
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let name: String = key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+
+    pub(crate) fn load(&self, key: &str) -> Option<CacheEntry> {
+        let text = std::fs::read_to_string(self.path(key)).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    pub(crate) fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        entry.age() <= self.max_age
+    }
+
+    /// Write an entry, replacing any previous one atomically
+    pub(crate) fn store(&self, key: &str, entry: &CacheEntry) -> Result<()> {
+        let path = self.path(key);
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| WeatherError::io(format!("Failed to create {}", self.dir.display()), e))?;
+        let text = serde_json::to_string(entry).map_err(|e| {
+            WeatherError::io("Failed to encode cache entry", std::io::Error::other(e))
+        })?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, text)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(|e| WeatherError::io(format!("Failed to write {}", path.display()), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str =
+        r#"[{"icaoId":"KSFO","rawOb":"KSFO 171756Z 29012KT 10SM FEW015 16/11 A3002"}]"#;
+
+    fn entry_aged(seconds: i64) -> CacheEntry {
+        CacheEntry {
+            fetched_at: chrono::Utc::now().timestamp() - seconds,
+            ..CacheEntry::new(BODY.to_string(), Some("\"abc\"".to_string()), None)
+        }
+    }
+
+    #[test]
+    fn stores_and_loads_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ObservationCache::new(dir.path().join("nested"), DEFAULT_MAX_AGE);
+        assert!(cache.load("metar-KSFO").is_none());
+
+        cache.store("metar-KSFO", &entry_aged(0)).unwrap();
+        let loaded = cache.load("metar-KSFO").unwrap();
+        assert_eq!(loaded.body, BODY);
+        assert_eq!(loaded.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(loaded.last_modified, None);
+    }
+
+    #[test]
+    fn replaces_entries_without_leaving_temporary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ObservationCache::new(dir.path(), DEFAULT_MAX_AGE);
+        cache.store("metar-KSFO", &entry_aged(600)).unwrap();
+        let newer = CacheEntry::new("[]".to_string(), None, None);
+        cache.store("metar-KSFO", &newer).unwrap();
+
+        assert_eq!(cache.load("metar-KSFO").unwrap().body, "[]");
+        let files: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|file| file.unwrap().file_name())
+            .collect();
+        assert_eq!(files, ["metar_KSFO.json"]);
+    }
+
+    #[test]
+    fn keeps_keys_inside_the_cache_directory() {
+        let cache = ObservationCache::new("/var/cache/weather", DEFAULT_MAX_AGE);
+        assert_eq!(
+            cache.path("metar-@CA-bbox37.0,-123.0"),
+            PathBuf::from("/var/cache/weather/metar__CA_bbox37_0__123_0.json")
+        );
+        assert_eq!(
+            cache.path("../../etc/passwd"),
+            PathBuf::from("/var/cache/weather/______etc_passwd.json")
+        );
+    }
+
+    #[test]
+    fn ignores_unreadable_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ObservationCache::new(dir.path(), DEFAULT_MAX_AGE);
+        std::fs::write(cache.path("metar-KSFO"), "{not json").unwrap();
+        assert!(cache.load("metar-KSFO").is_none());
+    }
+
+    #[test]
+    fn reports_entries_it_cannot_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let blocked = dir.path().join("file");
+        std::fs::write(&blocked, "").unwrap();
+        let cache = ObservationCache::new(&blocked, DEFAULT_MAX_AGE);
+        let error = cache.store("metar-KSFO", &entry_aged(0)).unwrap_err();
+        assert!(matches!(error, WeatherError::Io { .. }));
+    }
+
+    #[test]
+    fn serves_entries_up_to_the_maximum_age() {
+        let cache = ObservationCache::new("/unused", Duration::from_secs(300));
+        assert!(cache.is_fresh(&entry_aged(0)));
+        assert!(cache.is_fresh(&entry_aged(290)));
+        assert!(!cache.is_fresh(&entry_aged(310)));
+        // A clock that went backwards still counts as fresh
+        assert!(cache.is_fresh(&entry_aged(-60)));
+    }
+
+    #[test]
+    fn describes_entry_ages() {
+        assert_eq!(entry_aged(30).describe_age(), "less than a minute");
+        assert_eq!(entry_aged(90).describe_age(), "1 minute");
+        assert_eq!(entry_aged(45 * 60).describe_age(), "45 minutes");
+        assert_eq!(entry_aged(5 * 3600 + 60).describe_age(), "5 hours");
+    }
+}
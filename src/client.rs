@@ -0,0 +1,1139 @@
+//! HTTP client for the aviationweather.gov data API.
+
+use crate::bulk::BulkMetars;
+use crate::cache::{CacheEntry, ObservationCache};
+use crate::location::Coordinates;
+use crate::query::MetarQuery;
+use crate::stations::{NearbyStation, nearest_reports};
+use crate::{MetarData, Result, TafData, Warning, WeatherError};
+use crate::{iwxxm, xml};
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{
+    ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const DEFAULT_BASE_URL: &str = "https://aviationweather.gov/api/data";
+pub const DEFAULT_USER_AGENT: &str = concat!("aviation-weather-cli/", env!("CARGO_PKG_VERSION"));
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Radii searched for nearby stations, each tried until enough have reports
+pub(crate) const NEARBY_SEARCH_RADII_KM: [f64; 3] = [100.0, 300.0, 1000.0];
+
+/// Encoding requested from the API. Reports decode into the same types
+/// whichever is used, so one can be checked against another.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    Xml,
+    /// ICAO IWXXM; METARs only
+    Iwxxm,
+}
+
+impl ResponseFormat {
+    /// Value of the API's `format` parameter
+    pub fn param(&self) -> &str {
+        match self {
+            ResponseFormat::Json => "json",
+            ResponseFormat::Xml => "xml",
+            ResponseFormat::Iwxxm => "iwxxm",
+        }
+    }
+}
+
+/// Receives the [`Warning`]s a client works around
+#[derive(Clone)]
+struct WarningHandler(Arc<dyn Fn(&Warning) + Send + Sync>);
+
+impl fmt::Debug for WarningHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WarningHandler")
+    }
+}
+
+/// Builder for [`WeatherClient`]
+#[derive(Debug, Clone)]
+pub struct WeatherClientBuilder {
+    base_url: String,
+    user_agent: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    cache: Option<ObservationCache>,
+    format: ResponseFormat,
+    on_warning: Option<WarningHandler>,
+}
+
+impl Default for WeatherClientBuilder {
+    fn default() -> Self {
+        WeatherClientBuilder {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: Some(DEFAULT_TIMEOUT),
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            proxy: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            cache: None,
+            format: ResponseFormat::Json,
+            on_warning: None,
+        }
+    }
+}
+
+impl WeatherClientBuilder {
+    /// API root, e.g. a local mock or mirror of `https://aviationweather.gov/api/data`
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Total time allowed for each request; `None` waits indefinitely
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Send all requests through this proxy, e.g. `http://proxy.local:3128`
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Retries after the first attempt on rate limiting, server and network errors
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Delay before the first retry; doubled for each retry after that
+    pub fn initial_backoff(mut self, delay: Duration) -> Self {
+        self.initial_backoff = delay;
+        self
+    }
+
+    pub fn max_backoff(mut self, delay: Duration) -> Self {
+        self.max_backoff = delay;
+        self
+    }
+
+    /// Keep responses on disk and reuse them while fresh
+    pub fn cache(mut self, cache: ObservationCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Request reports as XML or IWXXM instead of JSON
+    pub fn format(mut self, format: ResponseFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Called with each retry, stale cache fallback and failed cache write.
+    /// Without a handler these happen silently.
+    pub fn on_warning(mut self, handler: impl Fn(&Warning) + Send + Sync + 'static) -> Self {
+        self.on_warning = Some(WarningHandler(Arc::new(handler)));
+        self
+    }
+
+    pub fn build(self) -> Result<WeatherClient> {
+        let mut builder = Client::builder().user_agent(&self.user_agent);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.reqwest_proxy()? {
+            builder = builder.proxy(proxy);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| WeatherError::http_client("Failed to build client", e))?;
+
+        Ok(WeatherClient {
+            client,
+            api: self.into_api(),
+        })
+    }
+
+    /// Build an [`AsyncWeatherClient`](crate::nonblocking::AsyncWeatherClient)
+    /// with the same settings
+    #[cfg(feature = "async")]
+    pub fn build_async(self) -> Result<crate::nonblocking::AsyncWeatherClient> {
+        let mut builder = reqwest::Client::builder().user_agent(&self.user_agent);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.reqwest_proxy()? {
+            builder = builder.proxy(proxy);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| WeatherError::http_client("Failed to build client", e))?;
+
+        Ok(crate::nonblocking::AsyncWeatherClient::from_parts(
+            client,
+            self.into_api(),
+        ))
+    }
+
+    fn reqwest_proxy(&self) -> Result<Option<reqwest::Proxy>> {
+        self.proxy
+            .as_ref()
+            .map(|proxy| {
+                reqwest::Proxy::all(proxy)
+                    .map_err(|e| WeatherError::http_client(format!("Invalid proxy {}", proxy), e))
+            })
+            .transpose()
+    }
+
+    fn into_api(self) -> Api {
+        Api {
+            base_url: self.base_url,
+            max_retries: self.max_retries,
+            initial_backoff: self.initial_backoff,
+            max_backoff: self.max_backoff,
+            cache: self.cache,
+            format: self.format,
+            on_warning: self.on_warning,
+        }
+    }
+}
+
+/// A reusable connection to the aviationweather.gov data API
+#[derive(Debug, Clone)]
+pub struct WeatherClient {
+    client: Client,
+    api: Api,
+}
+
+impl WeatherClient {
+    /// A client with the default endpoint, timeouts and retry policy
+    pub fn new() -> Result<WeatherClient> {
+        WeatherClient::builder().build()
+    }
+
+    pub fn builder() -> WeatherClientBuilder {
+        WeatherClientBuilder::default()
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.api.base_url()
+    }
+
+    /// GET `url`, retrying with exponential backoff on 429, 5xx and network errors.
+    /// With a cached entry, the request is made conditional on it having
+    /// changed, and `None` is returned if it hasn't.
+    fn send(&self, url: &str, cached: Option<&CacheEntry>) -> Result<Option<Response>> {
+        let mut attempt = 0;
+        loop {
+            let request = self.client.get(url).headers(conditional_headers(cached));
+            let error = match request.send() {
+                Ok(response) => match Outcome::of(url, response.status(), response.headers()) {
+                    Outcome::Body => return Ok(Some(response)),
+                    Outcome::NotModified => return Ok(None),
+                    Outcome::Retry(error) => error,
+                    Outcome::Fail(error) => return Err(error),
+                },
+                Err(e) if e.is_builder() => {
+                    return Err(WeatherError::http_client("Invalid request", e));
+                }
+                Err(e) => WeatherError::network(url, e),
+            };
+
+            let Some(delay) = self.api.retry_delay(attempt, &error) else {
+                return Err(error);
+            };
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+
+    fn get(&self, url: &str, cached: Option<&CacheEntry>) -> Result<Fetched> {
+        let Some(response) = self.send(url, cached)? else {
+            return Ok(Fetched::NotModified);
+        };
+        let (etag, last_modified) = validators(response.headers());
+        let text = response.text().map_err(|e| WeatherError::network(url, e))?;
+        Ok(Fetched::Body {
+            text,
+            etag,
+            last_modified,
+        })
+    }
+
+    fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .send(url, None)?
+            .ok_or_else(|| WeatherError::EmptyResponse(url.to_string()))?;
+        let bytes = response
+            .bytes()
+            .map_err(|e| WeatherError::network(url, e))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Fetch a response body, going through the cache if there is one. A fresh
+    /// entry is used as is; a stale one is revalidated, and served with a
+    /// [`Warning`] if the API cannot be reached or is failing.
+    fn fetch_text(&self, product: &str, ids: &str, params: &[(&str, String)]) -> Result<String> {
+        match self.api.lookup(product, ids, params) {
+            Lookup::Fresh(body) => Ok(body),
+            Lookup::Fetch { url, key, cached } => {
+                let fetched = self.get(&url, cached.as_ref());
+                self.api.complete(product, ids, key, fetched, cached)
+            }
+        }
+    }
+
+    fn fetch_reports<T: Report>(
+        &self,
+        product: &str,
+        icao: &str,
+        params: &[(&str, String)],
+    ) -> Result<Vec<T>> {
+        let response_text = self.fetch_text(product, &icao.to_uppercase(), params)?;
+        self.api.parse_reports(icao, &response_text)
+    }
+
+    /// Fetch the latest METAR for a station
+    pub fn fetch_weather_data(&self, icao: &str) -> Result<MetarData> {
+        first_or_no_data(icao, self.fetch_reports("metar", icao, &[])?)
+    }
+
+    /// Fetch the latest METAR for several stations in a single request.
+    ///
+    /// The result maps each requested station (uppercased) to its report, or to
+    /// `WeatherError::NoData` if the API returned nothing for it. With a cache,
+    /// only stations without a fresh entry are requested, and each report is
+    /// cached under its own station.
+    pub fn fetch_weather_batch(
+        &self,
+        icaos: &[&str],
+    ) -> Result<BTreeMap<String, Result<MetarData>>> {
+        let mut batch = self.api.batch(icaos);
+        if let Some(id) = batch.single_to_revalidate(&self.api) {
+            // A single station can be revalidated with a conditional request
+            let fetched = self.fetch_reports("metar", &id, &[]);
+            batch.complete_single(&id, fetched)?;
+        } else if !batch.to_fetch.is_empty() {
+            let url = self.api.url("metar", &batch.to_fetch.join(","), &[]);
+            let fetched = self.get(&url, None);
+            self.api.complete_batch(&mut batch, fetched)?;
+        }
+        Ok(batch.finish())
+    }
+
+    /// Fetch the latest METARs from the `count` reporting stations nearest
+    /// to `position`, nearest first, searching a wider area until enough
+    /// are found
+    pub fn fetch_nearby_weather(
+        &self,
+        position: &Coordinates,
+        count: usize,
+    ) -> Result<Vec<(NearbyStation, MetarData)>> {
+        let mut nearby = Vec::new();
+        for radius_km in NEARBY_SEARCH_RADII_KM {
+            let reports = self.fetch_area(&MetarQuery::radius(*position, radius_km))?;
+            nearby = nearest_reports(position, &reports, count);
+            if nearby.len() >= count {
+                break;
+            }
+        }
+        Ok(nearby)
+    }
+
+    /// Fetch the latest METAR from the reporting station nearest to `position`
+    pub fn fetch_nearest_weather(
+        &self,
+        position: &Coordinates,
+    ) -> Result<(NearbyStation, MetarData)> {
+        nearest_or_no_data(position, self.fetch_nearby_weather(position, 1)?)
+    }
+
+    /// Fetch the latest METAR from every station in an area
+    pub fn fetch_area(&self, query: &MetarQuery) -> Result<Vec<MetarData>> {
+        let (ids, params) = query.request();
+        self.api
+            .area_reports(query, &self.fetch_text("metar", &ids, &params)?)
+    }
+
+    /// Download a bulk METAR file, e.g. [`METARS_CSV_URL`](crate::bulk::METARS_CSV_URL),
+    /// to query locally
+    pub fn fetch_bulk_metars(&self, url: &str) -> Result<BulkMetars> {
+        BulkMetars::parse(&self.get_bytes(url)?)
+    }
+
+    /// Fetch every METAR issued for a station in the last `hours` hours, newest first
+    pub fn fetch_weather_history(&self, icao: &str, hours: u32) -> Result<Vec<MetarData>> {
+        let history = self.fetch_reports("metar", icao, &[("hours", hours.to_string())])?;
+        history_newest_first(icao, history)
+    }
+
+    /// Fetch the current terminal aerodrome forecast for a station
+    pub fn fetch_taf(&self, icao: &str) -> Result<TafData> {
+        first_or_no_data(icao, self.fetch_reports("taf", icao, &[])?)
+    }
+}
+
+/// Endpoint, retry policy and cache, shared by the blocking and async clients.
+/// Nothing here sends requests; the clients do that and hand results back.
+#[derive(Debug, Clone)]
+pub(crate) struct Api {
+    base_url: String,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    cache: Option<ObservationCache>,
+    format: ResponseFormat,
+    on_warning: Option<WarningHandler>,
+}
+
+/// Result of a (possibly conditional) GET
+pub(crate) enum Fetched {
+    Body {
+        text: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+    NotModified,
+}
+
+/// What to do with a response, decided from its status and headers
+pub(crate) enum Outcome {
+    Body,
+    NotModified,
+    Retry(WeatherError),
+    Fail(WeatherError),
+}
+
+impl Outcome {
+    pub fn of(url: &str, status: StatusCode, headers: &HeaderMap) -> Outcome {
+        if status == StatusCode::NOT_MODIFIED {
+            Outcome::NotModified
+        } else if status.is_success() {
+            Outcome::Body
+        } else if status == StatusCode::TOO_MANY_REQUESTS {
+            Outcome::Retry(WeatherError::RateLimited {
+                url: url.to_string(),
+                retry_after: retry_after(headers),
+            })
+        } else {
+            let error = WeatherError::Status {
+                status,
+                url: url.to_string(),
+            };
+            if status.is_server_error() {
+                Outcome::Retry(error)
+            } else {
+                Outcome::Fail(error)
+            }
+        }
+    }
+}
+
+/// Where a cache-aware fetch stands before going to the network
+pub(crate) enum Lookup {
+    Fresh(String),
+    Fetch {
+        url: String,
+        key: Option<String>,
+        cached: Option<CacheEntry>,
+    },
+}
+
+impl Api {
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn warn(&self, warning: Warning) {
+        if let Some(WarningHandler(handler)) = &self.on_warning {
+            handler(&warning);
+        }
+    }
+
+    /// Cache an entry, warning instead of failing since the cache is best effort
+    fn store(&self, key: &str, entry: &CacheEntry) {
+        if let Some(cache) = &self.cache
+            && let Err(e) = cache.store(key, entry)
+        {
+            self.warn(Warning::CacheWrite(e));
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+
+    /// Delay before retrying a failed attempt, with a [`Warning`], or `None` once
+    /// retries are exhausted
+    pub fn retry_delay(&self, attempt: u32, error: &WeatherError) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        let retry_after = match error {
+            WeatherError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        };
+        let delay = retry_after
+            .unwrap_or_else(|| self.backoff(attempt))
+            .min(self.max_backoff);
+        self.warn(Warning::Retrying {
+            error: error.clone(),
+            delay,
+            attempt: attempt + 1,
+            max_retries: self.max_retries,
+        });
+        Some(delay)
+    }
+
+    /// The request URL, with every value percent-encoded. Area queries
+    /// select stations with other parameters, leaving `ids` empty.
+    pub fn url(&self, product: &str, ids: &str, params: &[(&str, String)]) -> String {
+        let mut pairs = Vec::new();
+        if !ids.is_empty() {
+            pairs.push(("ids", ids));
+        }
+        pairs.push(("format", self.format.param()));
+        pairs.extend(params.iter().map(|(name, value)| (*name, value.as_str())));
+        let endpoint = format!("{}/{}", self.base_url, product);
+        match reqwest::Url::parse_with_params(&endpoint, &pairs) {
+            Ok(url) => url.into(),
+            // An invalid base URL is reported when the request is built
+            Err(_) => endpoint,
+        }
+    }
+
+    /// Serve a fresh cache entry, or say what to request and which stale
+    /// entry to revalidate
+    pub fn lookup(&self, product: &str, ids: &str, params: &[(&str, String)]) -> Lookup {
+        let url = self.url(product, ids, params);
+        let Some(ref cache) = self.cache else {
+            return Lookup::Fetch {
+                url,
+                key: None,
+                cached: None,
+            };
+        };
+
+        let key = self.cache_key(product, ids, params);
+        let cached = cache.load(&key);
+        if let Some(entry) = cached.as_ref().filter(|entry| cache.is_fresh(entry)) {
+            return Lookup::Fresh(entry.body.clone());
+        }
+        Lookup::Fetch {
+            url,
+            key: Some(key),
+            cached,
+        }
+    }
+
+    /// Finish a fetch started with [`Api::lookup`]: cache what came back, or
+    /// fall back to the stale entry if the API could not be reached
+    pub fn complete(
+        &self,
+        product: &str,
+        ids: &str,
+        key: Option<String>,
+        fetched: Result<Fetched>,
+        cached: Option<CacheEntry>,
+    ) -> Result<String> {
+        let store = |entry: &CacheEntry| {
+            if let Some(key) = &key {
+                self.store(key, entry);
+            }
+        };
+        match (fetched, cached) {
+            (
+                Ok(Fetched::Body {
+                    text,
+                    etag,
+                    last_modified,
+                }),
+                _,
+            ) => {
+                if !text.is_empty() {
+                    store(&CacheEntry::new(text.clone(), etag, last_modified));
+                }
+                Ok(text)
+            }
+            (Ok(Fetched::NotModified), Some(entry)) => {
+                let refreshed = CacheEntry::new(entry.body, entry.etag, entry.last_modified);
+                store(&refreshed);
+                Ok(refreshed.body)
+            }
+            (Ok(Fetched::NotModified), None) => Err(WeatherError::EmptyResponse(ids.to_string())),
+            // Only an outage justifies stale data; a refused request does not
+            (Err(e), Some(entry)) if e.is_transient() => {
+                self.warn(Warning::StaleCache {
+                    error: e,
+                    product: product.to_string(),
+                    ids: ids.to_string(),
+                    age: entry.describe_age(),
+                });
+                Ok(entry.body)
+            }
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    /// Start a batch METAR request, taking fresh reports from the cache
+    pub fn batch(&self, icaos: &[&str]) -> Batch {
+        let ids: Vec<String> = icaos.iter().map(|icao| icao.to_uppercase()).collect();
+
+        let mut results = BTreeMap::new();
+        let mut stale = BTreeMap::new();
+        let mut to_fetch = Vec::new();
+        for id in &ids {
+            if let Some(ref cache) = self.cache
+                && let Some(entry) = cache.load(&self.cache_key("metar", id, &[]))
+            {
+                if cache.is_fresh(&entry)
+                    && let Some(report) = self.first_report(&entry.body)
+                {
+                    results.insert(id.clone(), Ok(report));
+                    continue;
+                }
+                stale.insert(id.clone(), entry);
+            }
+            if !to_fetch.contains(id) {
+                to_fetch.push(id.clone());
+            }
+        }
+
+        Batch {
+            ids,
+            results,
+            stale,
+            to_fetch,
+        }
+    }
+
+    /// Record the response to a multi-station request, caching each report
+    /// under its own station
+    pub fn complete_batch(&self, batch: &mut Batch, fetched: Result<Fetched>) -> Result<()> {
+        match fetched {
+            Ok(Fetched::Body { text, .. }) => {
+                for (report, body) in self.split_reports(&text)? {
+                    let icao = report.icao_id.to_uppercase();
+                    // Keep the first (most recent) report per station
+                    if batch.results.contains_key(&icao) {
+                        continue;
+                    }
+                    if let Some(body) = body {
+                        let entry = CacheEntry::new(body, None, None);
+                        self.store(&self.cache_key("metar", &icao, &[]), &entry);
+                    }
+                    batch.results.insert(icao, Ok(report));
+                }
+                Ok(())
+            }
+            Ok(Fetched::NotModified) => Ok(()),
+            Err(e) if e.is_transient() && !batch.stale.is_empty() => {
+                for id in &batch.to_fetch {
+                    let report = batch.stale.get(id).and_then(|entry| {
+                        self.warn(Warning::StaleCache {
+                            error: e.clone(),
+                            product: "metar".to_string(),
+                            ids: id.clone(),
+                            age: entry.describe_age(),
+                        });
+                        self.first_report(&entry.body)
+                    });
+                    let result = report.ok_or_else(|| e.clone());
+                    batch.results.insert(id.clone(), result);
+                }
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decode a response in the requested format
+    pub fn parse_reports<T: Report>(&self, icao: &str, text: &str) -> Result<Vec<T>> {
+        if text.is_empty() {
+            return Err(WeatherError::EmptyResponse(icao.to_string()));
+        }
+        match self.format {
+            ResponseFormat::Json => {
+                serde_json::from_str(text).map_err(|e| WeatherError::invalid_json(text, e))
+            }
+            ResponseFormat::Xml => T::from_xml(text),
+            ResponseFormat::Iwxxm => T::from_iwxxm(text),
+        }
+    }
+
+    /// The METARs in a multi-station response, each with a body to cache it
+    /// under its own station. Only JSON responses can be split that way.
+    fn split_reports(&self, text: &str) -> Result<Vec<(MetarData, Option<String>)>> {
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.format != ResponseFormat::Json {
+            let reports: Vec<MetarData> = self.parse_reports("", text)?;
+            return Ok(reports.into_iter().map(|report| (report, None)).collect());
+        }
+        let values: Vec<serde_json::Value> =
+            serde_json::from_str(text).map_err(|e| WeatherError::invalid_json(text, e))?;
+        values
+            .into_iter()
+            .map(|value| {
+                let report = serde_json::from_value(value.clone())
+                    .map_err(|e| WeatherError::invalid_json(value.to_string(), e))?;
+                Ok((report, Some(format!("[{}]", value))))
+            })
+            .collect()
+    }
+
+    /// The latest reports in an area query's response
+    pub fn area_reports(&self, query: &MetarQuery, text: &str) -> Result<Vec<MetarData>> {
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(query.latest_reports(self.parse_reports("", text)?))
+    }
+
+    fn first_report(&self, body: &str) -> Option<MetarData> {
+        self.parse_reports("", body).ok()?.into_iter().next()
+    }
+
+    /// Cache entries hold responses as received, so the format is part of the key
+    fn cache_key(&self, product: &str, ids: &str, params: &[(&str, String)]) -> String {
+        let mut key = format!("{}-{}", product, ids);
+        for (name, value) in params {
+            key.push_str(&format!("-{}{}", name, value));
+        }
+        if self.format != ResponseFormat::Json {
+            key.push_str(&format!("-{}", self.format.param()));
+        }
+        key
+    }
+}
+
+/// A report type the API returns, in any [`ResponseFormat`]
+pub(crate) trait Report: DeserializeOwned {
+    fn from_xml(text: &str) -> Result<Vec<Self>>;
+    fn from_iwxxm(text: &str) -> Result<Vec<Self>>;
+}
+
+impl Report for MetarData {
+    fn from_xml(text: &str) -> Result<Vec<Self>> {
+        xml::parse_metars(text)
+    }
+
+    fn from_iwxxm(text: &str) -> Result<Vec<Self>> {
+        iwxxm::parse_metars(text)
+    }
+}
+
+impl Report for TafData {
+    fn from_xml(text: &str) -> Result<Vec<Self>> {
+        xml::parse_tafs(text)
+    }
+
+    fn from_iwxxm(_text: &str) -> Result<Vec<Self>> {
+        Err(WeatherError::invalid_data(
+            "IWXXM",
+            "TAFs are not supported; request them as JSON or XML",
+        ))
+    }
+}
+
+/// A batch METAR request in progress
+pub(crate) struct Batch {
+    ids: Vec<String>,
+    results: BTreeMap<String, Result<MetarData>>,
+    stale: BTreeMap<String, CacheEntry>,
+    /// Stations without a fresh cache entry
+    pub to_fetch: Vec<String>,
+}
+
+impl Batch {
+    /// The one station left to fetch, if it can be revalidated with a
+    /// conditional request through the cache
+    pub fn single_to_revalidate(&self, api: &Api) -> Option<String> {
+        match self.to_fetch.as_slice() {
+            [id] if api.cache.is_some() => Some(id.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn complete_single(&mut self, id: &str, fetched: Result<Vec<MetarData>>) -> Result<()> {
+        match fetched {
+            Ok(reports) => {
+                if let Some(report) = reports.into_iter().next() {
+                    self.results.insert(id.to_string(), Ok(report));
+                }
+                Ok(())
+            }
+            Err(WeatherError::EmptyResponse(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn finish(mut self) -> BTreeMap<String, Result<MetarData>> {
+        for id in self.ids {
+            self.results
+                .entry(id.clone())
+                .or_insert_with(|| Err(WeatherError::NoData(id)));
+        }
+        self.results
+    }
+}
+
+/// The nearest of `nearby`, or `NoData` if no station near `position` has a report
+pub(crate) fn nearest_or_no_data(
+    position: &Coordinates,
+    nearby: Vec<(NearbyStation, MetarData)>,
+) -> Result<(NearbyStation, MetarData)> {
+    nearby
+        .into_iter()
+        .next()
+        .ok_or_else(|| WeatherError::NoData(format!("any station near {}", position)))
+}
+
+pub(crate) fn first_or_no_data<T>(icao: &str, reports: Vec<T>) -> Result<T> {
+    reports
+        .into_iter()
+        .next()
+        .ok_or_else(|| WeatherError::NoData(icao.to_uppercase()))
+}
+
+pub(crate) fn history_newest_first(
+    icao: &str,
+    mut history: Vec<MetarData>,
+) -> Result<Vec<MetarData>> {
+    if history.is_empty() {
+        return Err(WeatherError::NoData(icao.to_uppercase()));
+    }
+    history.sort_by_key(|metar| std::cmp::Reverse(metar.obs_time));
+    Ok(history)
+}
+
+/// The `ETag` and `Last-Modified` of a response, to cache it with
+pub(crate) fn validators(headers: &HeaderMap) -> (Option<String>, Option<String>) {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|v: &HeaderValue| v.to_str().ok())
+            .map(str::to_string)
+    };
+    (header(ETAG), header(LAST_MODIFIED))
+}
+
+/// `If-None-Match` and `If-Modified-Since` headers for revalidating an entry
+pub(crate) fn conditional_headers(cached: Option<&CacheEntry>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let Some(entry) = cached else {
+        return headers;
+    };
+    let validators = [
+        (IF_NONE_MATCH, &entry.etag),
+        (IF_MODIFIED_SINCE, &entry.last_modified),
+    ];
+    for (name, value) in validators {
+        if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+/// Delay requested by a `Retry-After: <seconds>` header
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn api(builder: WeatherClientBuilder) -> Api {
+        builder.base_url("https://example.test/api/").into_api()
+    }
+
+    #[test]
+    fn percent_encodes_request_urls() {
+        let api = api(WeatherClient::builder());
+        assert_eq!(
+            api.url("metar", "KSFO,KOAK", &[("hours", "2".to_string())]),
+            "https://example.test/api/metar?ids=KSFO%2CKOAK&format=json&hours=2"
+        );
+        assert_eq!(
+            api.url("metar", "@CA", &[]),
+            "https://example.test/api/metar?ids=%40CA&format=json"
+        );
+        // Nothing in a value can end it or add parameters
+        assert_eq!(
+            api.url("taf", "KSFO&format=xml #1", &[]),
+            "https://example.test/api/taf?ids=KSFO%26format%3Dxml+%231&format=json"
+        );
+        assert_eq!(
+            api.url(
+                "metar",
+                "",
+                &[("bbox", "37.0000,-123.0000,38.0000,-121.0000".to_string())]
+            ),
+            "https://example.test/api/metar?format=json&bbox=37.0000%2C-123.0000%2C38.0000%2C-121.0000"
+        );
+    }
+
+    #[test]
+    fn doubles_the_backoff_up_to_the_maximum() {
+        let api = api(WeatherClient::builder()
+            .initial_backoff(Duration::from_millis(500))
+            .max_backoff(Duration::from_secs(5)));
+        let delays: Vec<Duration> = (0..6).map(|attempt| api.backoff(attempt)).collect();
+        assert_eq!(
+            delays,
+            [500, 1000, 2000, 4000, 5000, 5000].map(Duration::from_millis)
+        );
+        assert_eq!(api.backoff(40), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn stops_retrying_after_max_retries_with_a_warning_for_each() {
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&warnings);
+        let api = api(WeatherClient::builder()
+            .max_retries(2)
+            .initial_backoff(Duration::from_secs(1))
+            .on_warning(move |warning| seen.lock().unwrap().push(warning.to_string())));
+        let error = WeatherError::Status {
+            status: StatusCode::BAD_GATEWAY,
+            url: "https://example.test/api/metar".to_string(),
+        };
+        assert_eq!(api.retry_delay(0, &error), Some(Duration::from_secs(1)));
+        assert_eq!(api.retry_delay(1, &error), Some(Duration::from_secs(2)));
+        assert_eq!(api.retry_delay(2, &error), None);
+        assert_eq!(warnings.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn waits_as_long_as_a_rate_limit_asks_up_to_the_maximum() {
+        let api = api(WeatherClient::builder().max_backoff(Duration::from_secs(10)));
+        let limited = |seconds: Option<u64>| WeatherError::RateLimited {
+            url: "https://example.test/api/metar".to_string(),
+            retry_after: seconds.map(Duration::from_secs),
+        };
+        assert_eq!(
+            api.retry_delay(0, &limited(Some(7))),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            api.retry_delay(0, &limited(Some(120))),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            api.retry_delay(1, &limited(None)),
+            Some(DEFAULT_INITIAL_BACKOFF * 2)
+        );
+    }
+
+    #[test]
+    fn decides_what_to_do_from_the_status() {
+        let url = "https://example.test/api/metar";
+        let outcome = |status: u16, headers: &[(&'static str, &'static str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in headers {
+                map.insert(
+                    reqwest::header::HeaderName::from_static(name),
+                    HeaderValue::from_static(value),
+                );
+            }
+            Outcome::of(url, StatusCode::from_u16(status).unwrap(), &map)
+        };
+        assert!(matches!(outcome(200, &[]), Outcome::Body));
+        assert!(matches!(outcome(304, &[]), Outcome::NotModified));
+        assert!(matches!(
+            outcome(429, &[("retry-after", " 7 ")]),
+            Outcome::Retry(WeatherError::RateLimited { retry_after: Some(delay), .. })
+                if delay == Duration::from_secs(7)
+        ));
+        assert!(matches!(
+            outcome(429, &[("retry-after", "Wed, 21 Oct 2026 07:28:00 GMT")]),
+            Outcome::Retry(WeatherError::RateLimited {
+                retry_after: None,
+                ..
+            })
+        ));
+        assert!(matches!(
+            outcome(503, &[]),
+            Outcome::Retry(WeatherError::Status { status, .. }) if status == StatusCode::SERVICE_UNAVAILABLE
+        ));
+        assert!(matches!(
+            outcome(404, &[]),
+            Outcome::Fail(WeatherError::Status { status, .. }) if status == StatusCode::NOT_FOUND
+        ));
+        assert!(matches!(outcome(400, &[]), Outcome::Fail(_)));
+    }
+
+    const BODY: &str =
+        r#"[{"icaoId":"KSFO","rawOb":"KSFO 171756Z 29012KT 10SM FEW015 16/11 A3002"}]"#;
+
+    /// An API with a cache in a new directory, and the warnings it gives
+    fn cached_api() -> (Api, tempfile::TempDir, Arc<Mutex<Vec<String>>>) {
+        let dir = tempfile::tempdir().unwrap();
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&warnings);
+        let api = api(WeatherClient::builder()
+            .cache(ObservationCache::new(dir.path(), Duration::from_secs(300)))
+            .on_warning(move |warning| seen.lock().unwrap().push(warning.to_string())));
+        (api, dir, warnings)
+    }
+
+    fn stale_entry() -> CacheEntry {
+        CacheEntry {
+            fetched_at: chrono::Utc::now().timestamp() - 3600,
+            ..CacheEntry::new(BODY.to_string(), Some("\"v1\"".to_string()), None)
+        }
+    }
+
+    fn body(text: &str) -> Result<Fetched> {
+        Ok(Fetched::Body {
+            text: text.to_string(),
+            etag: Some("\"v2\"".to_string()),
+            last_modified: None,
+        })
+    }
+
+    fn server_error() -> WeatherError {
+        WeatherError::Status {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            url: "https://example.test/api/metar".to_string(),
+        }
+    }
+
+    #[test]
+    fn serves_fresh_entries_and_revalidates_stale_ones() {
+        let (api, _dir, _) = cached_api();
+        let cache = api.cache.clone().unwrap();
+        assert!(matches!(
+            api.lookup("metar", "KSFO", &[]),
+            Lookup::Fetch {
+                key: Some(_),
+                cached: None,
+                ..
+            }
+        ));
+
+        cache.store("metar-KSFO", &stale_entry()).unwrap();
+        match api.lookup("metar", "KSFO", &[]) {
+            Lookup::Fetch { url, key, cached } => {
+                assert_eq!(url, "https://example.test/api/metar?ids=KSFO&format=json");
+                assert_eq!(key.as_deref(), Some("metar-KSFO"));
+                assert_eq!(cached.unwrap().etag.as_deref(), Some("\"v1\""));
+            }
+            Lookup::Fresh(_) => panic!("a stale entry was served as fresh"),
+        }
+
+        cache
+            .store("metar-KSFO", &CacheEntry::new(BODY.to_string(), None, None))
+            .unwrap();
+        assert!(matches!(api.lookup("metar", "KSFO", &[]), Lookup::Fresh(text) if text == BODY));
+    }
+
+    #[test]
+    fn caches_new_responses() {
+        let (api, _dir, _) = cached_api();
+        let key = Some("metar-KSFO".to_string());
+        let text = api.complete("metar", "KSFO", key, body(BODY), Some(stale_entry()));
+        assert_eq!(text.unwrap(), BODY);
+        let stored = api.cache.as_ref().unwrap().load("metar-KSFO").unwrap();
+        assert_eq!(stored.etag.as_deref(), Some("\"v2\""));
+        assert!(stored.age() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn refreshes_entries_that_have_not_changed() {
+        let (api, _dir, warnings) = cached_api();
+        let key = Some("metar-KSFO".to_string());
+        let text = api.complete(
+            "metar",
+            "KSFO",
+            key,
+            Ok(Fetched::NotModified),
+            Some(stale_entry()),
+        );
+        assert_eq!(text.unwrap(), BODY);
+        let stored = api.cache.as_ref().unwrap().load("metar-KSFO").unwrap();
+        assert_eq!(stored.etag.as_deref(), Some("\"v1\""));
+        assert!(api.cache.as_ref().unwrap().is_fresh(&stored));
+        assert!(warnings.lock().unwrap().is_empty());
+
+        let unexpected = api.complete("metar", "KSFO", None, Ok(Fetched::NotModified), None);
+        assert!(matches!(unexpected, Err(WeatherError::EmptyResponse(_))));
+    }
+
+    #[test]
+    fn falls_back_to_stale_entries_only_on_transient_errors() {
+        let (api, _dir, warnings) = cached_api();
+        let key = Some("metar-KSFO".to_string());
+        let text = api.complete(
+            "metar",
+            "KSFO",
+            key.clone(),
+            Err(server_error()),
+            Some(stale_entry()),
+        );
+        assert_eq!(text.unwrap(), BODY);
+        assert_eq!(warnings.lock().unwrap().len(), 1);
+
+        let refused = WeatherError::Status {
+            status: StatusCode::BAD_REQUEST,
+            url: "https://example.test/api/metar".to_string(),
+        };
+        let text = api.complete(
+            "metar",
+            "KSFO",
+            key.clone(),
+            Err(refused),
+            Some(stale_entry()),
+        );
+        assert!(
+            matches!(text, Err(WeatherError::Status { status, .. }) if status == StatusCode::BAD_REQUEST)
+        );
+
+        let text = api.complete("metar", "KSFO", key, Err(server_error()), None);
+        assert!(matches!(text, Err(WeatherError::Status { .. })));
+        assert_eq!(warnings.lock().unwrap().len(), 1);
+    }
+}
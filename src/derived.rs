@@ -0,0 +1,229 @@
+//! Quantities derived from a basic observation: humidity, comfort indices,
+//! pressure and density altitude, and cloud base estimates.
+//!
+//! Temperatures are in degrees Celsius, pressures in hectopascals and heights
+//! in feet, matching the rest of the crate.
+
+use crate::units::{FEET_PER_METER, MPH_PER_KNOT};
+use crate::{MetarData, celsius_to_fahrenheit};
+use serde::Serialize;
+
+const STANDARD_PRESSURE_HPA: f64 = 1013.25;
+/// Height gain per degree Celsius of dewpoint spread for a rising parcel
+const CLOUD_BASE_FT_PER_C: f64 = 400.0;
+
+fn fahrenheit_to_celsius(f: f64) -> f64 {
+    (f - 32.0) * 5.0 / 9.0
+}
+
+/// Relative humidity in percent, using the Magnus approximation
+pub fn relative_humidity(temp_c: f64, dewpoint_c: f64) -> f64 {
+    let saturation = |t: f64| (17.625 * t / (243.04 + t)).exp();
+    (100.0 * saturation(dewpoint_c) / saturation(temp_c)).clamp(0.0, 100.0)
+}
+
+pub fn dewpoint_spread(temp_c: f64, dewpoint_c: f64) -> f64 {
+    temp_c - dewpoint_c
+}
+
+/// NWS heat index, or `None` below 80°F where it is not meaningful
+pub fn heat_index(temp_c: f64, relative_humidity: f64) -> Option<f64> {
+    let t = celsius_to_fahrenheit(temp_c);
+    if t < 80.0 {
+        return None;
+    }
+    let rh = relative_humidity;
+
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < 80.0 {
+        return Some(fahrenheit_to_celsius(simple));
+    }
+
+    let mut hi = -42.379 + 2.049_015_23 * t + 10.143_331_27 * rh
+        - 0.224_755_41 * t * rh
+        - 0.006_837_83 * t * t
+        - 0.054_817_17 * rh * rh
+        + 0.001_228_74 * t * t * rh
+        + 0.000_852_82 * t * rh * rh
+        - 0.000_001_99 * t * t * rh * rh;
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
+        hi -= ((13.0 - rh) / 4.0) * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+        hi += ((rh - 85.0) / 10.0) * ((87.0 - t) / 5.0);
+    }
+    Some(fahrenheit_to_celsius(hi))
+}
+
+/// NWS wind chill, or `None` above 50°F or in winds of 3 mph or less
+pub fn wind_chill(temp_c: f64, wind_kt: f64) -> Option<f64> {
+    let t = celsius_to_fahrenheit(temp_c);
+    let v = wind_kt * MPH_PER_KNOT;
+    if t > 50.0 || v <= 3.0 {
+        return None;
+    }
+    let v16 = v.powf(0.16);
+    Some(fahrenheit_to_celsius(
+        35.74 + 0.6215 * t - 35.75 * v16 + 0.4275 * t * v16,
+    ))
+}
+
+/// Pressure altitude in feet from the field elevation and altimeter setting
+pub fn pressure_altitude(elevation_ft: f64, altimeter_hpa: f64) -> f64 {
+    elevation_ft + 145_366.45 * (1.0 - (altimeter_hpa / STANDARD_PRESSURE_HPA).powf(0.190_284))
+}
+
+/// Density altitude in feet, correcting pressure altitude for the deviation
+/// from the ISA temperature at that altitude
+pub fn density_altitude(pressure_altitude_ft: f64, temp_c: f64) -> f64 {
+    let isa_temp_c = 15.0 - 1.98 * pressure_altitude_ft / 1000.0;
+    pressure_altitude_ft + 118.8 * (temp_c - isa_temp_c)
+}
+
+/// Estimated base of convective cloud in feet above ground level
+pub fn cloud_base_estimate(temp_c: f64, dewpoint_c: f64) -> f64 {
+    dewpoint_spread(temp_c, dewpoint_c).max(0.0) * CLOUD_BASE_FT_PER_C
+}
+
+/// Pressure at the station's elevation, from the altimeter setting
+pub fn station_pressure(altimeter_hpa: f64, elevation_m: f64) -> f64 {
+    altimeter_hpa * ((288.0 - 0.0065 * elevation_m) / 288.0).powf(5.2561)
+}
+
+/// Everything in this module that can be computed for one report.
+/// Fields are `None` when the inputs are missing or the quantity does not apply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct DerivedQuantities {
+    /// Percent
+    pub relative_humidity: Option<f64>,
+    /// Degrees Celsius
+    pub dewpoint_spread: Option<f64>,
+    /// Degrees Celsius
+    pub heat_index: Option<f64>,
+    /// Degrees Celsius
+    pub wind_chill: Option<f64>,
+    /// Feet
+    pub pressure_altitude: Option<f64>,
+    /// Feet
+    pub density_altitude: Option<f64>,
+    /// Feet above ground level
+    pub cloud_base_estimate: Option<f64>,
+    /// Hectopascals
+    pub station_pressure: Option<f64>,
+}
+
+impl DerivedQuantities {
+    pub fn from_metar(metar: &MetarData) -> DerivedQuantities {
+        let temp = metar.temperature();
+        let dewpoint = metar.dewpoint();
+        let altimeter = metar.altimeter().map(|a| a.hpa());
+        let elevation_m = metar.elev;
+
+        let relative_humidity = temp.zip(dewpoint).map(|(t, d)| relative_humidity(t, d));
+        let pressure_altitude = elevation_m
+            .zip(altimeter)
+            .map(|(e, a)| pressure_altitude(e * FEET_PER_METER, a));
+
+        DerivedQuantities {
+            relative_humidity,
+            dewpoint_spread: temp.zip(dewpoint).map(|(t, d)| dewpoint_spread(t, d)),
+            heat_index: temp
+                .zip(relative_humidity)
+                .and_then(|(t, rh)| heat_index(t, rh)),
+            wind_chill: temp
+                .zip(metar.wind())
+                .and_then(|(t, w)| wind_chill(t, w.speed_knots())),
+            pressure_altitude,
+            density_altitude: pressure_altitude
+                .zip(temp)
+                .map(|(pa, t)| density_altitude(pa, t)),
+            cloud_base_estimate: temp.zip(dewpoint).map(|(t, d)| cloud_base_estimate(t, d)),
+            station_pressure: altimeter
+                .zip(elevation_m)
+                .map(|(a, e)| station_pressure(a, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    #[test]
+    fn relative_humidity_from_spread() {
+        assert_near(relative_humidity(20.0, 20.0), 100.0, 1e-9);
+        assert_near(relative_humidity(30.0, 10.0), 28.9, 0.1);
+        // Dewpoint reported above the temperature is clamped
+        assert_eq!(relative_humidity(10.0, 11.0), 100.0);
+    }
+
+    #[test]
+    fn heat_index_matches_nws_table() {
+        // 95°F at 50% is 105°F in the NWS table
+        assert_near(
+            celsius_to_fahrenheit(heat_index(35.0, 50.0).unwrap()),
+            105.0,
+            0.5,
+        );
+        assert_eq!(heat_index(25.0, 90.0), None);
+    }
+
+    #[test]
+    fn wind_chill_matches_nws_table() {
+        // 14°F in 23 mph (20 kt) is about -5°F
+        assert_near(
+            celsius_to_fahrenheit(wind_chill(-10.0, 20.0).unwrap()),
+            -4.7,
+            0.1,
+        );
+        assert_eq!(wind_chill(15.0, 20.0), None);
+        assert_eq!(wind_chill(-10.0, 2.0), None);
+    }
+
+    #[test]
+    fn pressure_and_density_altitude() {
+        assert_near(
+            pressure_altitude(5000.0, STANDARD_PRESSURE_HPA),
+            5000.0,
+            1e-6,
+        );
+        // About 27 feet per hectopascal near sea level
+        assert_near(pressure_altitude(0.0, 1003.25), 274.1, 0.1);
+        assert_near(density_altitude(0.0, 15.0), 0.0, 1e-9);
+        assert_near(density_altitude(5000.0, 30.0), 7958.1, 0.1);
+    }
+
+    #[test]
+    fn cloud_base_and_station_pressure() {
+        assert_eq!(cloud_base_estimate(20.0, 10.0), 4000.0);
+        assert_eq!(cloud_base_estimate(10.0, 11.0), 0.0);
+        assert_near(station_pressure(1013.25, 0.0), 1013.25, 1e-9);
+        assert_near(station_pressure(1013.25, 1609.0), 834.2, 0.1);
+    }
+
+    #[test]
+    fn derives_everything_available_from_a_report() {
+        let metar = MetarData {
+            elev: Some(1609.0),
+            ..MetarData::from_raw("KDEN 171753Z 36020KT 10SM SKC M10/M15 A2992").unwrap()
+        };
+        let derived = DerivedQuantities::from_metar(&metar);
+        assert_eq!(derived.dewpoint_spread, Some(5.0));
+        assert_eq!(derived.heat_index, None);
+        assert!(derived.wind_chill.unwrap() < -10.0);
+        assert!(derived.density_altitude.unwrap() < derived.pressure_altitude.unwrap());
+        assert_eq!(derived.cloud_base_estimate, Some(2000.0));
+
+        let missing = DerivedQuantities::from_metar(&MetarData::default());
+        assert_eq!(missing, DerivedQuantities::default());
+    }
+}
@@ -0,0 +1,552 @@
+//! Errors returned by the library, with hints for users and exit codes for
+//! the binaries.
+
+use crate::{MetarParseError, find_station, suggest_stations};
+use reqwest::StatusCode;
+use std::error::Error;
+use std::fmt;
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// General failure, e.g. a bad argument or unwritable output file
+pub const EXIT_FAILURE: i32 = 1;
+/// The station is unknown or has no current report. (2 is used by argument parsing.)
+pub const EXIT_NO_DATA: i32 = 3;
+/// The API could not be reached: timeout, DNS, TLS or connection failure
+pub const EXIT_NETWORK: i32 = 4;
+/// The API was reached but refused, rate limited or returned something unusable
+pub const EXIT_SERVICE: i32 = 5;
+/// Raw METAR input could not be decoded
+pub const EXIT_INVALID_REPORT: i32 = 6;
+
+/// Why a request never got an HTTP response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkErrorKind {
+    Timeout,
+    Dns,
+    Tls,
+    Connect,
+    Other,
+}
+
+impl NetworkErrorKind {
+    fn of(error: &reqwest::Error) -> NetworkErrorKind {
+        if error.is_timeout() {
+            return NetworkErrorKind::Timeout;
+        }
+        let causes = causes(error);
+        if causes.iter().any(|cause| cause.is::<rustls::Error>()) {
+            return NetworkErrorKind::Tls;
+        }
+        // The innermost I/O error is what actually failed
+        let io = causes
+            .iter()
+            .rev()
+            .find_map(|cause| cause.downcast_ref::<std::io::Error>());
+        match io.map(|io| (io.kind(), io.raw_os_error())) {
+            Some((ErrorKind::TimedOut, _)) => NetworkErrorKind::Timeout,
+            _ if !error.is_connect() => NetworkErrorKind::Other,
+            // Sockets fail with an OS error code; the system resolver's
+            // lookup failures have none
+            Some((_, Some(_))) => NetworkErrorKind::Connect,
+            // Connected, then closed during the handshake
+            Some((ErrorKind::UnexpectedEof, None))
+                if error.url().is_some_and(|url| url.scheme() == "https") =>
+            {
+                NetworkErrorKind::Tls
+            }
+            Some((ErrorKind::Other | ErrorKind::InvalidData | ErrorKind::UnexpectedEof, None)) => {
+                NetworkErrorKind::Connect
+            }
+            Some((_, None)) => NetworkErrorKind::Dns,
+            None => NetworkErrorKind::Connect,
+        }
+    }
+}
+
+/// `error` and everything under it. `io::Error::source` skips the error an
+/// I/O error wraps, e.g. a TLS error, so those are unwrapped explicitly.
+fn causes<'a>(error: &'a (dyn Error + 'static)) -> Vec<&'a (dyn Error + 'static)> {
+    let mut causes = Vec::new();
+    let mut next = Some(error);
+    while let Some(cause) = next {
+        causes.push(cause);
+        next = match cause.downcast_ref::<std::io::Error>() {
+            Some(io) => match io.get_ref() {
+                Some(inner) => Some(inner as &(dyn Error + 'static)),
+                None => io.source(),
+            },
+            None => cause.source(),
+        };
+    }
+    causes
+}
+
+impl fmt::Display for NetworkErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NetworkErrorKind::Timeout => "timed out",
+            NetworkErrorKind::Dns => "DNS lookup failed",
+            NetworkErrorKind::Tls => "TLS error",
+            NetworkErrorKind::Connect => "connection failed",
+            NetworkErrorKind::Other => "failed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Sources are kept behind `Arc` so that errors can be cloned, e.g. when one
+/// failed batch request is reported against every station in it.
+#[derive(Debug, Clone)]
+pub enum WeatherError {
+    /// The HTTP client could not be set up, e.g. because of an invalid proxy URL
+    HttpClient {
+        message: String,
+        source: Arc<reqwest::Error>,
+    },
+    /// The request got no HTTP response
+    Network {
+        kind: NetworkErrorKind,
+        url: String,
+        source: Arc<reqwest::Error>,
+    },
+    /// HTTP 429, with the delay the API asked for
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+    /// Any other unsuccessful HTTP status
+    Status {
+        status: StatusCode,
+        url: String,
+    },
+    /// The API returned nothing for this station
+    EmptyResponse(String),
+    /// The API response had no report for this station
+    NoData(String),
+    /// The station is not in the built-in database, which this operation needs
+    UnknownStation(String),
+    InvalidJson {
+        body: String,
+        source: Arc<serde_json::Error>,
+    },
+    /// Data in some other format, e.g. a bulk CSV file, could not be read
+    InvalidData {
+        format: &'static str,
+        message: String,
+    },
+    InvalidReport(MetarParseError),
+    /// An announcement template has a syntax error or failed to render
+    Template(Arc<minijinja::Error>),
+    Io {
+        context: String,
+        source: Arc<std::io::Error>,
+    },
+}
+
+impl WeatherError {
+    pub(crate) fn http_client(message: impl Into<String>, source: reqwest::Error) -> WeatherError {
+        WeatherError::HttpClient {
+            message: message.into(),
+            source: Arc::new(source),
+        }
+    }
+
+    pub(crate) fn network(url: &str, source: reqwest::Error) -> WeatherError {
+        WeatherError::Network {
+            kind: NetworkErrorKind::of(&source),
+            url: url.to_string(),
+            source: Arc::new(source),
+        }
+    }
+
+    pub(crate) fn invalid_json(body: impl Into<String>, source: serde_json::Error) -> WeatherError {
+        WeatherError::InvalidJson {
+            body: body.into(),
+            source: Arc::new(source),
+        }
+    }
+
+    pub(crate) fn invalid_data(format: &'static str, message: impl fmt::Display) -> WeatherError {
+        WeatherError::InvalidData {
+            format,
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn template(source: minijinja::Error) -> WeatherError {
+        WeatherError::Template(Arc::new(source))
+    }
+
+    pub(crate) fn io(context: impl Into<String>, source: std::io::Error) -> WeatherError {
+        WeatherError::Io {
+            context: context.into(),
+            source: Arc::new(source),
+        }
+    }
+
+    /// Whether retrying the same request later might succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            WeatherError::Network { .. } | WeatherError::RateLimited { .. } => true,
+            WeatherError::Status { status, .. } => status.is_server_error(),
+            _ => false,
+        }
+    }
+
+    /// Advice for the user on what to do about the error, if there is any
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            WeatherError::EmptyResponse(icao)
+            | WeatherError::NoData(icao)
+            | WeatherError::UnknownStation(icao) => unknown_station_hint(icao),
+            WeatherError::Network { kind, .. } => match kind {
+                NetworkErrorKind::Timeout => {
+                    "The API did not answer in time; try again or allow a longer timeout"
+                }
+                NetworkErrorKind::Dns => "Check your network connection and DNS settings",
+                NetworkErrorKind::Tls => {
+                    "Check the system clock, and any proxy that intercepts HTTPS traffic"
+                }
+                NetworkErrorKind::Connect | NetworkErrorKind::Other => {
+                    "Check your network connection and proxy settings"
+                }
+            }
+            .to_string(),
+            WeatherError::RateLimited {
+                retry_after: Some(delay),
+                ..
+            } => format!(
+                "The API is limiting requests; wait {}s before trying again",
+                delay.as_secs()
+            ),
+            WeatherError::RateLimited { .. } => {
+                "The API is limiting requests; wait a minute before trying again".to_string()
+            }
+            WeatherError::Status { status, .. } if status.is_server_error() => {
+                "aviationweather.gov may be having problems; try again later".to_string()
+            }
+            _ => return None,
+        };
+        Some(hint)
+    }
+
+    /// Process exit code for a binary that fails with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            WeatherError::EmptyResponse(_)
+            | WeatherError::NoData(_)
+            | WeatherError::UnknownStation(_) => EXIT_NO_DATA,
+            WeatherError::Network { .. } => EXIT_NETWORK,
+            WeatherError::RateLimited { .. }
+            | WeatherError::Status { .. }
+            | WeatherError::InvalidJson { .. }
+            | WeatherError::InvalidData { .. } => EXIT_SERVICE,
+            WeatherError::InvalidReport(_) => EXIT_INVALID_REPORT,
+            WeatherError::HttpClient { .. }
+            | WeatherError::Template(_)
+            | WeatherError::Io { .. } => EXIT_FAILURE,
+        }
+    }
+}
+
+/// Hint for a station the API has nothing for: what it is if we know it,
+/// otherwise the stations that might have been meant
+fn unknown_station_hint(icao: &str) -> String {
+    if let Some(station) = find_station(icao) {
+        return format!(
+            "{} may not have a current report of this kind; small airports often have no weather reporting or forecast",
+            station.display_name()
+        );
+    }
+    let suggestions = suggest_stations(icao, 3);
+    if suggestions.is_empty() {
+        let mut hint = "This may not be a valid identifier. Verify the airport code at https://aviationweather.gov".to_string();
+        // resolve_station_id turns unknown three-letter codes into US identifiers
+        if let Some(code) = icao.strip_prefix('K')
+            && code.len() == 3
+        {
+            hint.push_str(&format!(
+                "\n{} was looked up as a US airport; for an airport elsewhere, use its four-letter ICAO identifier",
+                code
+            ));
+        }
+        return hint;
+    }
+    let lines: Vec<String> = suggestions
+        .iter()
+        .map(|station| format!("  {}  {}", station.icao, station.display_name()))
+        .collect();
+    format!("Did you mean:\n{}", lines.join("\n"))
+}
+
+impl fmt::Display for WeatherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeatherError::HttpClient { message, .. } => {
+                write!(f, "HTTP client error: {}", message)
+            }
+            WeatherError::Network { kind, url, .. } => {
+                write!(f, "Request to {} {}", url, kind)
+            }
+            WeatherError::RateLimited { url, .. } => {
+                write!(f, "Request to {} was rate limited (HTTP 429)", url)
+            }
+            WeatherError::Status { status, url } => {
+                write!(f, "Request to {} failed: HTTP {}", url, status)
+            }
+            WeatherError::EmptyResponse(icao) => write!(f, "Empty response from API for {}", icao),
+            WeatherError::InvalidJson { body, .. } => {
+                write!(f, "Failed to parse JSON response: {}", body)
+            }
+            WeatherError::NoData(icao) => write!(f, "No weather data found for ICAO: {}", icao),
+            WeatherError::UnknownStation(id) => write!(f, "Unknown station: {}", id),
+            WeatherError::InvalidData { format, message } => {
+                write!(f, "Failed to parse {} data: {}", format, message)
+            }
+            WeatherError::InvalidReport(e) => write!(f, "Invalid METAR: {}", e),
+            WeatherError::Template(e) => write!(f, "Template error: {}", e),
+            WeatherError::Io { context, .. } => write!(f, "I/O error: {}", context),
+        }
+    }
+}
+
+impl Error for WeatherError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WeatherError::HttpClient { source, .. } | WeatherError::Network { source, .. } => {
+                Some(source.as_ref())
+            }
+            WeatherError::InvalidJson { source, .. } => Some(source.as_ref()),
+            WeatherError::InvalidReport(e) => Some(e),
+            WeatherError::Template(e) => Some(e.as_ref()),
+            WeatherError::Io { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, WeatherError>;
+
+/// A problem the client worked around, passed to the handler set with
+/// [`WeatherClientBuilder::on_warning`](crate::WeatherClientBuilder::on_warning)
+#[derive(Debug, Clone)]
+pub enum Warning {
+    /// A request failed and is about to be retried
+    Retrying {
+        error: WeatherError,
+        delay: Duration,
+        attempt: u32,
+        max_retries: u32,
+    },
+    /// The API could not be reached, so a stale cached response was used
+    StaleCache {
+        error: WeatherError,
+        product: String,
+        ids: String,
+        age: String,
+    },
+    /// A response could not be written to the cache
+    CacheWrite(WeatherError),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::Retrying {
+                error,
+                delay,
+                attempt,
+                max_retries,
+            } => write!(
+                f,
+                "{}; retrying in {:.1}s ({}/{})",
+                error,
+                delay.as_secs_f64(),
+                attempt,
+                max_retries
+            ),
+            Warning::StaleCache {
+                error,
+                product,
+                ids,
+                age,
+            } => write!(
+                f,
+                "{}; using cached {} data for {} from {} ago",
+                error,
+                product.to_uppercase(),
+                ids,
+                age
+            ),
+            Warning::CacheWrite(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Print an error and its chain of causes as `Error: ...: ...`, followed by
+/// any hint. Used by the binaries.
+pub fn report_error(error: &(dyn Error + 'static)) {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        // Many errors already include their cause in their own message
+        let text = cause.to_string();
+        if !message.contains(&text) {
+            message.push_str(&format!(": {}", text));
+        }
+        source = cause.source();
+    }
+    eprintln!("Error: {}", message);
+    if let Some(hint) = error
+        .downcast_ref::<WeatherError>()
+        .and_then(WeatherError::hint)
+    {
+        eprintln!("{}", hint);
+    }
+}
+
+/// Exit code for any error a binary fails with
+pub fn exit_code(error: &(dyn Error + 'static)) -> i32 {
+    error
+        .downcast_ref::<WeatherError>()
+        .map_or(EXIT_FAILURE, WeatherError::exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    /// The error from a GET of `url` that gives up after `timeout`
+    fn request_error(url: &str, timeout: Duration) -> reqwest::Error {
+        reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap()
+            .get(url)
+            .send()
+            .unwrap_err()
+    }
+
+    fn status(code: u16) -> WeatherError {
+        WeatherError::Status {
+            status: StatusCode::from_u16(code).unwrap(),
+            url: "https://aviationweather.gov/api/data/metar".to_string(),
+        }
+    }
+
+    #[test]
+    fn classifies_refused_connections() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let error = request_error(
+            &format!("http://127.0.0.1:{}/", port),
+            Duration::from_secs(5),
+        );
+        assert_eq!(NetworkErrorKind::of(&error), NetworkErrorKind::Connect);
+    }
+
+    #[test]
+    fn classifies_timeouts() {
+        // Connections are accepted by the OS but never answered
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let error = request_error(&url, Duration::from_millis(200));
+        assert_eq!(NetworkErrorKind::of(&error), NetworkErrorKind::Timeout);
+    }
+
+    #[test]
+    fn classifies_failed_tls_handshakes() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("https://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+        });
+        let error = request_error(&url, Duration::from_secs(5));
+        server.join().unwrap();
+        assert_eq!(NetworkErrorKind::of(&error), NetworkErrorKind::Tls);
+    }
+
+    #[test]
+    fn classifies_failed_lookups() {
+        // .invalid never resolves (RFC 6761)
+        let error = request_error("http://station.invalid/", Duration::from_secs(10));
+        assert_eq!(NetworkErrorKind::of(&error), NetworkErrorKind::Dns);
+    }
+
+    #[test]
+    fn exits_with_a_code_for_each_kind_of_failure() {
+        assert_eq!(
+            WeatherError::NoData("KSFO".to_string()).exit_code(),
+            EXIT_NO_DATA
+        );
+        assert_eq!(
+            WeatherError::UnknownStation("XXXX".to_string()).exit_code(),
+            EXIT_NO_DATA
+        );
+        let rate_limited = WeatherError::RateLimited {
+            url: "https://aviationweather.gov/api/data/metar".to_string(),
+            retry_after: None,
+        };
+        assert_eq!(rate_limited.exit_code(), EXIT_SERVICE);
+        assert_eq!(status(503).exit_code(), EXIT_SERVICE);
+        assert_eq!(
+            WeatherError::invalid_data("CSV", "no header").exit_code(),
+            EXIT_SERVICE
+        );
+        assert_eq!(
+            WeatherError::InvalidReport(MetarParseError::MissingTime).exit_code(),
+            EXIT_INVALID_REPORT
+        );
+        let io = WeatherError::io(
+            "Failed to read metars.txt",
+            std::io::ErrorKind::NotFound.into(),
+        );
+        assert_eq!(io.exit_code(), EXIT_FAILURE);
+        assert_eq!(exit_code(&io), EXIT_FAILURE);
+        assert_eq!(exit_code(&std::fmt::Error), EXIT_FAILURE);
+
+        let error = request_error("http://station.invalid/", Duration::from_secs(10));
+        let network = WeatherError::network("http://station.invalid/", error);
+        assert_eq!(network.exit_code(), EXIT_NETWORK);
+        assert!(network.is_transient());
+    }
+
+    #[test]
+    fn hints_at_what_to_do() {
+        let rate_limited = WeatherError::RateLimited {
+            url: "https://aviationweather.gov/api/data/metar".to_string(),
+            retry_after: Some(Duration::from_secs(30)),
+        };
+        assert_eq!(
+            rate_limited.hint().unwrap(),
+            "The API is limiting requests; wait 30s before trying again"
+        );
+        assert!(status(502).hint().unwrap().contains("try again later"));
+        assert_eq!(status(404).hint(), None);
+        assert_eq!(
+            WeatherError::InvalidReport(MetarParseError::Empty).hint(),
+            None
+        );
+    }
+
+    #[test]
+    fn hints_at_stations_that_were_meant() {
+        let known = WeatherError::NoData("KSFO".to_string()).hint().unwrap();
+        assert!(known.starts_with("San Francisco"), "{}", known);
+        let typo = WeatherError::NoData("KSFP".to_string()).hint().unwrap();
+        assert!(typo.starts_with("Did you mean:\n"), "{}", typo);
+        assert!(typo.contains("\n  KSFO  San Francisco Intl"), "{}", typo);
+        let guessed = WeatherError::NoData("KQQQ".to_string()).hint().unwrap();
+        assert!(guessed.ends_with(
+            "QQQ was looked up as a US airport; for an airport elsewhere, use its four-letter ICAO identifier"
+        ));
+    }
+}
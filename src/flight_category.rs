@@ -0,0 +1,214 @@
+//! VFR/MVFR/IFR/LIFR flight category from ceiling and visibility.
+
+use crate::MetarData;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Flight category using the FAA ceiling and visibility thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FlightCategory {
+    /// Ceiling above 3,000 ft and visibility above 5 miles
+    Vfr,
+    /// Ceiling 1,000 to 3,000 ft and/or visibility 3 to 5 miles
+    Mvfr,
+    /// Ceiling 500 to below 1,000 ft and/or visibility 1 to below 3 miles
+    Ifr,
+    /// Ceiling below 500 ft and/or visibility below 1 mile
+    Lifr,
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+impl FlightCategory {
+    /// Categorize from the ceiling in feet AGL (`None` for no ceiling) and the
+    /// visibility in statute miles (`None` if not reported)
+    pub fn from_conditions(ceiling_ft: Option<u32>, visibility_sm: Option<f64>) -> FlightCategory {
+        let by_ceiling = match ceiling_ft {
+            Some(c) if c < 500 => FlightCategory::Lifr,
+            Some(c) if c < 1000 => FlightCategory::Ifr,
+            Some(c) if c <= 3000 => FlightCategory::Mvfr,
+            _ => FlightCategory::Vfr,
+        };
+        let by_visibility = match visibility_sm {
+            Some(v) if v < 1.0 => FlightCategory::Lifr,
+            Some(v) if v < 3.0 => FlightCategory::Ifr,
+            Some(v) if v <= 5.0 => FlightCategory::Mvfr,
+            _ => FlightCategory::Vfr,
+        };
+        by_ceiling.max(by_visibility)
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            FlightCategory::Vfr => "VFR",
+            FlightCategory::Mvfr => "MVFR",
+            FlightCategory::Ifr => "IFR",
+            FlightCategory::Lifr => "LIFR",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            FlightCategory::Vfr => "Visual flight rules",
+            FlightCategory::Mvfr => "Marginal visual flight rules",
+            FlightCategory::Ifr => "Instrument flight rules",
+            FlightCategory::Lifr => "Low instrument flight rules",
+        }
+    }
+
+    /// ANSI escape sequence for the conventional chart color of this category
+    pub fn ansi_color(&self) -> &str {
+        match self {
+            FlightCategory::Vfr => "\x1b[32m",  // green
+            FlightCategory::Mvfr => "\x1b[34m", // blue
+            FlightCategory::Ifr => "\x1b[31m",  // red
+            FlightCategory::Lifr => "\x1b[35m", // magenta
+        }
+    }
+
+    /// The category code, wrapped in ANSI color codes if `color` is set
+    pub fn display_code(&self, color: bool) -> String {
+        if color {
+            format!("{}{}{}", self.ansi_color(), self.code(), ANSI_RESET)
+        } else {
+            self.code().to_string()
+        }
+    }
+}
+
+impl fmt::Display for FlightCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for FlightCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "VFR" => Ok(FlightCategory::Vfr),
+            "MVFR" => Ok(FlightCategory::Mvfr),
+            "IFR" => Ok(FlightCategory::Ifr),
+            "LIFR" => Ok(FlightCategory::Lifr),
+            other => Err(format!("Unknown flight category: {}", other)),
+        }
+    }
+}
+
+/// Keep only the reports whose flight category is one of `categories`
+pub fn filter_by_flight_category<'a>(
+    metars: impl IntoIterator<Item = &'a MetarData>,
+    categories: &[FlightCategory],
+) -> Vec<&'a MetarData> {
+    metars
+        .into_iter()
+        .filter(|metar| {
+            metar
+                .flight_category()
+                .is_some_and(|category| categories.contains(&category))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metar(raw: &str) -> MetarData {
+        MetarData {
+            raw_ob: raw.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn categorizes_ceiling_at_its_thresholds() {
+        let category = |ceiling| FlightCategory::from_conditions(Some(ceiling), Some(10.0));
+        assert_eq!(category(499), FlightCategory::Lifr);
+        assert_eq!(category(500), FlightCategory::Ifr);
+        assert_eq!(category(999), FlightCategory::Ifr);
+        assert_eq!(category(1000), FlightCategory::Mvfr);
+        assert_eq!(category(3000), FlightCategory::Mvfr);
+        assert_eq!(category(3100), FlightCategory::Vfr);
+        assert_eq!(
+            FlightCategory::from_conditions(None, Some(10.0)),
+            FlightCategory::Vfr
+        );
+    }
+
+    #[test]
+    fn categorizes_visibility_at_its_thresholds() {
+        let category = |visibility| FlightCategory::from_conditions(None, Some(visibility));
+        assert_eq!(category(0.75), FlightCategory::Lifr);
+        assert_eq!(category(1.0), FlightCategory::Ifr);
+        assert_eq!(category(2.5), FlightCategory::Ifr);
+        assert_eq!(category(3.0), FlightCategory::Mvfr);
+        assert_eq!(category(5.0), FlightCategory::Mvfr);
+        assert_eq!(category(6.0), FlightCategory::Vfr);
+        assert_eq!(
+            FlightCategory::from_conditions(None, None),
+            FlightCategory::Vfr
+        );
+    }
+
+    #[test]
+    fn takes_the_worse_of_ceiling_and_visibility() {
+        assert_eq!(
+            FlightCategory::from_conditions(Some(800), Some(10.0)),
+            FlightCategory::Ifr
+        );
+        assert_eq!(
+            FlightCategory::from_conditions(Some(5000), Some(0.5)),
+            FlightCategory::Lifr
+        );
+        assert_eq!(
+            FlightCategory::from_conditions(Some(2500), Some(4.0)),
+            FlightCategory::Mvfr
+        );
+    }
+
+    #[test]
+    fn categorizes_reports() {
+        let ifr = metar("KSFO 171756Z 29012KT 2SM BR OVC015 16/11 A3002");
+        assert_eq!(ifr.flight_category(), Some(FlightCategory::Ifr));
+        let lifr = metar("KOAK 171753Z 00000KT 1/2SM FG VV002 12/12 A3000");
+        assert_eq!(lifr.flight_category(), Some(FlightCategory::Lifr));
+    }
+
+    #[test]
+    fn falls_back_to_the_api_category_without_visibility() {
+        let missing = "KXYZ 171756Z AUTO 27005KT OVC004 10/09 A3000";
+        let reported = MetarData {
+            flt_cat: Some("IFR".to_string()),
+            ..metar(missing)
+        };
+        assert_eq!(reported.flight_category(), Some(FlightCategory::Ifr));
+        // Without fltCat the ceiling alone decides
+        assert_eq!(metar(missing).flight_category(), Some(FlightCategory::Lifr));
+        assert_eq!(metar("").flight_category(), None);
+    }
+
+    #[test]
+    fn filters_reports_by_category() {
+        let reports = [
+            metar("KSFO 171756Z 29012KT 10SM FEW015 16/11 A3002"),
+            metar("KOAK 171753Z 00000KT 1/2SM FG VV002 12/12 A3000"),
+            metar("KSJC 171756Z 31010KT 4SM HZ BKN025 22/08 A3001"),
+        ];
+        let kept =
+            filter_by_flight_category(&reports, &[FlightCategory::Mvfr, FlightCategory::Lifr]);
+        let ids: Vec<&str> = kept.iter().map(|m| &m.raw_ob[..4]).collect();
+        assert_eq!(ids, ["KOAK", "KSJC"]);
+        assert!(filter_by_flight_category(&reports, &[]).is_empty());
+    }
+
+    #[test]
+    fn parses_category_codes() {
+        assert_eq!("mvfr".parse(), Ok(FlightCategory::Mvfr));
+        assert_eq!(" LIFR ".parse(), Ok(FlightCategory::Lifr));
+        assert!("XFR".parse::<FlightCategory>().is_err());
+    }
+}
@@ -0,0 +1,404 @@
+//! METAR and SPECI reports in ICAO's IWXXM XML model, as returned by the
+//! aviationweather.gov data API (`format=iwxxm`) and published by other
+//! meteorological services.
+//!
+//! IWXXM carries decoded values only, so reports read from it have an empty
+//! `raw_ob`, and the [`MetarData`] accessors fall back to the decoded fields.
+
+use crate::units::{
+    FEET_PER_METER, HPA_PER_INHG, KMH_PER_KNOT, METERS_PER_STATUTE_MILE, MS_PER_KNOT,
+};
+use crate::{CloudReport, MetarData, ReportedVisibility, Result, WeatherError, WindDirection};
+use chrono::DateTime;
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+
+/// Whether an XML document uses the IWXXM namespace
+pub fn is_iwxxm(text: &str) -> bool {
+    text.contains("icao.int/iwxxm")
+}
+
+/// Every METAR and SPECI in an IWXXM document, whether it holds a single
+/// report or a bulletin of them
+pub fn parse_metars(text: &str) -> Result<Vec<MetarData>> {
+    let mut reader = quick_xml::Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    let mut reports = Vec::new();
+    let mut current: Option<MetarData> = None;
+    // Local names of the open elements, and the unit of measure of the innermost
+    let mut path: Vec<String> = Vec::new();
+    let mut uom: Option<String> = None;
+    loop {
+        let event = reader.read_event().map_err(xml_error)?;
+        match event {
+            Event::Start(start) => {
+                let name = local_name(&start);
+                let attributes = attributes(&start)?;
+                if name == "METAR" || name == "SPECI" {
+                    current = Some(MetarData {
+                        metar_type: Some(name.clone()),
+                        ..Default::default()
+                    });
+                }
+                if let Some(metar) = current.as_mut() {
+                    read_attributes(metar, &name, &attributes);
+                }
+                uom = attributes.get("uom").cloned();
+                path.push(name);
+            }
+            Event::Empty(empty) => {
+                if let Some(metar) = current.as_mut() {
+                    read_attributes(metar, &local_name(&empty), &attributes(&empty)?);
+                }
+            }
+            Event::Text(text) => {
+                if let Some(metar) = current.as_mut() {
+                    let value = text.unescape().map_err(xml_error)?;
+                    read_value(metar, &path, value.trim(), uom.as_deref());
+                }
+            }
+            Event::End(_) => {
+                if let Some(name) = path.pop()
+                    && (name == "METAR" || name == "SPECI")
+                {
+                    reports.extend(current.take().filter(|m| !m.icao_id.is_empty()));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(reports)
+}
+
+fn xml_error(e: impl std::fmt::Display) -> WeatherError {
+    WeatherError::invalid_data("IWXXM", e)
+}
+
+fn local_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+/// Attributes by local name, e.g. `href` for `xlink:href`
+fn attributes(element: &BytesStart) -> Result<HashMap<String, String>> {
+    let mut attributes = HashMap::new();
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(xml_error)?;
+        let name = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+        let value = attribute.unescape_value().map_err(xml_error)?;
+        attributes.insert(name, value.into_owned());
+    }
+    Ok(attributes)
+}
+
+/// The code at the end of a WMO code list URL, e.g. "+TSRA" for
+/// `http://codes.wmo.int/306/4678/+TSRA`
+fn code(href: &str) -> &str {
+    href.rsplit('/').next().unwrap_or(href)
+}
+
+/// Fields given as attributes rather than element text
+fn read_attributes(metar: &mut MetarData, name: &str, attributes: &HashMap<String, String>) {
+    let flag = |attribute: &str| attributes.get(attribute).is_some_and(|v| v == "true");
+    let href = attributes.get("href").map(|href| code(href));
+    match name {
+        "MeteorologicalAerodromeObservation" if flag("cloudAndVisibilityOK") => {
+            metar.visib = Some(ReportedVisibility {
+                statute_miles: 10.0,
+                or_more: true,
+            });
+        }
+        "AerodromeSurfaceWind" if flag("variableWindDirection") => {
+            metar.wdir = Some(WindDirection::Variable);
+        }
+        "presentWeather" => {
+            if let Some(weather) = href {
+                metar.wx_string = Some(match metar.wx_string.take() {
+                    Some(existing) => format!("{} {}", existing, weather),
+                    None => weather.to_string(),
+                });
+            }
+        }
+        "amount" => {
+            // WMO code table 0 20 008
+            let cover = match href {
+                Some("1") => "FEW",
+                Some("2") => "SCT",
+                Some("3") => "BKN",
+                Some("4") => "OVC",
+                _ => return,
+            };
+            metar.clouds.push(CloudReport {
+                cover: cover.to_string(),
+                base: None,
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Fields given as element text, in the unit of measure `uom`
+fn read_value(metar: &mut MetarData, path: &[String], value: &str, uom: Option<&str>) {
+    let Some(name) = path.last() else {
+        return;
+    };
+    let within = |ancestor: &str| path.iter().any(|p| p == ancestor);
+    let number = value.parse::<f64>().ok();
+    match name.as_str() {
+        "timePosition" if within("observationTime") || metar.obs_time.is_none() => {
+            if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+                metar.obs_time = Some(time.timestamp());
+                metar.report_time = Some(time.format("%Y-%m-%d %H:%M:%S").to_string());
+            }
+        }
+        "locationIndicatorICAO" => metar.icao_id = value.to_uppercase(),
+        "designator" if metar.icao_id.is_empty() => metar.icao_id = value.to_uppercase(),
+        "pos" => {
+            let mut coordinates = value.split_whitespace().map(|v| v.parse().ok());
+            metar.lat = coordinates.next().flatten();
+            metar.lon = coordinates.next().flatten();
+        }
+        "elevation" => metar.elev = number.map(|v| meters(v, uom)),
+        "airTemperature" => metar.temp = number.map(|v| celsius(v, uom)),
+        "dewpointTemperature" => metar.dewp = number.map(|v| celsius(v, uom)),
+        "qnh" => {
+            metar.altim = number.map(|v| match uom {
+                Some("[in_i'Hg]") => v * HPA_PER_INHG,
+                _ => v,
+            })
+        }
+        "meanWindDirection" if metar.wdir.is_none() => {
+            metar.wdir = number.map(|v| WindDirection::Degrees(v.round() as u16));
+        }
+        "meanWindSpeed" => metar.wspd = number.map(|v| knots(v, uom)),
+        "windGustSpeed" => metar.wgst = number.map(|v| knots(v, uom)),
+        "prevailingVisibility" => {
+            metar.visib = number.map(|v| ReportedVisibility {
+                statute_miles: statute_miles(v, uom),
+                or_more: false,
+            });
+        }
+        "prevailingVisibilityOperator" if value == "ABOVE" => {
+            if let Some(visib) = metar.visib.as_mut() {
+                visib.or_more = true;
+            }
+        }
+        "base" if within("CloudLayer") => {
+            if let Some(layer) = metar.clouds.last_mut() {
+                layer.base = number.map(|v| feet(v, uom));
+            }
+        }
+        "verticalVisibility" => metar.vert_vis = number.map(|v| feet(v, uom)),
+        _ => {}
+    }
+}
+
+fn celsius(value: f64, uom: Option<&str>) -> f64 {
+    match uom {
+        Some("[degF]") => (value - 32.0) * 5.0 / 9.0,
+        _ => value,
+    }
+}
+
+fn knots(value: f64, uom: Option<&str>) -> u32 {
+    let knots = match uom {
+        Some("m/s") => value / MS_PER_KNOT,
+        Some("km/h") => value / KMH_PER_KNOT,
+        _ => value,
+    };
+    knots.round() as u32
+}
+
+fn feet(value: f64, uom: Option<&str>) -> u32 {
+    let feet = match uom {
+        Some("m") | Some("M") => value * FEET_PER_METER,
+        _ => value,
+    };
+    feet.round() as u32
+}
+
+fn meters(value: f64, uom: Option<&str>) -> f64 {
+    match uom {
+        Some("[ft_i]") | Some("FT") => value / FEET_PER_METER,
+        _ => value,
+    }
+}
+
+fn statute_miles(value: f64, uom: Option<&str>) -> f64 {
+    match uom {
+        Some("km") => value * 1000.0 / METERS_PER_STATUTE_MILE,
+        Some("[mi_i]") | Some("[sm_i]") => value,
+        _ => value / METERS_PER_STATUTE_MILE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// EGLL 171750Z 24015G28KT 4000 -RA BKN008 OVC015 12/10 Q1003, as IWXXM 3.0
+    const METAR: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<iwxxm:METAR xmlns:iwxxm="http://icao.int/iwxxm/3.0" xmlns:aixm="http://www.aixm.aero/schema/5.1.1"
+    xmlns:gml="http://www.opengis.net/gml/3.2" xmlns:xlink="http://www.w3.org/1999/xlink"
+    gml:id="uuid.1" reportStatus="NORMAL" automatedStation="false">
+  <iwxxm:issueTime><gml:TimeInstant gml:id="uuid.2"><gml:timePosition>2026-10-17T17:50:00Z</gml:timePosition></gml:TimeInstant></iwxxm:issueTime>
+  <iwxxm:aerodrome>
+    <aixm:AirportHeliport gml:id="uuid.3">
+      <aixm:timeSlice><aixm:AirportHeliportTimeSlice gml:id="uuid.4">
+        <aixm:designator>EGLL</aixm:designator>
+        <aixm:locationIndicatorICAO>EGLL</aixm:locationIndicatorICAO>
+        <aixm:ARP><aixm:ElevatedPoint gml:id="uuid.5" srsName="urn:ogc:def:crs:EPSG::4326">
+          <gml:pos>51.4775 -0.4614</gml:pos>
+          <aixm:elevation uom="M">25</aixm:elevation>
+        </aixm:ElevatedPoint></aixm:ARP>
+      </aixm:AirportHeliportTimeSlice></aixm:timeSlice>
+    </aixm:AirportHeliport>
+  </iwxxm:aerodrome>
+  <iwxxm:observationTime><gml:TimeInstant gml:id="uuid.6"><gml:timePosition>2026-10-17T17:50:00Z</gml:timePosition></gml:TimeInstant></iwxxm:observationTime>
+  <iwxxm:observation>
+    <iwxxm:MeteorologicalAerodromeObservation cloudAndVisibilityOK="false">
+      <iwxxm:airTemperature uom="Cel">12</iwxxm:airTemperature>
+      <iwxxm:dewpointTemperature uom="Cel">10</iwxxm:dewpointTemperature>
+      <iwxxm:qnh uom="hPa">1003</iwxxm:qnh>
+      <iwxxm:surfaceWind><iwxxm:AerodromeSurfaceWind variableWindDirection="false">
+        <iwxxm:meanWindDirection uom="deg">240</iwxxm:meanWindDirection>
+        <iwxxm:meanWindSpeed uom="[kn_i]">15</iwxxm:meanWindSpeed>
+        <iwxxm:windGustSpeed uom="[kn_i]">28</iwxxm:windGustSpeed>
+      </iwxxm:AerodromeSurfaceWind></iwxxm:surfaceWind>
+      <iwxxm:visibility><iwxxm:AerodromeHorizontalVisibility>
+        <iwxxm:prevailingVisibility uom="m">4000</iwxxm:prevailingVisibility>
+      </iwxxm:AerodromeHorizontalVisibility></iwxxm:visibility>
+      <iwxxm:presentWeather xlink:href="http://codes.wmo.int/306/4678/-RA"/>
+      <iwxxm:cloud><iwxxm:AerodromeCloud>
+        <iwxxm:layer><iwxxm:CloudLayer>
+          <iwxxm:amount xlink:href="http://codes.wmo.int/49-2/CloudAmountReportedAtAerodrome/3"/>
+          <iwxxm:base uom="[ft_i]">800</iwxxm:base>
+        </iwxxm:CloudLayer></iwxxm:layer>
+        <iwxxm:layer><iwxxm:CloudLayer>
+          <iwxxm:amount xlink:href="http://codes.wmo.int/49-2/CloudAmountReportedAtAerodrome/4"/>
+          <iwxxm:base uom="[ft_i]">1500</iwxxm:base>
+        </iwxxm:CloudLayer></iwxxm:layer>
+      </iwxxm:AerodromeCloud></iwxxm:cloud>
+    </iwxxm:MeteorologicalAerodromeObservation>
+  </iwxxm:observation>
+</iwxxm:METAR>"#;
+
+    /// A bulletin with two reports in other units: UUEE 171800Z 18005MPS CAVOK 08/02 Q1021
+    /// and KDEN 171815Z VRB03KT 1/2SM FG VV002 M01/M01 A3001
+    const BULLETIN: &str = r#"<collect:MeteorologicalBulletin xmlns:collect="http://def.wmo.int/collect/2014"
+    xmlns:iwxxm="http://icao.int/iwxxm/3.0" xmlns:aixm="http://www.aixm.aero/schema/5.1.1"
+    xmlns:gml="http://www.opengis.net/gml/3.2" xmlns:xlink="http://www.w3.org/1999/xlink">
+  <collect:meteorologicalInformation>
+    <iwxxm:METAR>
+      <iwxxm:aerodrome><aixm:AirportHeliport><aixm:timeSlice><aixm:AirportHeliportTimeSlice>
+        <aixm:designator>UUEE</aixm:designator>
+      </aixm:AirportHeliportTimeSlice></aixm:timeSlice></aixm:AirportHeliport></iwxxm:aerodrome>
+      <iwxxm:observationTime><gml:TimeInstant><gml:timePosition>2026-10-17T18:00:00Z</gml:timePosition></gml:TimeInstant></iwxxm:observationTime>
+      <iwxxm:observation>
+        <iwxxm:MeteorologicalAerodromeObservation cloudAndVisibilityOK="true">
+          <iwxxm:airTemperature uom="Cel">8</iwxxm:airTemperature>
+          <iwxxm:dewpointTemperature uom="Cel">2</iwxxm:dewpointTemperature>
+          <iwxxm:qnh uom="hPa">1021</iwxxm:qnh>
+          <iwxxm:surfaceWind><iwxxm:AerodromeSurfaceWind>
+            <iwxxm:meanWindDirection uom="deg">180</iwxxm:meanWindDirection>
+            <iwxxm:meanWindSpeed uom="m/s">5</iwxxm:meanWindSpeed>
+          </iwxxm:AerodromeSurfaceWind></iwxxm:surfaceWind>
+        </iwxxm:MeteorologicalAerodromeObservation>
+      </iwxxm:observation>
+    </iwxxm:METAR>
+  </collect:meteorologicalInformation>
+  <collect:meteorologicalInformation>
+    <iwxxm:SPECI automatedStation="true">
+      <iwxxm:aerodrome><aixm:AirportHeliport><aixm:timeSlice><aixm:AirportHeliportTimeSlice>
+        <aixm:locationIndicatorICAO>KDEN</aixm:locationIndicatorICAO>
+      </aixm:AirportHeliportTimeSlice></aixm:timeSlice></aixm:AirportHeliport></iwxxm:aerodrome>
+      <iwxxm:observationTime><gml:TimeInstant><gml:timePosition>2026-10-17T18:15:00Z</gml:timePosition></gml:TimeInstant></iwxxm:observationTime>
+      <iwxxm:observation>
+        <iwxxm:MeteorologicalAerodromeObservation>
+          <iwxxm:airTemperature uom="[degF]">30.2</iwxxm:airTemperature>
+          <iwxxm:qnh uom="[in_i'Hg]">30.01</iwxxm:qnh>
+          <iwxxm:surfaceWind><iwxxm:AerodromeSurfaceWind variableWindDirection="true">
+            <iwxxm:meanWindSpeed uom="[kn_i]">3</iwxxm:meanWindSpeed>
+          </iwxxm:AerodromeSurfaceWind></iwxxm:surfaceWind>
+          <iwxxm:visibility><iwxxm:AerodromeHorizontalVisibility>
+            <iwxxm:prevailingVisibility uom="[mi_i]">0.5</iwxxm:prevailingVisibility>
+          </iwxxm:AerodromeHorizontalVisibility></iwxxm:visibility>
+          <iwxxm:presentWeather xlink:href="http://codes.wmo.int/306/4678/FG"/>
+          <iwxxm:cloud><iwxxm:AerodromeCloud>
+            <iwxxm:verticalVisibility uom="m">60</iwxxm:verticalVisibility>
+          </iwxxm:AerodromeCloud></iwxxm:cloud>
+        </iwxxm:MeteorologicalAerodromeObservation>
+      </iwxxm:observation>
+    </iwxxm:SPECI>
+  </collect:meteorologicalInformation>
+</collect:MeteorologicalBulletin>"#;
+
+    #[test]
+    fn recognizes_iwxxm() {
+        assert!(is_iwxxm(METAR));
+        assert!(is_iwxxm(BULLETIN));
+        assert!(!is_iwxxm("<response><data><METAR/></data></response>"));
+    }
+
+    #[test]
+    fn decodes_a_metar() {
+        let reports = parse_metars(METAR).unwrap();
+        assert_eq!(reports.len(), 1);
+        let metar = &reports[0];
+        assert_eq!(metar.icao_id, "EGLL");
+        assert_eq!(metar.metar_type.as_deref(), Some("METAR"));
+        assert!(metar.raw_ob.is_empty());
+        assert_eq!(metar.obs_time, Some(1_792_259_400));
+        assert_eq!(metar.report_time.as_deref(), Some("2026-10-17 17:50:00"));
+        assert_eq!(
+            (metar.lat, metar.lon, metar.elev),
+            (Some(51.4775), Some(-0.4614), Some(25.0))
+        );
+        assert_eq!(
+            (metar.temp, metar.dewp, metar.altim),
+            (Some(12.0), Some(10.0), Some(1003.0))
+        );
+        assert_eq!(metar.wdir, Some(WindDirection::Degrees(240)));
+        assert_eq!((metar.wspd, metar.wgst), (Some(15), Some(28)));
+        let visib = metar.visib.as_ref().unwrap();
+        assert!((visib.statute_miles - 2.49).abs() < 0.01 && !visib.or_more);
+        assert_eq!(metar.wx_string.as_deref(), Some("-RA"));
+        let clouds: Vec<(&str, Option<u32>)> = metar
+            .clouds
+            .iter()
+            .map(|layer| (layer.cover.as_str(), layer.base))
+            .collect();
+        assert_eq!(clouds, [("BKN", Some(800)), ("OVC", Some(1500))]);
+        assert_eq!(metar.ceiling(), Some(800));
+    }
+
+    #[test]
+    fn decodes_a_bulletin_in_other_units() {
+        let reports = parse_metars(BULLETIN).unwrap();
+        assert_eq!(reports.len(), 2);
+
+        let uuee = &reports[0];
+        assert_eq!(uuee.icao_id, "UUEE");
+        assert_eq!(uuee.wspd, Some(10));
+        let visib = uuee.visib.as_ref().unwrap();
+        assert_eq!((visib.statute_miles, visib.or_more), (10.0, true));
+        assert!(uuee.clouds.is_empty());
+
+        let kden = &reports[1];
+        assert_eq!(kden.icao_id, "KDEN");
+        assert_eq!(kden.metar_type.as_deref(), Some("SPECI"));
+        assert_eq!(kden.wdir, Some(WindDirection::Variable));
+        assert!((kden.temp.unwrap() + 1.0).abs() < 1e-9);
+        assert!((kden.altim.unwrap() - 1016.3).abs() < 0.1);
+        assert_eq!(kden.visib.as_ref().unwrap().statute_miles, 0.5);
+        assert_eq!(kden.wx_string.as_deref(), Some("FG"));
+        assert_eq!(kden.vert_vis, Some(197));
+    }
+
+    #[test]
+    fn rejects_malformed_xml() {
+        assert!(parse_metars("<iwxxm:METAR><iwxxm:qnh></iwxxm:METAR>").is_err());
+        assert_eq!(parse_metars("<empty/>").unwrap().len(), 0);
+    }
+}
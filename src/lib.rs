@@ -1,55 +1,66 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 mod abbreviations;
+pub mod bulk;
+pub mod cache;
+pub mod client;
+pub mod derived;
+pub mod error;
+pub mod flight_category;
+pub mod iwxxm;
+pub mod location;
+pub mod metar;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+pub mod output;
+pub mod present_weather;
+pub mod query;
+pub mod stations;
+pub mod taf;
+pub mod trend;
 pub mod tts;
+pub mod units;
+pub mod xml;
 
 pub use abbreviations::expand_abbreviations;
-
-#[derive(Debug)]
-pub enum WeatherError {
-    HttpClient(String),
-    Request(String),
-    EmptyResponse(String),
-    InvalidJson(String),
-    NoData(String),
-}
-
-impl fmt::Display for WeatherError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            WeatherError::HttpClient(msg) => write!(f, "HTTP client error: {}", msg),
-            WeatherError::Request(msg) => write!(f, "Request failed: {}", msg),
-            WeatherError::EmptyResponse(icao) => write!(
-                f,
-                "Empty response from API. ICAO code '{}' may not be valid or may not have current weather data. Try adding 'K' prefix for US airports (e.g., KRHV)",
-                icao
-            ),
-            WeatherError::InvalidJson(msg) => write!(f, "Failed to parse JSON response: {}", msg),
-            WeatherError::NoData(icao) => write!(
-                f,
-                "No weather data found for ICAO: {}. This airport may not report METAR data or may not be a valid ICAO identifier.\nCommon reasons:\n- Small airports may not have weather reporting\n- Try the full ICAO code (US airports: add 'K' prefix, e.g., KRHV)\n- Verify the airport code at https://aviationweather.gov",
-                icao
-            ),
-        }
-    }
-}
-
-impl std::error::Error for WeatherError {}
-
-pub type Result<T> = std::result::Result<T, WeatherError>;
+pub use bulk::BulkMetars;
+pub use cache::ObservationCache;
+pub use client::{ResponseFormat, WeatherClient, WeatherClientBuilder};
+pub use derived::DerivedQuantities;
+pub use error::{NetworkErrorKind, Result, Warning, WeatherError};
+pub use flight_category::{FlightCategory, filter_by_flight_category};
+pub use location::Coordinates;
+pub use metar::{MetarParseError, Observation, Remarks};
+#[cfg(feature = "async")]
+pub use nonblocking::AsyncWeatherClient;
+pub use output::{DecodedReport, OutputFormat};
+pub use present_weather::{WeatherPhenomenon, parse_present_weather};
+pub use query::{BoundingBox, MetarQuery};
+pub use stations::{
+    NearbyStation, Station, find_station, nearest_reports, nearest_stations, resolve_station_id,
+    search_stations, suggest_stations,
+};
+pub use taf::{Taf, TafData};
+pub use trend::{TrendReport, analyze_trends};
+use units::FEET_PER_METER;
+pub use units::Units;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WmoCode {
     // Precipitation
-    Rain,        // RA
-    Snow,        // SN
-    Drizzle,     // DZ
-    SnowGrains,  // SG
-    IceCrystals, // IC
-    IcePellets,  // PL
-    Hail,        // GR
-    SmallHail,   // GS
+    Rain,                 // RA
+    Snow,                 // SN
+    Drizzle,              // DZ
+    SnowGrains,           // SG
+    IceCrystals,          // IC
+    IcePellets,           // PL
+    Hail,                 // GR
+    SmallHail,            // GS
+    UnknownPrecipitation, // UP
 
     // Obscuration
     Fog,         // FG
@@ -81,6 +92,7 @@ impl WmoCode {
             WmoCode::IcePellets => "PL",
             WmoCode::Hail => "GR",
             WmoCode::SmallHail => "GS",
+            WmoCode::UnknownPrecipitation => "UP",
             WmoCode::Fog => "FG",
             WmoCode::Mist => "BR",
             WmoCode::Haze => "HZ",
@@ -108,6 +120,7 @@ impl WmoCode {
             WmoCode::IcePellets => "Ice Pellets",
             WmoCode::Hail => "Hail",
             WmoCode::SmallHail => "Small Hail/Snow Pellets",
+            WmoCode::UnknownPrecipitation => "Unknown Precipitation",
             WmoCode::Fog => "Fog",
             WmoCode::Mist => "Mist",
             WmoCode::Haze => "Haze",
@@ -125,6 +138,10 @@ impl WmoCode {
         }
     }
 
+    pub fn from_code(code: &str) -> Option<WmoCode> {
+        WmoCode::all_codes().into_iter().find(|c| c.code() == code)
+    }
+
     fn all_codes() -> Vec<WmoCode> {
         vec![
             WmoCode::Rain,
@@ -135,6 +152,7 @@ impl WmoCode {
             WmoCode::IcePellets,
             WmoCode::Hail,
             WmoCode::SmallHail,
+            WmoCode::UnknownPrecipitation,
             WmoCode::Fog,
             WmoCode::Mist,
             WmoCode::Haze,
@@ -153,87 +171,670 @@ impl WmoCode {
     }
 }
 
+/// Serialized as its code and description, e.g. `{"code": "RA", "description": "Rain"}`
+impl Serialize for WmoCode {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut code = s.serialize_struct("WmoCode", 2)?;
+        code.serialize_field("code", self.code())?;
+        code.serialize_field("description", self.description())?;
+        code.end()
+    }
+}
+
 impl fmt::Display for WmoCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} ({})", self.code(), self.description())
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// Wind direction as reported by the API: degrees true or "VRB"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindDirection {
+    Degrees(u16),
+    Variable,
+}
+
+/// Visibility as reported by the API, where "10+" means ten miles or more
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReportedVisibility {
+    pub statute_miles: f64,
+    pub or_more: bool,
+}
+
+/// The API mixes numeric and string encodings for some fields
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(f64),
+    String(String),
+}
+
+/// Parses degrees or "VRB"
+impl FromStr for WindDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("VRB") {
+            return Ok(WindDirection::Variable);
+        }
+        s.parse()
+            .map(WindDirection::Degrees)
+            .map_err(|_| format!("invalid wind direction: {}", s))
+    }
+}
+
+impl<'de> Deserialize<'de> for WindDirection {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        match NumberOrString::deserialize(d)? {
+            NumberOrString::Number(n) => Ok(WindDirection::Degrees(n as u16)),
+            NumberOrString::String(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Serialized as the API does: degrees or "VRB"
+impl Serialize for WindDirection {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            WindDirection::Degrees(d) => s.serialize_u16(*d),
+            WindDirection::Variable => s.serialize_str("VRB"),
+        }
+    }
+}
+
+/// Parses statute miles such as "10+", "1/2" or "2.5"
+impl FromStr for ReportedVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let (value, or_more) = match trimmed.strip_suffix('+') {
+            Some(v) => (v, true),
+            None => (trimmed, false),
+        };
+        let statute_miles = match value.split_once('/') {
+            Some((num, den)) => num
+                .parse::<f64>()
+                .ok()
+                .zip(den.parse::<f64>().ok())
+                .map(|(n, d)| n / d),
+            None => value.parse().ok(),
+        }
+        .ok_or_else(|| format!("invalid visibility: {}", s))?;
+        Ok(ReportedVisibility {
+            statute_miles,
+            or_more,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ReportedVisibility {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        match NumberOrString::deserialize(d)? {
+            NumberOrString::Number(n) => Ok(ReportedVisibility {
+                statute_miles: n,
+                or_more: false,
+            }),
+            NumberOrString::String(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Serialized as the API does: statute miles, or a string such as "10+"
+impl Serialize for ReportedVisibility {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        if self.or_more {
+            s.serialize_str(&format!("{}+", self.statute_miles))
+        } else {
+            s.serialize_f64(self.statute_miles)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CloudReport {
+    pub cover: String,
+    /// Base of the layer in feet above ground level
+    pub base: Option<u32>,
+}
+
+/// A METAR report as returned by the aviationweather.gov data API
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct MetarData {
     #[serde(rename = "icaoId")]
-    #[allow(dead_code)]
     pub icao_id: String,
-    #[serde(rename = "rawOb")]
-    pub raw_ob: String,
+    #[serde(rename = "receiptTime")]
+    pub receipt_time: Option<String>,
+    /// Observation time in seconds since the Unix epoch
+    #[serde(rename = "obsTime")]
+    pub obs_time: Option<i64>,
+    #[serde(rename = "reportTime")]
+    pub report_time: Option<String>,
     pub temp: Option<f64>,
+    pub dewp: Option<f64>,
+    pub wdir: Option<WindDirection>,
+    /// Wind speed in knots
+    pub wspd: Option<u32>,
+    /// Gust speed in knots
+    pub wgst: Option<u32>,
+    pub visib: Option<ReportedVisibility>,
+    /// Altimeter setting in hectopascals
+    pub altim: Option<f64>,
+    /// Sea level pressure in hectopascals
+    pub slp: Option<f64>,
+    #[serde(rename = "qcField")]
+    pub qc_field: Option<i64>,
     #[serde(rename = "wxString")]
     pub wx_string: Option<String>,
+    /// Three-hour pressure tendency in hectopascals
+    #[serde(rename = "presTend")]
+    pub pres_tend: Option<f64>,
+    #[serde(rename = "maxT")]
+    pub max_t: Option<f64>,
+    #[serde(rename = "minT")]
+    pub min_t: Option<f64>,
+    #[serde(rename = "maxT24")]
+    pub max_t24: Option<f64>,
+    #[serde(rename = "minT24")]
+    pub min_t24: Option<f64>,
+    /// Precipitation amounts in inches
+    pub precip: Option<f64>,
+    #[serde(rename = "pcp3hr")]
+    pub pcp3hr: Option<f64>,
+    #[serde(rename = "pcp6hr")]
+    pub pcp6hr: Option<f64>,
+    #[serde(rename = "pcp24hr")]
+    pub pcp24hr: Option<f64>,
+    /// Snow depth in inches
+    pub snow: Option<f64>,
+    /// Vertical visibility in feet
+    #[serde(rename = "vertVis")]
+    pub vert_vis: Option<u32>,
+    #[serde(rename = "metarType")]
+    pub metar_type: Option<String>,
+    #[serde(rename = "rawOb")]
+    pub raw_ob: String,
+    #[serde(rename = "mostRecent")]
+    pub most_recent: Option<i64>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Station elevation in meters
+    pub elev: Option<f64>,
+    pub prior: Option<i64>,
     pub name: Option<String>,
+    #[serde(default)]
+    pub clouds: Vec<CloudReport>,
+    /// Flight category (VFR, MVFR, IFR, LIFR) when computed by the API
+    #[serde(rename = "fltCat")]
+    pub flt_cat: Option<String>,
+    /// `raw_ob` decoded on first use, so the accessors below parse it once
+    #[serde(skip)]
+    decoded: OnceLock<std::result::Result<Observation, MetarParseError>>,
+}
+
+impl MetarData {
+    /// Build a report from raw METAR text rather than the API, e.g. to replay
+    /// archived reports. The observation time is placed in the month nearest
+    /// to now; station name and location come from the station database.
+    pub fn from_raw(raw: &str) -> std::result::Result<MetarData, MetarParseError> {
+        let raw = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        let obs = Observation::parse(&raw)?;
+        let obs_time = obs.time.resolve(chrono::Utc::now());
+        let wx_codes: Vec<String> = obs.weather.iter().map(|w| w.code()).collect();
+        let remarks = obs.coded_remarks();
+        let station = find_station(&obs.station);
+        Ok(MetarData {
+            icao_id: obs.station.clone(),
+            obs_time: obs_time.map(|t| t.timestamp()),
+            report_time: obs_time.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+            // The remarks carry tenths of a degree, as the API reports
+            temp: remarks
+                .temperature
+                .or_else(|| obs.temperature.map(f64::from)),
+            dewp: remarks.dewpoint.or_else(|| obs.dewpoint.map(f64::from)),
+            altim: obs.altimeter.map(|a| a.hpa()),
+            slp: remarks.sea_level_pressure,
+            wx_string: (!wx_codes.is_empty()).then(|| wx_codes.join(" ")),
+            vert_vis: obs
+                .sky
+                .iter()
+                .find(|layer| layer.cover == metar::CloudCover::VerticalVisibility)
+                .and_then(|layer| layer.base),
+            metar_type: Some(obs.report_type.to_string()),
+            lat: station.map(|s| s.lat),
+            lon: station.map(|s| s.lon),
+            elev: station.map(Station::elevation_m),
+            name: station.map(Station::display_name),
+            raw_ob: raw,
+            ..Default::default()
+        })
+    }
+
+    /// Station name from the API, or from the station database if the API
+    /// gave none
+    pub fn station_name(&self) -> Option<String> {
+        self.name
+            .clone()
+            .or_else(|| find_station(&self.icao_id).map(Station::display_name))
+    }
+
+    /// The raw METAR text decoded into a structured observation. It is
+    /// decoded once, so `raw_ob` should not be changed after this is called.
+    pub fn observation(&self) -> std::result::Result<&Observation, MetarParseError> {
+        self.decoded
+            .get_or_init(|| Observation::parse(&self.raw_ob))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Temperature in degrees Celsius, from the API or the raw report
+    pub fn temperature(&self) -> Option<f64> {
+        self.temp.or_else(|| {
+            self.observation()
+                .ok()
+                .and_then(|obs| obs.temperature.map(f64::from))
+        })
+    }
+
+    /// Dewpoint in degrees Celsius, from the API or the raw report
+    pub fn dewpoint(&self) -> Option<f64> {
+        self.dewp.or_else(|| {
+            self.observation()
+                .ok()
+                .and_then(|obs| obs.dewpoint.map(f64::from))
+        })
+    }
+
+    /// Wind from the API fields, with the variable sector taken from the raw report
+    pub fn wind(&self) -> Option<metar::Wind> {
+        let decoded = self.observation().ok().and_then(|obs| obs.wind.as_ref());
+        let Some(speed) = self.wspd else {
+            return decoded.cloned();
+        };
+        Some(metar::Wind {
+            direction: match self.wdir {
+                Some(WindDirection::Degrees(d)) => Some(d),
+                _ => None,
+            },
+            speed: speed as u16,
+            gust: self.wgst.map(|g| g as u16),
+            unit: metar::SpeedUnit::Knots,
+            variable_sector: decoded.and_then(|w| w.variable_sector),
+        })
+    }
+
+    /// Prevailing visibility from the API, or from the raw report (CAVOK counts as 10 km)
+    pub fn visibility(&self) -> Option<metar::Visibility> {
+        if let Some(visib) = self.visib {
+            return Some(metar::Visibility {
+                distance: visib.statute_miles,
+                unit: metar::DistanceUnit::StatuteMiles,
+                bound: visib.or_more.then_some(metar::Bound::GreaterThan),
+            });
+        }
+        let obs = self.observation().ok()?;
+        if obs.cavok {
+            return Some(metar::Visibility {
+                distance: 10000.0,
+                unit: metar::DistanceUnit::Meters,
+                bound: Some(metar::Bound::GreaterThan),
+            });
+        }
+        obs.visibility.clone()
+    }
+
+    /// Sky condition from the raw report, which carries CB/TCU types,
+    /// falling back to the API's cloud list
+    pub fn sky(&self) -> Vec<metar::CloudLayer> {
+        if let Ok(obs) = self.observation()
+            && !obs.sky.is_empty()
+        {
+            return obs.sky.clone();
+        }
+        self.clouds
+            .iter()
+            .filter_map(|c| {
+                Some(metar::CloudLayer {
+                    cover: metar::CloudCover::from_code(&c.cover)?,
+                    base: c.base,
+                    cloud_type: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Height in feet of the lowest broken, overcast or vertical visibility layer
+    pub fn ceiling(&self) -> Option<u32> {
+        self.sky()
+            .iter()
+            .filter(|layer| layer.cover.is_ceiling())
+            .filter_map(|layer| layer.base)
+            .min()
+    }
+
+    /// Flight category from the ceiling and visibility, falling back to the
+    /// API's `fltCat` when the visibility is unknown
+    pub fn flight_category(&self) -> Option<FlightCategory> {
+        if let Some(vis) = self.visibility() {
+            return Some(FlightCategory::from_conditions(
+                self.ceiling(),
+                Some(vis.statute_miles()),
+            ));
+        }
+        self.flt_cat
+            .as_deref()
+            .and_then(|c| c.parse().ok())
+            .or_else(|| {
+                let sky = self.sky();
+                (!sky.is_empty()).then(|| FlightCategory::from_conditions(self.ceiling(), None))
+            })
+    }
+
+    /// Altimeter setting in the units the station reported, falling back to the API value
+    pub fn altimeter(&self) -> Option<metar::Altimeter> {
+        self.observation()
+            .ok()
+            .and_then(|obs| obs.altimeter)
+            .or_else(|| self.altim.map(metar::Altimeter::Hectopascals))
+    }
+
+    /// Humidity, comfort indices, density altitude and so on
+    pub fn derived(&self) -> DerivedQuantities {
+        DerivedQuantities::from_metar(self)
+    }
 }
 
 pub fn celsius_to_fahrenheit(c: f64) -> f64 {
     (c * 9.0 / 5.0) + 32.0
 }
 
+/// Flatten a weather string into its WMO codes in reported order.
+/// A thunderstorm descriptor (e.g. `TSRA`) yields both `TS` and `RA`.
 pub fn parse_wmo_codes(wx_string: &str) -> Vec<WmoCode> {
-    WmoCode::all_codes()
+    parse_present_weather(wx_string)
+        .into_iter()
+        .flat_map(|p| {
+            let thunderstorm = (p.descriptor == Some(present_weather::Descriptor::Thunderstorm))
+                .then_some(WmoCode::Thunderstorm);
+            thunderstorm.into_iter().chain(p.phenomena)
+        })
+        .collect()
+}
+
+/// Decode text holding one or more raw METARs, one per line or each
+/// terminated by `=`. Blank lines are skipped.
+pub fn parse_metar_text(text: &str) -> Vec<std::result::Result<MetarData, MetarParseError>> {
+    let reports: Vec<&str> = if text.contains('=') {
+        text.split('=').collect()
+    } else {
+        text.lines().collect()
+    };
+    reports
         .into_iter()
-        .filter(|code| wx_string.contains(code.code()))
+        .map(str::trim)
+        .filter(|report| !report.is_empty())
+        .map(MetarData::from_raw)
         .collect()
 }
 
+/// Read raw METAR text from a file, or from standard input if `path` is `-`
+pub fn read_metar_input(path: &str) -> Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)
+            .map_err(|e| WeatherError::io("Failed to read standard input", e))?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|e| WeatherError::io(format!("Failed to read {}", path), e))
+    }
+}
+
+/// Fetch the latest METAR for a station using a default [`WeatherClient`]
 pub fn fetch_weather_data(icao: &str) -> Result<MetarData> {
-    let url = format!(
-        "https://aviationweather.gov/api/data/metar?ids={}&format=json",
-        icao.to_uppercase()
-    );
+    WeatherClient::new()?.fetch_weather_data(icao)
+}
 
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("aviation-weather-cli/0.1.0")
-        .build()
-        .map_err(|e| WeatherError::HttpClient(e.to_string()))?;
+/// Fetch the latest METAR from the reporting station nearest to `position`.
+/// See [`WeatherClient::fetch_nearest_weather`].
+pub fn fetch_nearest_weather(position: &Coordinates) -> Result<(NearbyStation, MetarData)> {
+    WeatherClient::new()?.fetch_nearest_weather(position)
+}
 
-    let response_text = client
-        .get(&url)
-        .send()
-        .map_err(|e| WeatherError::Request(e.to_string()))?
-        .text()
-        .map_err(|e| WeatherError::Request(e.to_string()))?;
+/// Fetch the latest METAR for several stations in a single request.
+/// See [`WeatherClient::fetch_weather_batch`].
+pub fn fetch_weather_batch(icaos: &[&str]) -> Result<BTreeMap<String, Result<MetarData>>> {
+    WeatherClient::new()?.fetch_weather_batch(icaos)
+}
 
-    if response_text.is_empty() {
-        return Err(WeatherError::EmptyResponse(icao.to_string()));
-    }
+/// Fetch the latest METAR from every station in an area.
+/// See [`WeatherClient::fetch_area`].
+pub fn fetch_area(query: &MetarQuery) -> Result<Vec<MetarData>> {
+    WeatherClient::new()?.fetch_area(query)
+}
 
-    let response: Vec<MetarData> = serde_json::from_str(&response_text)
-        .map_err(|e| WeatherError::InvalidJson(format!("{}: {}", e, response_text)))?;
+/// Download a bulk METAR file to query locally.
+/// See [`WeatherClient::fetch_bulk_metars`].
+pub fn fetch_bulk_metars(url: &str) -> Result<BulkMetars> {
+    WeatherClient::new()?.fetch_bulk_metars(url)
+}
 
-    if response.is_empty() {
-        return Err(WeatherError::NoData(icao.to_uppercase()));
-    }
+/// Fetch every METAR issued for a station in the last `hours` hours, newest first
+pub fn fetch_weather_history(icao: &str, hours: u32) -> Result<Vec<MetarData>> {
+    WeatherClient::new()?.fetch_weather_history(icao, hours)
+}
 
-    response
-        .into_iter()
-        .next()
-        .ok_or_else(|| WeatherError::NoData(icao.to_uppercase()))
+/// Fetch the current terminal aerodrome forecast for a station
+pub fn fetch_taf(icao: &str) -> Result<TafData> {
+    WeatherClient::new()?.fetch_taf(icao)
+}
+
+/// Options controlling `display_weather` output
+#[derive(Debug, Clone, Default)]
+pub struct DisplayOptions {
+    /// Color the flight category with ANSI escape codes
+    pub color: bool,
+    pub units: Units,
 }
 
-pub fn display_weather(metar: &MetarData) {
-    println!("Raw METAR: {}", metar.raw_ob);
-    if let Some(ref name) = metar.name {
+pub fn display_weather(metar: &MetarData, options: &DisplayOptions) {
+    let units = &options.units;
+    // Reports decoded from IWXXM have no raw text
+    if !metar.raw_ob.is_empty() {
+        println!("Raw METAR: {}", metar.raw_ob);
+    }
+    if let Some(name) = metar.station_name() {
         println!("Station: {}", name);
     }
+    if let (Some(lat), Some(lon)) = (metar.lat, metar.lon) {
+        match metar.elev {
+            Some(elev) => println!(
+                "Location: {:.4}, {:.4} (elevation {})",
+                lat,
+                lon,
+                units.format_altitude(elev * FEET_PER_METER)
+            ),
+            None => println!("Location: {:.4}, {:.4}", lat, lon),
+        }
+    }
     println!();
 
-    if let Some(temp_c) = metar.temp {
-        let temp_f = celsius_to_fahrenheit(temp_c);
-        println!("Temperature: {:.1}°F ({:.1}°C)", temp_f, temp_c);
+    let observation = metar.observation().ok();
+
+    let report_type = metar
+        .metar_type
+        .clone()
+        .or_else(|| observation.as_ref().map(|o| o.report_type.to_string()));
+    if let Some(report_type) = report_type {
+        let mut report = report_type;
+        if let Some(obs) = observation {
+            if obs.auto {
+                report.push_str(", automated");
+            }
+            if obs.corrected {
+                report.push_str(", corrected");
+            }
+        }
+        println!("Report: {}", report);
+    }
+    if let Some(ref report_time) = metar.report_time {
+        println!("Report Time: {} UTC", report_time);
+    } else if let Some(obs) = observation {
+        println!("Report Time: {}", obs.time);
+    }
+
+    if let Some(temp_c) = metar.temperature() {
+        println!("Temperature: {}", units.format_temperature(temp_c));
     } else {
         println!("Temperature: Not available");
     }
 
+    if let Some(dewp_c) = metar.dewpoint() {
+        println!("Dewpoint: {}", units.format_temperature(dewp_c));
+    }
+
+    if let Some(wind) = metar.wind() {
+        println!("Wind: {}", units.format_wind(&wind));
+    }
+
+    if observation.as_ref().is_some_and(|o| o.cavok) {
+        println!("Visibility: CAVOK (ceiling and visibility OK)");
+    } else if let Some(vis) = metar.visibility() {
+        println!("Visibility: {}", units.format_visibility(&vis));
+    }
+    if let Some((vis, direction)) = observation
+        .as_ref()
+        .and_then(|o| o.minimum_visibility.as_ref())
+    {
+        println!(
+            "Minimum Visibility: {} to the {}",
+            units.format_visibility(vis),
+            direction
+        );
+    }
+
+    if let Some(obs) = observation {
+        for rvr in &obs.runway_visual_range {
+            println!("Runway Visual Range: {}", rvr);
+        }
+    }
+
+    let sky = metar.sky();
+    if !sky.is_empty() {
+        println!("Sky Condition:");
+        for layer in &sky {
+            println!("  - {}", units.format_cloud_layer(layer));
+        }
+    }
+    if let Some(vert_vis) = metar.vert_vis {
+        println!(
+            "Vertical Visibility: {}",
+            units.format_altitude(vert_vis as f64)
+        );
+    }
+
+    if let Some(altimeter) = metar.altimeter() {
+        println!("Altimeter: {}", units.format_pressure(altimeter.hpa()));
+    }
+    if let Some(slp) = metar.slp {
+        println!("Sea Level Pressure: {}", units.format_pressure_precise(slp));
+    }
+    if let Some(pres_tend) = metar.pres_tend {
+        println!(
+            "3-Hour Pressure Tendency: {:+.*} {}",
+            units.pressure.decimals().max(1),
+            units.pressure.from_hpa(pres_tend),
+            units.pressure.abbreviation()
+        );
+    }
+
+    let derived = metar.derived();
+    if let Some(rh) = derived.relative_humidity {
+        println!("Relative Humidity: {:.0}%", rh);
+    }
+    if let Some(spread) = derived.dewpoint_spread {
+        println!(
+            "Dewpoint Spread: {:.1}{}",
+            units.temperature.difference_from_celsius(spread),
+            units.temperature.symbol()
+        );
+    }
+    if let Some(hi) = derived.heat_index {
+        println!("Heat Index: {}", units.format_temperature(hi));
+    }
+    if let Some(wc) = derived.wind_chill {
+        println!("Wind Chill: {}", units.format_temperature(wc));
+    }
+    if let Some(base) = derived.cloud_base_estimate {
+        println!("Estimated Cloud Base: {} AGL", units.format_altitude(base));
+    }
+    if let Some(stn) = derived.station_pressure {
+        println!("Station Pressure: {}", units.format_pressure_precise(stn));
+    }
+    if let Some(pa) = derived.pressure_altitude {
+        println!("Pressure Altitude: {}", units.format_altitude(pa));
+    }
+    if let Some(da) = derived.density_altitude {
+        println!("Density Altitude: {}", units.format_altitude(da));
+    }
+
+    if let (Some(max_t), Some(min_t)) = (metar.max_t, metar.min_t) {
+        println!(
+            "6-Hour Max/Min Temperature: {} / {}",
+            units.format_temperature(max_t),
+            units.format_temperature(min_t)
+        );
+    }
+    if let (Some(max_t24), Some(min_t24)) = (metar.max_t24, metar.min_t24) {
+        println!(
+            "24-Hour Max/Min Temperature: {} / {}",
+            units.format_temperature(max_t24),
+            units.format_temperature(min_t24)
+        );
+    }
+    for (label, amount) in [
+        ("Precipitation (1 hour)", metar.precip),
+        ("Precipitation (3 hours)", metar.pcp3hr),
+        ("Precipitation (6 hours)", metar.pcp6hr),
+        ("Precipitation (24 hours)", metar.pcp24hr),
+        ("Snow Depth", metar.snow),
+    ] {
+        if let Some(amount) = amount {
+            println!("{}: {:.2} in", label, amount);
+        }
+    }
+
+    if let Some(category) = metar.flight_category() {
+        println!(
+            "Flight Category: {} ({})",
+            category.display_code(options.color),
+            category.description()
+        );
+    }
+
+    if let Some(obs) = observation {
+        for trend in &obs.trends {
+            println!("Trend: {}", trend);
+        }
+        if let Some(peak) = obs.coded_remarks().peak_wind {
+            println!("Peak Wind: {}", peak);
+        }
+        if let Some(ref remarks) = obs.remarks {
+            println!("Remarks: {}", remarks);
+        }
+    }
+
     if let Some(ref wx) = metar.wx_string {
         println!("Weather String: {}", wx);
+        let phenomena = parse_present_weather(wx);
+        if !phenomena.is_empty() {
+            println!("Present Weather:");
+            for phenomenon in &phenomena {
+                println!("  - {}", phenomenon);
+            }
+        }
         let codes = parse_wmo_codes(wx);
         if !codes.is_empty() {
             println!("WMO Codes Found:");
@@ -248,3 +849,199 @@ pub fn display_weather(metar: &MetarData) {
         println!("WMO Codes Found: None");
     }
 }
+
+/// Print a TAF, limited to the groups in effect during the next `hours` if given
+pub fn display_taf(taf_data: &TafData, hours: Option<u32>) {
+    println!("Raw TAF: {}", taf_data.raw_taf);
+    if let Some(name) = taf_data.station_name() {
+        println!("Station: {}", name);
+    }
+    if let Some(ref issue_time) = taf_data.issue_time {
+        println!("Issued: {} UTC", issue_time);
+    }
+    println!();
+
+    let taf = match taf_data.taf() {
+        Ok(taf) => taf,
+        Err(e) => {
+            println!("Decoded TAF: Not available ({})", e);
+            return;
+        }
+    };
+
+    println!("Valid: {}", taf.validity);
+    if taf.amended {
+        println!("Amended forecast");
+    }
+
+    let groups: Vec<&taf::ForecastGroup> = match hours {
+        Some(hours) => {
+            let now = chrono::Utc::now();
+            taf.groups_between(now, now + chrono::Duration::hours(hours as i64))
+        }
+        None => taf.groups.iter().collect(),
+    };
+    if groups.is_empty() {
+        println!("No forecast groups in effect for the requested period");
+    }
+
+    for group in groups {
+        println!();
+        println!("{}: {}", group.change, group.period);
+        if let Some(ref wind) = group.wind {
+            println!("  Wind: {}", wind);
+        }
+        if group.cavok {
+            println!("  Visibility: CAVOK (ceiling and visibility OK)");
+        } else if let Some(ref vis) = group.visibility {
+            println!("  Visibility: {}", vis);
+        }
+        for phenomenon in &group.weather {
+            println!("  Weather: {}", phenomenon);
+        }
+        if group.no_significant_weather {
+            println!("  Weather: No significant weather");
+        }
+        for layer in &group.sky {
+            println!("  Sky: {}", layer);
+        }
+        if let Some(ref wind_shear) = group.wind_shear {
+            println!("  Wind Shear: {}", wind_shear);
+        }
+    }
+
+    if let Some(ref remarks) = taf.remarks {
+        println!();
+        println!("Remarks: {}", remarks);
+    }
+}
+
+/// Print a table of observations (newest first) followed by the trend summary
+pub fn display_history(history: &[MetarData], options: &DisplayOptions) {
+    let units = &options.units;
+    if let Some(name) = history.first().and_then(MetarData::station_name) {
+        println!("Station: {}", name);
+        println!();
+    }
+
+    println!(
+        "{:<8} {:>7} {:>7} {:<30} {:>10} {:>10} {:>8}",
+        "Time", "Temp", "Dewpt", "Wind", "Vis", "Altimeter", "Ceiling"
+    );
+    for metar in history {
+        let obs = metar.observation().ok();
+        let time = obs.as_ref().map(|o| o.time.to_string()).unwrap_or_default();
+        let temp = metar
+            .temperature()
+            .map(|t| units.format_temperature(t))
+            .unwrap_or_default();
+        let dewp = metar
+            .dewpoint()
+            .map(|d| units.format_temperature(d))
+            .unwrap_or_default();
+        let wind = metar
+            .wind()
+            .map(|w| units.format_wind(&w))
+            .unwrap_or_default();
+        let vis = metar
+            .visibility()
+            .map(|v| units.format_visibility(&v))
+            .unwrap_or_default();
+        let altimeter = metar
+            .altimeter()
+            .map(|a| units.format_pressure(a.hpa()))
+            .unwrap_or_default();
+        let ceiling = metar
+            .ceiling()
+            .map(|c| units.format_altitude(c as f64))
+            .unwrap_or_else(|| "none".to_string());
+        println!(
+            "{:<8} {:>7} {:>7} {:<30} {:>10} {:>10} {:>8}",
+            time, temp, dewp, wind, vis, altimeter, ceiling
+        );
+    }
+
+    println!();
+    match analyze_trends(history) {
+        Some(report) => {
+            println!(
+                "Trends over {:.1} hours ({} observations):",
+                report.span_hours, report.observations
+            );
+            for line in report.describe(units) {
+                println!("  - {}", line);
+            }
+        }
+        None => println!("Trends: Not enough observations"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station_ids(text: &str) -> Vec<std::result::Result<String, MetarParseError>> {
+        parse_metar_text(text)
+            .into_iter()
+            .map(|report| report.map(|metar| metar.icao_id))
+            .collect()
+    }
+
+    #[test]
+    fn reads_one_report_per_line_skipping_blank_lines() {
+        let text = "KSFO 171756Z 29012KT 10SM FEW015 16/11 A3002\n\n  \n\
+                    KOAK 171753Z 00000KT 1/2SM FG VV002 12/12 A3000\r\n";
+        assert_eq!(
+            station_ids(text),
+            [Ok("KSFO".to_string()), Ok("KOAK".to_string())]
+        );
+    }
+
+    #[test]
+    fn reads_reports_terminated_by_equals_across_lines() {
+        let text = "EGLL 171750Z VRB02KT CAVOK\n  14/11 Q1009 NOSIG=\nLFPG 171730Z 24015KT 9999 FEW025 12/08 Q1002=\n";
+        let reports = parse_metar_text(text);
+        assert_eq!(reports.len(), 2);
+        let heathrow = reports[0].as_ref().unwrap();
+        assert_eq!(
+            heathrow.raw_ob,
+            "EGLL 171750Z VRB02KT CAVOK 14/11 Q1009 NOSIG"
+        );
+        assert_eq!(heathrow.temp, Some(14.0));
+        assert_eq!(reports[1].as_ref().unwrap().icao_id, "LFPG");
+    }
+
+    #[test]
+    fn keeps_good_reports_around_a_bad_one() {
+        let text = "KSFO 171756Z 29012KT 10SM FEW015 16/11 A3002\n\
+                    KSJC 1717Z 27015KT 10SM CLR 18/16 A2992\n\
+                    KSJC 171753Z 12ÉÉKT 10SM CLR 18/16 A2992\n\
+                    KPHX 171751Z 18005KT 10SM CLR 43/M02 A2990";
+        assert_eq!(
+            station_ids(text),
+            [
+                Ok("KSFO".to_string()),
+                Err(MetarParseError::InvalidTime("1717Z".to_string())),
+                Ok("KSJC".to_string()),
+                Ok("KPHX".to_string()),
+            ]
+        );
+        let reports = parse_metar_text(text);
+        let garbled = reports[2].as_ref().unwrap();
+        assert_eq!(garbled.observation().unwrap().unparsed, ["12ÉÉKT"]);
+        assert_eq!(garbled.temp, Some(18.0));
+    }
+
+    #[test]
+    fn reads_input_files() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"KSFO 171756Z 29012KT 10SM FEW015 16/11 A3002\n")
+            .unwrap();
+        let text = read_metar_input(file.path().to_str().unwrap()).unwrap();
+        assert_eq!(station_ids(&text), [Ok("KSFO".to_string())]);
+
+        let missing = read_metar_input("/nonexistent/metars.txt").unwrap_err();
+        assert!(matches!(missing, WeatherError::Io { .. }));
+        assert_eq!(missing.exit_code(), error::EXIT_FAILURE);
+    }
+}
@@ -0,0 +1,191 @@
+//! Positions on the Earth: parsing coordinates and Maidenhead grid locators,
+//! and great-circle distance and bearing between them.
+
+use std::fmt;
+use std::str::FromStr;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Cells per step of a Maidenhead locator: field, square, subsquare, extended square
+const MAIDENHEAD_DIVISIONS: [u32; 4] = [18, 10, 24, 10];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    /// Degrees north
+    pub lat: f64,
+    /// Degrees east
+    pub lon: f64,
+}
+
+impl Coordinates {
+    pub fn new(lat: f64, lon: f64) -> Option<Coordinates> {
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon))
+            .then_some(Coordinates { lat, lon })
+    }
+
+    /// Center of a Maidenhead grid square of 2 to 8 characters, e.g. "CM97" or "CM97bi"
+    pub fn from_maidenhead(locator: &str) -> Result<Coordinates, String> {
+        let chars: Vec<char> = locator.trim().to_ascii_uppercase().chars().collect();
+        if chars.is_empty() || !chars.len().is_multiple_of(2) || chars.len() > 8 {
+            return Err(format!(
+                "Invalid grid locator '{}': expected 2, 4, 6 or 8 characters",
+                locator
+            ));
+        }
+
+        let (mut lon, mut lat) = (-180.0, -90.0);
+        let (mut width, mut height) = (360.0, 180.0);
+        for (pair, divisions) in chars.chunks(2).zip(MAIDENHEAD_DIVISIONS) {
+            // Fields and subsquares are letters, squares digits
+            let base = if divisions == 10 { '0' } else { 'A' };
+            let index = |c: char| {
+                (c as u32)
+                    .checked_sub(base as u32)
+                    .filter(|i| *i < divisions)
+                    .ok_or_else(|| format!("Invalid grid locator '{}'", locator))
+            };
+            width /= divisions as f64;
+            height /= divisions as f64;
+            lon += index(pair[0])? as f64 * width;
+            lat += index(pair[1])? as f64 * height;
+        }
+        Ok(Coordinates {
+            lat: lat + height / 2.0,
+            lon: lon + width / 2.0,
+        })
+    }
+
+    /// Great-circle distance in kilometers
+    pub fn distance_km(&self, other: &Coordinates) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.lon - self.lon).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+
+    /// Initial true bearing to `other` in degrees, 0 to 360
+    pub fn bearing_to(&self, other: &Coordinates) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlon = (other.lon - self.lon).to_radians();
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+}
+
+/// Parses "lat,lon" in decimal degrees, e.g. "37.33,-121.89"
+impl FromStr for Coordinates {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid coordinates '{}': expected LAT,LON", s);
+        let (lat, lon) = s.split_once(',').ok_or_else(invalid)?;
+        let lat = lat.trim().parse().map_err(|_| invalid())?;
+        let lon = lon.trim().parse().map_err(|_| invalid())?;
+        Coordinates::new(lat, lon).ok_or_else(|| format!("Coordinates '{}' are out of range", s))
+    }
+}
+
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.4}, {:.4}", self.lat, self.lon)
+    }
+}
+
+/// One of 16 compass points for a bearing in degrees, e.g. "NNE"
+pub fn compass_point(bearing: f64) -> &'static str {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+    let index = (bearing.rem_euclid(360.0) / 22.5).round() as usize % 16;
+    POINTS[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetarData;
+    use crate::stations::nearest_reports;
+
+    const KSFO: Coordinates = Coordinates {
+        lat: 37.6196,
+        lon: -122.3656,
+    };
+    const KLAX: Coordinates = Coordinates {
+        lat: 33.9382,
+        lon: -118.3866,
+    };
+
+    #[test]
+    fn parses_coordinates() {
+        assert_eq!(
+            "37.33, -121.89".parse(),
+            Ok(Coordinates {
+                lat: 37.33,
+                lon: -121.89
+            })
+        );
+        assert!("37.33".parse::<Coordinates>().is_err());
+        assert!("north,west".parse::<Coordinates>().is_err());
+        assert!("91,0".parse::<Coordinates>().is_err());
+        assert!("0,-181".parse::<Coordinates>().is_err());
+    }
+
+    #[test]
+    fn decodes_maidenhead_locators() {
+        let square = Coordinates::from_maidenhead("CM97").unwrap();
+        assert!((square.lat - 37.5).abs() < 1e-9 && (square.lon + 121.0).abs() < 1e-9);
+        let subsquare = Coordinates::from_maidenhead("cm97bi").unwrap();
+        assert!((subsquare.lat - 37.354_167).abs() < 1e-6);
+        assert!((subsquare.lon + 121.875).abs() < 1e-6);
+        assert!(Coordinates::from_maidenhead("CM9").is_err());
+        assert!(Coordinates::from_maidenhead("SM97").is_err());
+        assert!(Coordinates::from_maidenhead("CMA7").is_err());
+    }
+
+    #[test]
+    fn measures_distance_and_bearing() {
+        assert!((KSFO.distance_km(&KLAX) - 544.3).abs() < 0.1);
+        assert!((KSFO.bearing_to(&KLAX) - 137.6).abs() < 0.1);
+        // Great circles bend, so the way back is not the reciprocal
+        assert!((KLAX.bearing_to(&KSFO) - 319.9).abs() < 0.1);
+        assert_eq!(KSFO.distance_km(&KSFO), 0.0);
+
+        let jfk = Coordinates::new(40.6398, -73.7789).unwrap();
+        let lhr = Coordinates::new(51.4706, -0.4619).unwrap();
+        assert!((jfk.distance_km(&lhr) - 5539.6).abs() < 0.1);
+        assert_eq!(compass_point(jfk.bearing_to(&lhr)), "NE");
+    }
+
+    #[test]
+    fn names_compass_points() {
+        assert_eq!(compass_point(0.0), "N");
+        assert_eq!(compass_point(11.0), "N");
+        assert_eq!(compass_point(12.0), "NNE");
+        assert_eq!(compass_point(137.6), "SE");
+        assert_eq!(compass_point(359.0), "N");
+        assert_eq!(compass_point(-90.0), "W");
+    }
+
+    #[test]
+    fn orders_reports_by_distance() {
+        let report = |raw: &str, position: Option<Coordinates>| MetarData {
+            lat: position.map(|p| p.lat),
+            lon: position.map(|p| p.lon),
+            ..MetarData::from_raw(raw).unwrap()
+        };
+        let reports = [
+            report("KLAX 171753Z 25008KT 10SM SKC 24/12 A2999", Some(KLAX)),
+            report("KSFO 171756Z 28012KT 10SM FEW008 18/12 A3000", Some(KSFO)),
+            report("KOAK 171753Z 29010KT 10SM CLR 19/11 A3000", None),
+        ];
+        let san_jose = Coordinates::new(37.33, -121.89).unwrap();
+        let nearby = nearest_reports(&san_jose, &reports, 5);
+        let ids: Vec<&str> = nearby.iter().map(|(s, _)| s.icao.as_str()).collect();
+        assert_eq!(ids, ["KSFO", "KLAX"]);
+        assert_eq!(compass_point(nearby[0].0.bearing), "NW");
+        assert_eq!(nearest_reports(&san_jose, &reports, 1).len(), 1);
+    }
+}
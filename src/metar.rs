@@ -0,0 +1,1334 @@
+//! Decoder for raw METAR/SPECI reports.
+//!
+//! This works purely on the report text, so it can be used on data from any
+//! source, not just the aviationweather.gov JSON API.
+
+use crate::WeatherPhenomenon;
+use crate::units::{
+    HPA_PER_INHG, KMH_PER_KNOT, METERS_PER_FOOT, METERS_PER_STATUTE_MILE, MS_PER_KNOT,
+    format_number,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetarParseError {
+    Empty,
+    MissingStation,
+    InvalidStation(String),
+    MissingTime,
+    InvalidTime(String),
+    NilReport(String),
+}
+
+impl fmt::Display for MetarParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetarParseError::Empty => write!(f, "METAR report is empty"),
+            MetarParseError::MissingStation => write!(f, "METAR report has no station identifier"),
+            MetarParseError::InvalidStation(s) => write!(f, "Invalid station identifier: {}", s),
+            MetarParseError::MissingTime => write!(f, "METAR report has no issue time"),
+            MetarParseError::InvalidTime(s) => write!(f, "Invalid issue time: {}", s),
+            MetarParseError::NilReport(s) => write!(f, "NIL report for station {}", s),
+        }
+    }
+}
+
+impl std::error::Error for MetarParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportType {
+    Metar,
+    Speci,
+}
+
+impl fmt::Display for ReportType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportType::Metar => write!(f, "METAR"),
+            ReportType::Speci => write!(f, "SPECI"),
+        }
+    }
+}
+
+/// Day of month and UTC time, as in the `DDHHMMZ` issue time group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DayTime {
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl DayTime {
+    /// Place this day/time in the month nearest to `reference`.
+    /// Reports only carry the day of month, so the month and year are inferred.
+    pub fn resolve(&self, reference: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (-1i32..=1)
+            .filter_map(|offset| {
+                let months = reference.year() * 12 + reference.month0() as i32 + offset;
+                let date = NaiveDate::from_ymd_opt(
+                    months.div_euclid(12),
+                    months.rem_euclid(12) as u32 + 1,
+                    self.day as u32,
+                )?;
+                // Hour 24 is used for the end of a day in forecast periods
+                let time = date.and_hms_opt(0, 0, 0)?
+                    + Duration::hours(self.hour as i64)
+                    + Duration::minutes(self.minute as i64);
+                Some(time.and_utc())
+            })
+            .min_by_key(|time| (*time - reference).num_seconds().abs())
+    }
+}
+
+impl fmt::Display for DayTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}{:02}{:02}Z", self.day, self.hour, self.minute)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeedUnit {
+    Knots,
+    MetersPerSecond,
+    KilometersPerHour,
+}
+
+impl SpeedUnit {
+    pub fn abbreviation(&self) -> &str {
+        match self {
+            SpeedUnit::Knots => "kt",
+            SpeedUnit::MetersPerSecond => "m/s",
+            SpeedUnit::KilometersPerHour => "km/h",
+        }
+    }
+
+    fn to_knots(self, value: f64) -> f64 {
+        match self {
+            SpeedUnit::Knots => value,
+            SpeedUnit::MetersPerSecond => value / MS_PER_KNOT,
+            SpeedUnit::KilometersPerHour => value / KMH_PER_KNOT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Wind {
+    /// Direction the wind is blowing from in degrees true; `None` for VRB
+    pub direction: Option<u16>,
+    pub speed: u16,
+    pub gust: Option<u16>,
+    pub unit: SpeedUnit,
+    /// Extremes of a variable direction group such as `240V300`
+    pub variable_sector: Option<(u16, u16)>,
+}
+
+impl Wind {
+    pub fn is_calm(&self) -> bool {
+        self.speed == 0 && self.gust.is_none()
+    }
+
+    pub fn is_variable(&self) -> bool {
+        self.direction.is_none()
+    }
+
+    pub fn speed_knots(&self) -> f64 {
+        self.unit.to_knots(self.speed as f64)
+    }
+
+    pub fn gust_knots(&self) -> Option<f64> {
+        self.gust.map(|g| self.unit.to_knots(g as f64))
+    }
+}
+
+impl fmt::Display for Wind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_calm() {
+            return write!(f, "Calm");
+        }
+        match self.direction {
+            Some(dir) => write!(f, "{:03}°", dir)?,
+            None => write!(f, "Variable")?,
+        }
+        write!(f, " at {} {}", self.speed, self.unit.abbreviation())?;
+        if let Some(gust) = self.gust {
+            write!(f, " gusting {} {}", gust, self.unit.abbreviation())?;
+        }
+        if let Some((from, to)) = self.variable_sector {
+            write!(f, ", variable {:03}°-{:03}°", from, to)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceUnit {
+    StatuteMiles,
+    Meters,
+    Feet,
+}
+
+impl DistanceUnit {
+    pub fn abbreviation(&self) -> &str {
+        match self {
+            DistanceUnit::StatuteMiles => "SM",
+            DistanceUnit::Meters => "m",
+            DistanceUnit::Feet => "ft",
+        }
+    }
+}
+
+/// `M` (less than) and `P` (greater than) prefixes on visibility and RVR values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bound {
+    LessThan,
+    GreaterThan,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Visibility {
+    pub distance: f64,
+    pub unit: DistanceUnit,
+    pub bound: Option<Bound>,
+}
+
+impl Visibility {
+    pub fn statute_miles(&self) -> f64 {
+        match self.unit {
+            DistanceUnit::StatuteMiles => self.distance,
+            DistanceUnit::Meters => self.distance / METERS_PER_STATUTE_MILE,
+            DistanceUnit::Feet => self.distance * METERS_PER_FOOT / METERS_PER_STATUTE_MILE,
+        }
+    }
+
+    pub fn meters(&self) -> f64 {
+        match self.unit {
+            DistanceUnit::StatuteMiles => self.distance * METERS_PER_STATUTE_MILE,
+            DistanceUnit::Meters => self.distance,
+            DistanceUnit::Feet => self.distance * METERS_PER_FOOT,
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bound {
+            Some(Bound::LessThan) => write!(f, "less than ")?,
+            Some(Bound::GreaterThan) => write!(f, "more than ")?,
+            None => {}
+        }
+        write!(
+            f,
+            "{} {}",
+            format_number(self.distance),
+            self.unit.abbreviation()
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RvrTendency {
+    Upward,
+    Downward,
+    NoChange,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunwayVisualRange {
+    pub runway: String,
+    pub bound: Option<Bound>,
+    pub range: u32,
+    /// Upper end of a variable range such as `R28L/1200V1800FT`
+    pub variable_max: Option<u32>,
+    pub unit: DistanceUnit,
+    pub tendency: Option<RvrTendency>,
+}
+
+impl fmt::Display for RunwayVisualRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Runway {} ", self.runway)?;
+        match self.bound {
+            Some(Bound::LessThan) => write!(f, "less than ")?,
+            Some(Bound::GreaterThan) => write!(f, "more than ")?,
+            None => {}
+        }
+        write!(f, "{}", self.range)?;
+        if let Some(max) = self.variable_max {
+            write!(f, " to {}", max)?;
+        }
+        write!(f, " {}", self.unit.abbreviation())?;
+        match self.tendency {
+            Some(RvrTendency::Upward) => write!(f, ", increasing"),
+            Some(RvrTendency::Downward) => write!(f, ", decreasing"),
+            Some(RvrTendency::NoChange) => write!(f, ", no change"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloudCover {
+    /// SKC, reported by a human observer
+    SkyClear,
+    /// CLR, no clouds below 12,000 ft reported by an automated station
+    Clear,
+    /// NSC
+    NoSignificantCloud,
+    /// NCD
+    NoCloudDetected,
+    Few,
+    Scattered,
+    Broken,
+    Overcast,
+    /// VV, indefinite ceiling with vertical visibility
+    VerticalVisibility,
+}
+
+impl CloudCover {
+    pub fn code(&self) -> &str {
+        match self {
+            CloudCover::SkyClear => "SKC",
+            CloudCover::Clear => "CLR",
+            CloudCover::NoSignificantCloud => "NSC",
+            CloudCover::NoCloudDetected => "NCD",
+            CloudCover::Few => "FEW",
+            CloudCover::Scattered => "SCT",
+            CloudCover::Broken => "BKN",
+            CloudCover::Overcast => "OVC",
+            CloudCover::VerticalVisibility => "VV",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            CloudCover::SkyClear | CloudCover::Clear => "Clear",
+            CloudCover::NoSignificantCloud => "No significant cloud",
+            CloudCover::NoCloudDetected => "No cloud detected",
+            CloudCover::Few => "Few",
+            CloudCover::Scattered => "Scattered",
+            CloudCover::Broken => "Broken",
+            CloudCover::Overcast => "Overcast",
+            CloudCover::VerticalVisibility => "Vertical visibility",
+        }
+    }
+
+    /// Broken, overcast and vertical visibility layers constitute a ceiling
+    pub fn is_ceiling(&self) -> bool {
+        matches!(
+            self,
+            CloudCover::Broken | CloudCover::Overcast | CloudCover::VerticalVisibility
+        )
+    }
+
+    pub(crate) fn from_code(code: &str) -> Option<CloudCover> {
+        match code {
+            "SKC" => Some(CloudCover::SkyClear),
+            "CLR" => Some(CloudCover::Clear),
+            "NSC" => Some(CloudCover::NoSignificantCloud),
+            "NCD" => Some(CloudCover::NoCloudDetected),
+            "FEW" => Some(CloudCover::Few),
+            "SCT" => Some(CloudCover::Scattered),
+            "BKN" => Some(CloudCover::Broken),
+            "OVC" => Some(CloudCover::Overcast),
+            "VV" => Some(CloudCover::VerticalVisibility),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloudType {
+    Cumulonimbus,
+    ToweringCumulus,
+}
+
+impl CloudType {
+    pub fn code(&self) -> &str {
+        match self {
+            CloudType::Cumulonimbus => "CB",
+            CloudType::ToweringCumulus => "TCU",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            CloudType::Cumulonimbus => "Cumulonimbus",
+            CloudType::ToweringCumulus => "Towering cumulus",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CloudLayer {
+    pub cover: CloudCover,
+    /// Height of the layer base in feet above ground level
+    pub base: Option<u32>,
+    pub cloud_type: Option<CloudType>,
+}
+
+impl fmt::Display for CloudLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cover.description())?;
+        if let Some(base) = self.base {
+            write!(f, " at {} ft", base)?;
+        }
+        if let Some(cloud_type) = self.cloud_type {
+            write!(f, " ({})", cloud_type.description())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Altimeter {
+    /// `A` group, inches of mercury
+    InchesOfMercury(f64),
+    /// `Q` group, QNH in hectopascals
+    Hectopascals(f64),
+}
+
+impl Altimeter {
+    pub fn in_hg(&self) -> f64 {
+        match *self {
+            Altimeter::InchesOfMercury(v) => v,
+            Altimeter::Hectopascals(v) => v / HPA_PER_INHG,
+        }
+    }
+
+    pub fn hpa(&self) -> f64 {
+        match *self {
+            Altimeter::InchesOfMercury(v) => v * HPA_PER_INHG,
+            Altimeter::Hectopascals(v) => v,
+        }
+    }
+}
+
+impl fmt::Display for Altimeter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Altimeter::InchesOfMercury(v) => write!(f, "{:.2} inHg", v),
+            Altimeter::Hectopascals(v) => write!(f, "{:.0} hPa", v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trend {
+    /// NOSIG
+    NoSignificantChange,
+    /// BECMG followed by the forecast groups
+    Becoming(String),
+    /// TEMPO followed by the forecast groups
+    Temporary(String),
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trend::NoSignificantChange => write!(f, "No significant change"),
+            Trend::Becoming(groups) => write!(f, "Becoming {}", groups),
+            Trend::Temporary(groups) => write!(f, "Temporarily {}", groups),
+        }
+    }
+}
+
+/// A decoded METAR or SPECI report
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Observation {
+    pub report_type: ReportType,
+    pub station: String,
+    pub time: DayTime,
+    /// Fully automated report with no human intervention
+    pub auto: bool,
+    /// Corrected report (COR)
+    pub corrected: bool,
+    pub wind: Option<Wind>,
+    pub visibility: Option<Visibility>,
+    /// Lowest visibility and the direction it is toward, when reported
+    /// separately from the prevailing visibility, e.g. `1500SW`
+    pub minimum_visibility: Option<(Visibility, String)>,
+    /// Ceiling and visibility OK
+    pub cavok: bool,
+    pub runway_visual_range: Vec<RunwayVisualRange>,
+    /// Present and recent weather groups such as `-RA` or `VCTS`
+    pub weather: Vec<WeatherPhenomenon>,
+    pub sky: Vec<CloudLayer>,
+    /// Air temperature in degrees Celsius
+    pub temperature: Option<i32>,
+    /// Dewpoint in degrees Celsius
+    pub dewpoint: Option<i32>,
+    pub altimeter: Option<Altimeter>,
+    pub trends: Vec<Trend>,
+    /// Everything following RMK
+    pub remarks: Option<String>,
+    /// Body groups the decoder did not recognize
+    pub unparsed: Vec<String>,
+}
+
+impl Observation {
+    pub fn parse(raw: &str) -> Result<Observation, MetarParseError> {
+        let mut tokens = raw
+            .split_whitespace()
+            .map(|t| t.trim_end_matches('='))
+            .filter(|t| !t.is_empty())
+            .peekable();
+
+        if tokens.peek().is_none() {
+            return Err(MetarParseError::Empty);
+        }
+
+        let mut report_type = ReportType::Metar;
+        let mut corrected = false;
+        match tokens.peek() {
+            Some(&"METAR") => {
+                tokens.next();
+            }
+            Some(&"SPECI") => {
+                report_type = ReportType::Speci;
+                tokens.next();
+            }
+            _ => {}
+        }
+        if tokens.peek() == Some(&"COR") {
+            corrected = true;
+            tokens.next();
+        }
+
+        let station = tokens.next().ok_or(MetarParseError::MissingStation)?;
+        if !is_station(station) {
+            return Err(MetarParseError::InvalidStation(station.to_string()));
+        }
+
+        let time_token = tokens.next().ok_or(MetarParseError::MissingTime)?;
+        if time_token == "NIL" {
+            return Err(MetarParseError::NilReport(station.to_string()));
+        }
+        let time = parse_day_time(time_token)
+            .ok_or_else(|| MetarParseError::InvalidTime(time_token.to_string()))?;
+
+        let mut obs = Observation {
+            report_type,
+            station: station.to_string(),
+            time,
+            auto: false,
+            corrected,
+            wind: None,
+            visibility: None,
+            minimum_visibility: None,
+            cavok: false,
+            runway_visual_range: Vec::new(),
+            weather: Vec::new(),
+            sky: Vec::new(),
+            temperature: None,
+            dewpoint: None,
+            altimeter: None,
+            trends: Vec::new(),
+            remarks: None,
+            unparsed: Vec::new(),
+        };
+
+        let rest: Vec<&str> = tokens.collect();
+        let mut i = 0;
+        while i < rest.len() {
+            let token = rest[i];
+            match token {
+                "NIL" => return Err(MetarParseError::NilReport(obs.station)),
+                "AUTO" => obs.auto = true,
+                "COR" => obs.corrected = true,
+                "CAVOK" => obs.cavok = true,
+                "RMK" => {
+                    let remarks = rest[i + 1..].join(" ");
+                    if !remarks.is_empty() {
+                        obs.remarks = Some(remarks);
+                    }
+                    break;
+                }
+                "NOSIG" => obs.trends.push(Trend::NoSignificantChange),
+                "BECMG" | "TEMPO" => {
+                    let end = rest[i + 1..]
+                        .iter()
+                        .position(|t| matches!(*t, "BECMG" | "TEMPO" | "NOSIG" | "RMK"))
+                        .map(|p| i + 1 + p)
+                        .unwrap_or(rest.len());
+                    let groups = rest[i + 1..end].join(" ");
+                    obs.trends.push(if token == "BECMG" {
+                        Trend::Becoming(groups)
+                    } else {
+                        Trend::Temporary(groups)
+                    });
+                    i = end;
+                    continue;
+                }
+                _ => {
+                    if obs.wind.is_none()
+                        && let Some(wind) = parse_wind(token)
+                    {
+                        obs.wind = Some(wind);
+                    } else if let Some(sector) = parse_variable_sector(token)
+                        && let Some(ref mut wind) = obs.wind
+                    {
+                        wind.variable_sector = Some(sector);
+                    } else if obs.visibility.is_none()
+                        && i + 1 < rest.len()
+                        && is_whole_number(token)
+                        && let Some(mut vis) = parse_visibility(rest[i + 1])
+                        && rest[i + 1].contains('/')
+                    {
+                        // Mixed fraction split across two tokens, e.g. "1 1/2SM"
+                        vis.distance += token.parse::<f64>().unwrap_or(0.0);
+                        obs.visibility = Some(vis);
+                        i += 1;
+                    } else if obs.visibility.is_none()
+                        && let Some(vis) = parse_visibility(token)
+                    {
+                        obs.visibility = Some(vis);
+                    } else if obs.minimum_visibility.is_none()
+                        && let Some(minimum) = parse_directional_visibility(token)
+                    {
+                        obs.minimum_visibility = Some(minimum);
+                    } else if let Some(rvr) = parse_rvr(token) {
+                        obs.runway_visual_range.push(rvr);
+                    } else if let Some(phenomenon) = WeatherPhenomenon::parse(token) {
+                        obs.weather.push(phenomenon);
+                    } else if let Some(layer) = parse_cloud_layer(token) {
+                        obs.sky.push(layer);
+                    } else if obs.temperature.is_none()
+                        && obs.dewpoint.is_none()
+                        && let Some((temp, dewp)) = parse_temperature(token)
+                    {
+                        obs.temperature = temp;
+                        obs.dewpoint = dewp;
+                    } else if obs.altimeter.is_none()
+                        && let Some(altim) = parse_altimeter(token)
+                    {
+                        obs.altimeter = Some(altim);
+                    } else {
+                        obs.unparsed.push(token.to_string());
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        Ok(obs)
+    }
+
+    /// Height in feet of the lowest broken, overcast or vertical visibility layer
+    pub fn ceiling(&self) -> Option<u32> {
+        self.sky
+            .iter()
+            .filter(|layer| layer.cover.is_ceiling())
+            .filter_map(|layer| layer.base)
+            .min()
+    }
+
+    /// The coded groups in the remarks, decoded
+    pub fn coded_remarks(&self) -> Remarks {
+        self.remarks
+            .as_deref()
+            .map(Remarks::parse)
+            .unwrap_or_default()
+    }
+}
+
+/// Highest gust since the last routine report, from `PK WND 28045/1732`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeakWind {
+    /// Degrees true
+    pub direction: u16,
+    /// Knots
+    pub speed: u16,
+    /// Omitted in the report when it is the hour of the observation
+    pub hour: Option<u8>,
+    pub minute: u8,
+}
+
+impl fmt::Display for PeakWind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:03}° at {} kt at ", self.direction, self.speed)?;
+        match self.hour {
+            Some(hour) => write!(f, "{:02}{:02}Z", hour, self.minute),
+            None => write!(f, "minute {:02}", self.minute),
+        }
+    }
+}
+
+/// The coded remarks of a US METAR that carry values not in the report body
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Remarks {
+    /// AO1 (no precipitation sensor) or AO2
+    pub station_type: Option<String>,
+    /// Sea level pressure in hectopascals, from `SLP123`
+    pub sea_level_pressure: Option<f64>,
+    /// Temperature in degrees Celsius to a tenth, from `T01220083`
+    pub temperature: Option<f64>,
+    /// Dewpoint in degrees Celsius to a tenth
+    pub dewpoint: Option<f64>,
+    pub peak_wind: Option<PeakWind>,
+}
+
+impl Remarks {
+    /// Decode the groups that follow RMK; anything else is ignored
+    pub fn parse(text: &str) -> Remarks {
+        let mut remarks = Remarks::default();
+        let groups: Vec<&str> = text.split_whitespace().collect();
+        let mut i = 0;
+        while i < groups.len() {
+            let group = groups[i];
+            if matches!(group, "AO1" | "AO2") {
+                remarks.station_type = Some(group.to_string());
+            } else if group == "PK"
+                && groups.get(i + 1) == Some(&"WND")
+                && let Some(peak) = groups.get(i + 2).and_then(|g| parse_peak_wind(g))
+            {
+                remarks.peak_wind = Some(peak);
+                i += 2;
+            } else if let Some(pressure) =
+                group.strip_prefix("SLP").and_then(parse_sea_level_pressure)
+            {
+                remarks.sea_level_pressure = Some(pressure);
+            } else if let Some((temp, dewp)) = parse_precise_temperature(group) {
+                remarks.temperature = Some(temp);
+                remarks.dewpoint = dewp;
+            }
+            i += 1;
+        }
+        remarks
+    }
+}
+
+impl FromStr for Observation {
+    type Err = MetarParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Observation::parse(s)
+    }
+}
+
+pub(crate) fn all_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_whole_number(s: &str) -> bool {
+    all_digits(s) && s.len() <= 2
+}
+
+pub(crate) fn is_station(s: &str) -> bool {
+    s.len() == 4
+        && s.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && s.chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+pub(crate) fn parse_day_time(s: &str) -> Option<DayTime> {
+    let digits = s.strip_suffix('Z')?;
+    if digits.len() != 6 || !all_digits(digits) {
+        return None;
+    }
+    let time = DayTime {
+        day: digits[0..2].parse().ok()?,
+        hour: digits[2..4].parse().ok()?,
+        minute: digits[4..6].parse().ok()?,
+    };
+    if time.day == 0 || time.day > 31 || time.hour > 24 || time.minute > 59 {
+        return None;
+    }
+    Some(time)
+}
+
+pub(crate) fn parse_wind(s: &str) -> Option<Wind> {
+    let (body, unit) = if let Some(b) = s.strip_suffix("KT") {
+        (b, SpeedUnit::Knots)
+    } else if let Some(b) = s.strip_suffix("MPS") {
+        (b, SpeedUnit::MetersPerSecond)
+    } else if let Some(b) = s.strip_suffix("KMH") {
+        (b, SpeedUnit::KilometersPerHour)
+    } else {
+        return None;
+    };
+
+    let (dir, rest) = (body.get(..3)?, body.get(3..)?);
+    if rest.len() < 2 {
+        return None;
+    }
+    let direction = match dir {
+        "VRB" => None,
+        d if all_digits(d) => Some(d.parse().ok()?),
+        _ => return None,
+    };
+
+    let (speed_str, gust_str) = match rest.split_once('G') {
+        Some((speed, gust)) => (speed, Some(gust)),
+        None => (rest, None),
+    };
+    let speed_str = speed_str.trim_start_matches('P');
+    if !all_digits(speed_str) {
+        return None;
+    }
+    let gust = match gust_str {
+        Some(g) => {
+            let g = g.trim_start_matches('P');
+            if !all_digits(g) {
+                return None;
+            }
+            Some(g.parse().ok()?)
+        }
+        None => None,
+    };
+
+    Some(Wind {
+        direction,
+        speed: speed_str.parse().ok()?,
+        gust,
+        unit,
+        variable_sector: None,
+    })
+}
+
+pub(crate) fn parse_variable_sector(s: &str) -> Option<(u16, u16)> {
+    let (from, to) = s.split_once('V')?;
+    if from.len() != 3 || to.len() != 3 || !all_digits(from) || !all_digits(to) {
+        return None;
+    }
+    Some((from.parse().ok()?, to.parse().ok()?))
+}
+
+fn parse_fraction(s: &str) -> Option<f64> {
+    match s.split_once('/') {
+        Some((num, den)) => {
+            if !all_digits(num) || !all_digits(den) {
+                return None;
+            }
+            let den: f64 = den.parse().ok()?;
+            if den == 0.0 {
+                return None;
+            }
+            Some(num.parse::<f64>().ok()? / den)
+        }
+        None if all_digits(s) => s.parse().ok(),
+        None => None,
+    }
+}
+
+pub(crate) fn parse_visibility(s: &str) -> Option<Visibility> {
+    if let Some(body) = s.strip_suffix("SM") {
+        let (bound, body) = if let Some(b) = body.strip_prefix('M') {
+            (Some(Bound::LessThan), b)
+        } else if let Some(b) = body.strip_prefix('P') {
+            (Some(Bound::GreaterThan), b)
+        } else {
+            (None, body)
+        };
+        return Some(Visibility {
+            distance: parse_fraction(body)?,
+            unit: DistanceUnit::StatuteMiles,
+            bound,
+        });
+    }
+
+    // Metric visibility, optionally followed by NDV (no directional variation)
+    let digits = s.get(0..4)?;
+    let suffix = &s[4..];
+    if !all_digits(digits) || !(suffix.is_empty() || suffix == "NDV") {
+        return None;
+    }
+    let meters: f64 = digits.parse().ok()?;
+    Some(if meters >= 9999.0 {
+        Visibility {
+            distance: 10000.0,
+            unit: DistanceUnit::Meters,
+            bound: Some(Bound::GreaterThan),
+        }
+    } else {
+        Visibility {
+            distance: meters,
+            unit: DistanceUnit::Meters,
+            bound: None,
+        }
+    })
+}
+
+/// Metric visibility toward one direction, e.g. `1500SW`
+fn parse_directional_visibility(s: &str) -> Option<(Visibility, String)> {
+    const DIRECTIONS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    let direction = s.get(4..)?;
+    if !DIRECTIONS.contains(&direction) {
+        return None;
+    }
+    Some((parse_visibility(&s[0..4])?, direction.to_string()))
+}
+
+fn parse_rvr(s: &str) -> Option<RunwayVisualRange> {
+    let body = s.strip_prefix('R')?;
+    let (runway, value) = body.split_once('/')?;
+    if !runway.get(0..2).is_some_and(all_digits) {
+        return None;
+    }
+
+    // The tendency follows the value directly, e.g. `R27/0600U`, or after a slash
+    let tendency = match value.chars().last() {
+        Some('U') => Some(RvrTendency::Upward),
+        Some('D') => Some(RvrTendency::Downward),
+        Some('N') => Some(RvrTendency::NoChange),
+        _ => None,
+    };
+    let value = match tendency {
+        Some(_) => value[..value.len() - 1].trim_end_matches('/'),
+        None => value,
+    };
+    let (value, unit) = match value.strip_suffix("FT") {
+        Some(v) => (v, DistanceUnit::Feet),
+        None => (value, DistanceUnit::Meters),
+    };
+    let (bound, value) = if let Some(v) = value.strip_prefix('M') {
+        (Some(Bound::LessThan), v)
+    } else if let Some(v) = value.strip_prefix('P') {
+        (Some(Bound::GreaterThan), v)
+    } else {
+        (None, value)
+    };
+    let (range, variable_max) = match value.split_once('V') {
+        Some((min, max)) => {
+            let max = max.trim_start_matches(['M', 'P']);
+            if !all_digits(min) || !all_digits(max) {
+                return None;
+            }
+            (min.parse().ok()?, Some(max.parse().ok()?))
+        }
+        None if all_digits(value) => (value.parse().ok()?, None),
+        None => return None,
+    };
+
+    Some(RunwayVisualRange {
+        runway: runway.to_string(),
+        bound,
+        range,
+        variable_max,
+        unit,
+        tendency,
+    })
+}
+
+pub(crate) fn parse_cloud_layer(s: &str) -> Option<CloudLayer> {
+    if matches!(s, "SKC" | "CLR" | "NSC" | "NCD") {
+        return Some(CloudLayer {
+            cover: CloudCover::from_code(s)?,
+            base: None,
+            cloud_type: None,
+        });
+    }
+
+    let split = if s.starts_with("VV") { 2 } else { 3 };
+    let cover = CloudCover::from_code(s.get(0..split)?)?;
+    if !matches!(
+        cover,
+        CloudCover::Few
+            | CloudCover::Scattered
+            | CloudCover::Broken
+            | CloudCover::Overcast
+            | CloudCover::VerticalVisibility
+    ) {
+        return None;
+    }
+    let height = s.get(split..split + 3)?;
+    let base = if all_digits(height) {
+        Some(height.parse::<u32>().ok()? * 100)
+    } else if height == "///" {
+        None
+    } else {
+        return None;
+    };
+    let cloud_type = match &s[split + 3..] {
+        "" | "///" => None,
+        "CB" => Some(CloudType::Cumulonimbus),
+        "TCU" => Some(CloudType::ToweringCumulus),
+        _ => return None,
+    };
+
+    Some(CloudLayer {
+        cover,
+        base,
+        cloud_type,
+    })
+}
+
+fn parse_signed_temperature(s: &str) -> Option<Option<i32>> {
+    if s.is_empty() || s.chars().all(|c| c == '/') {
+        return Some(None);
+    }
+    let (negative, digits) = match s.strip_prefix('M') {
+        Some(d) => (true, d),
+        None => (false, s),
+    };
+    if digits.len() != 2 || !all_digits(digits) {
+        return None;
+    }
+    let value: i32 = digits.parse().ok()?;
+    Some(Some(if negative { -value } else { value }))
+}
+
+fn parse_temperature(s: &str) -> Option<(Option<i32>, Option<i32>)> {
+    let (temp, dewp) = s.split_once('/')?;
+    let temp = parse_signed_temperature(temp)?;
+    let dewp = parse_signed_temperature(dewp)?;
+    if temp.is_none() && dewp.is_none() {
+        return None;
+    }
+    Some((temp, dewp))
+}
+
+/// `SLPppp`: tenths of a hectopascal, without the leading 9 or 10
+fn parse_sea_level_pressure(digits: &str) -> Option<f64> {
+    if digits.len() != 3 || !all_digits(digits) {
+        return None;
+    }
+    let tenths: f64 = digits.parse().ok()?;
+    let base = if tenths < 500.0 { 1000.0 } else { 900.0 };
+    Some(base + tenths / 10.0)
+}
+
+/// `TsTTTsDDD`, each a sign digit (1 for below zero) and tenths of a degree;
+/// the dewpoint may be missing
+fn parse_precise_temperature(s: &str) -> Option<(f64, Option<f64>)> {
+    let digits = s.strip_prefix('T')?;
+    if !matches!(digits.len(), 4 | 8) || !all_digits(digits) {
+        return None;
+    }
+    let value = |group: &str| -> Option<f64> {
+        let tenths: f64 = group[1..].parse().ok()?;
+        match &group[0..1] {
+            "0" => Some(tenths / 10.0),
+            "1" => Some(-tenths / 10.0),
+            _ => None,
+        }
+    };
+    let temperature = value(&digits[0..4])?;
+    let dewpoint = match digits.get(4..) {
+        Some("") | None => None,
+        Some(group) => Some(value(group)?),
+    };
+    Some((temperature, dewpoint))
+}
+
+/// `dddff(f)/(hh)mm`, e.g. `28045/1732` or `28045/32`
+fn parse_peak_wind(s: &str) -> Option<PeakWind> {
+    let (wind, time) = s.split_once('/')?;
+    if !matches!(wind.len(), 5 | 6) || !all_digits(wind) || !all_digits(time) {
+        return None;
+    }
+    let (hour, minute) = match time.len() {
+        2 => (None, time),
+        4 => (Some(time[0..2].parse().ok()?), &time[2..4]),
+        _ => return None,
+    };
+    Some(PeakWind {
+        direction: wind[0..3].parse().ok()?,
+        speed: wind[3..].parse().ok()?,
+        hour,
+        minute: minute.parse().ok()?,
+    })
+}
+
+fn parse_altimeter(s: &str) -> Option<Altimeter> {
+    let digits = s.get(1..)?;
+    if digits.len() != 4 || !all_digits(digits) {
+        return None;
+    }
+    let value: f64 = digits.parse().ok()?;
+    match &s[0..1] {
+        "A" => Some(Altimeter::InchesOfMercury(value / 100.0)),
+        "Q" => Some(Altimeter::Hectopascals(value)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> Observation {
+        Observation::parse(raw).unwrap()
+    }
+
+    #[test]
+    fn decodes_a_routine_us_report() {
+        let obs = parse(
+            "METAR KSFO 171756Z 29012G20KT 240V300 10SM -RA FEW015 BKN030 OVC050 16/11 A3002 RMK AO2",
+        );
+        assert_eq!(obs.report_type, ReportType::Metar);
+        assert_eq!(obs.station, "KSFO");
+        assert_eq!(
+            obs.time,
+            DayTime {
+                day: 17,
+                hour: 17,
+                minute: 56
+            }
+        );
+        assert_eq!(
+            obs.wind,
+            Some(Wind {
+                direction: Some(290),
+                speed: 12,
+                gust: Some(20),
+                unit: SpeedUnit::Knots,
+                variable_sector: Some((240, 300)),
+            })
+        );
+        assert_eq!(obs.visibility.as_ref().unwrap().statute_miles(), 10.0);
+        assert_eq!(obs.weather.len(), 1);
+        assert_eq!(obs.sky.len(), 3);
+        assert_eq!(obs.ceiling(), Some(3000));
+        assert_eq!(obs.temperature, Some(16));
+        assert_eq!(obs.dewpoint, Some(11));
+        assert_eq!(obs.altimeter, Some(Altimeter::InchesOfMercury(30.02)));
+        assert_eq!(obs.remarks.as_deref(), Some("AO2"));
+        assert!(obs.unparsed.is_empty());
+    }
+
+    #[test]
+    fn decodes_mixed_fraction_visibility() {
+        let obs = parse("KBOS 171754Z 05008KT 1 1/2SM BR OVC004 08/07 A2998");
+        let vis = obs.visibility.as_ref().unwrap();
+        assert_eq!(vis.distance, 1.5);
+        assert_eq!(vis.unit, DistanceUnit::StatuteMiles);
+        assert_eq!(vis.bound, None);
+        assert_eq!(obs.ceiling(), Some(400));
+        assert!(obs.unparsed.is_empty());
+    }
+
+    #[test]
+    fn decodes_bounded_visibility() {
+        let less = parse("KSEA 171753Z 00000KT M1/4SM FG VV001 09/09 A3010");
+        let vis = less.visibility.unwrap();
+        assert_eq!(vis.distance, 0.25);
+        assert_eq!(vis.bound, Some(Bound::LessThan));
+
+        let more = parse("KDEN 171753Z 18006KT P6SM SKC 22/M02 A3021");
+        let vis = more.visibility.unwrap();
+        assert_eq!(vis.distance, 6.0);
+        assert_eq!(vis.bound, Some(Bound::GreaterThan));
+
+        let metric = parse("EDDF 171750Z 24008KT 9999 FEW030 15/08 Q1018");
+        let vis = metric.visibility.unwrap();
+        assert_eq!(vis.distance, 10000.0);
+        assert_eq!(vis.unit, DistanceUnit::Meters);
+        assert_eq!(vis.bound, Some(Bound::GreaterThan));
+    }
+
+    #[test]
+    fn decodes_directional_minimum_visibility() {
+        let obs = parse("LFPG 171730Z 20005KT 4000 1500SW BR BKN005 11/10 Q1012 NOSIG");
+        assert_eq!(obs.visibility.unwrap().distance, 4000.0);
+        let (minimum, direction) = obs.minimum_visibility.unwrap();
+        assert_eq!(minimum.distance, 1500.0);
+        assert_eq!(direction, "SW");
+        assert!(obs.unparsed.is_empty());
+    }
+
+    #[test]
+    fn decodes_runway_visual_range_with_tendencies() {
+        let obs = parse(
+            "EGLL 171750Z 24004KT 0350 R27L/0550U R27R/P1500N R09L/M0050D FG VV001 09/09 Q1009",
+        );
+        let rvr = &obs.runway_visual_range;
+        assert_eq!(rvr.len(), 3);
+        assert_eq!(rvr[0].runway, "27L");
+        assert_eq!(rvr[0].range, 550);
+        assert_eq!(rvr[0].unit, DistanceUnit::Meters);
+        assert_eq!(rvr[0].tendency, Some(RvrTendency::Upward));
+        assert_eq!(rvr[1].bound, Some(Bound::GreaterThan));
+        assert_eq!(rvr[1].tendency, Some(RvrTendency::NoChange));
+        assert_eq!(rvr[2].bound, Some(Bound::LessThan));
+        assert_eq!(rvr[2].range, 50);
+        assert_eq!(rvr[2].tendency, Some(RvrTendency::Downward));
+        assert!(obs.unparsed.is_empty());
+    }
+
+    #[test]
+    fn decodes_variable_runway_visual_range_in_feet() {
+        let obs = parse("KJFK 171751Z 04010KT 1/2SM R04R/2000V4000FT FG OVC002 12/12 A2990");
+        let rvr = &obs.runway_visual_range[0];
+        assert_eq!(rvr.runway, "04R");
+        assert_eq!(rvr.range, 2000);
+        assert_eq!(rvr.variable_max, Some(4000));
+        assert_eq!(rvr.unit, DistanceUnit::Feet);
+        assert_eq!(rvr.tendency, None);
+    }
+
+    #[test]
+    fn rejects_non_ascii_runway_designators() {
+        let obs = parse("EGLL 171750Z 24004KT 0350 R1É/0600 FG 09/09 Q1009");
+        assert!(obs.runway_visual_range.is_empty());
+        assert_eq!(obs.unparsed, ["R1É/0600"]);
+    }
+
+    #[test]
+    fn rejects_non_ascii_wind() {
+        let obs = parse("KSJC 171753Z 12ÉÉKT 10SM CLR 18/16 A2992");
+        assert_eq!(obs.wind, None);
+        assert_eq!(obs.unparsed, ["12ÉÉKT"]);
+        assert_eq!(parse_wind("ÉÉÉ05KT"), None);
+        assert_eq!(parse_wind("1É05KT"), None);
+    }
+
+    #[test]
+    fn decodes_vertical_visibility_as_ceiling() {
+        let obs = parse("KOAK 171753Z AUTO 00000KT 1/4SM FG VV002 12/12 A3000");
+        assert_eq!(obs.sky[0].cover, CloudCover::VerticalVisibility);
+        assert_eq!(obs.sky[0].base, Some(200));
+        assert_eq!(obs.ceiling(), Some(200));
+        assert!(obs.wind.unwrap().is_calm());
+    }
+
+    #[test]
+    fn decodes_cavok_and_metric_altimeter() {
+        let obs = parse("EGLL 171750Z VRB02KT CAVOK M01/M05 Q0998 NOSIG");
+        assert!(obs.cavok);
+        assert!(obs.visibility.is_none());
+        assert!(obs.wind.unwrap().is_variable());
+        assert_eq!(obs.temperature, Some(-1));
+        assert_eq!(obs.dewpoint, Some(-5));
+        assert_eq!(obs.altimeter, Some(Altimeter::Hectopascals(998.0)));
+        assert_eq!(obs.trends, [Trend::NoSignificantChange]);
+    }
+
+    #[test]
+    fn decodes_becoming_and_temporary_trends() {
+        let obs =
+            parse("EHAM 171755Z 22015KT 9999 BKN020 14/10 Q1004 BECMG 25020G30KT TEMPO 4000 SHRA");
+        assert_eq!(
+            obs.trends,
+            [
+                Trend::Becoming("25020G30KT".to_string()),
+                Trend::Temporary("4000 SHRA".to_string()),
+            ]
+        );
+        assert_eq!(obs.wind.unwrap().speed, 15);
+    }
+
+    #[test]
+    fn decodes_auto_and_corrected_speci() {
+        let obs = parse("SPECI COR KSJC 171812Z AUTO 31018G27KT 10SM CLR 21/04 A3001");
+        assert_eq!(obs.report_type, ReportType::Speci);
+        assert!(obs.corrected);
+        assert!(obs.auto);
+        assert_eq!(obs.sky[0].cover, CloudCover::Clear);
+
+        let late = parse("KSJC 171756Z COR 31018KT 10SM CLR 21/04 A3001");
+        assert!(late.corrected);
+        assert!(!late.auto);
+    }
+
+    #[test]
+    fn decodes_metric_wind_units() {
+        let obs = parse("UUEE 171730Z 27006MPS 9999 SCT030 05/01 Q1015");
+        let wind = obs.wind.unwrap();
+        assert_eq!(wind.unit, SpeedUnit::MetersPerSecond);
+        assert!((wind.speed_knots() - 11.66).abs() < 0.01);
+    }
+
+    #[test]
+    fn reports_nil_and_malformed_reports() {
+        assert_eq!(
+            Observation::parse("METAR KXYZ NIL="),
+            Err(MetarParseError::NilReport("KXYZ".to_string()))
+        );
+        assert_eq!(
+            Observation::parse("KXYZ 171756Z NIL"),
+            Err(MetarParseError::NilReport("KXYZ".to_string()))
+        );
+        assert_eq!(Observation::parse("   "), Err(MetarParseError::Empty));
+        assert_eq!(
+            Observation::parse("kxyz 171756Z"),
+            Err(MetarParseError::InvalidStation("kxyz".to_string()))
+        );
+        assert_eq!(
+            Observation::parse("KXYZ 321756Z"),
+            Err(MetarParseError::InvalidTime("321756Z".to_string()))
+        );
+        assert_eq!(
+            Observation::parse("KXYZ"),
+            Err(MetarParseError::MissingTime)
+        );
+    }
+
+    #[test]
+    fn keeps_unrecognized_groups() {
+        let obs = parse("KXYZ 171756Z 27010KT 10SM XYZZY CLR 20/10 A3000");
+        assert_eq!(obs.unparsed, ["XYZZY"]);
+    }
+
+    #[test]
+    fn decodes_coded_remarks() {
+        let obs = parse(
+            "KOAK 171753Z 27012G28KT 10SM FEW020 12/08 A3000 RMK AO2 PK WND 28032/1732 SLP123 T01220083",
+        );
+        let remarks = obs.coded_remarks();
+        assert_eq!(remarks.station_type.as_deref(), Some("AO2"));
+        assert_eq!(remarks.sea_level_pressure, Some(1012.3));
+        assert_eq!(remarks.temperature, Some(12.2));
+        assert_eq!(remarks.dewpoint, Some(8.3));
+        assert_eq!(
+            remarks.peak_wind,
+            Some(PeakWind {
+                direction: 280,
+                speed: 32,
+                hour: Some(17),
+                minute: 32,
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_remarks_below_zero_and_low_pressure() {
+        let remarks = Remarks::parse("AO1 PK WND 310105/05 SLP982 T10561072");
+        assert_eq!(remarks.station_type.as_deref(), Some("AO1"));
+        assert_eq!(remarks.sea_level_pressure, Some(998.2));
+        assert_eq!(remarks.temperature, Some(-5.6));
+        assert_eq!(remarks.dewpoint, Some(-7.2));
+        let peak = remarks.peak_wind.unwrap();
+        assert_eq!(peak.speed, 105);
+        assert_eq!(peak.hour, None);
+        assert_eq!(peak.minute, 5);
+    }
+
+    #[test]
+    fn ignores_missing_remark_values() {
+        let remarks = Remarks::parse("AO2 SLPNO T0122 $");
+        assert_eq!(remarks.sea_level_pressure, None);
+        assert_eq!(remarks.temperature, Some(12.2));
+        assert_eq!(remarks.dewpoint, None);
+        assert_eq!(Remarks::parse(""), Remarks::default());
+    }
+
+    #[test]
+    fn resolves_day_into_nearest_month() {
+        let reference = NaiveDate::from_ymd_opt(2025, 3, 1)
+            .unwrap()
+            .and_hms_opt(1, 0, 0)
+            .unwrap()
+            .and_utc();
+        let time = DayTime {
+            day: 28,
+            hour: 23,
+            minute: 50,
+        };
+        let resolved = time.resolve(reference).unwrap();
+        assert_eq!(
+            resolved.date_naive(),
+            NaiveDate::from_ymd_opt(2025, 2, 28).unwrap()
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Async counterparts of the API fetchers, enabled by the `async` feature.
+//!
+//! [`AsyncWeatherClient`] has the same retry policy, cache and batch behavior
+//! as [`WeatherClient`](crate::WeatherClient), and is cheap to clone, so many
+//! stations can be fetched concurrently from one client. Retries wait on a
+//! tokio timer and cache files are read and written on tokio's blocking pool,
+//! so it must be used from within a tokio runtime.
+
+use crate::bulk::BulkMetars;
+use crate::cache::CacheEntry;
+use crate::client::{
+    Api, Fetched, Lookup, NEARBY_SEARCH_RADII_KM, Outcome, Report, conditional_headers,
+    first_or_no_data, history_newest_first, nearest_or_no_data, validators,
+};
+use crate::location::Coordinates;
+use crate::query::MetarQuery;
+use crate::stations::{NearbyStation, nearest_reports};
+use crate::{MetarData, Result, TafData, WeatherClientBuilder, WeatherError};
+use reqwest::{Client, Response};
+use std::collections::BTreeMap;
+
+/// An async connection to the aviationweather.gov data API.
+/// Build one with [`WeatherClientBuilder::build_async`].
+#[derive(Debug, Clone)]
+pub struct AsyncWeatherClient {
+    client: Client,
+    api: Api,
+}
+
+impl AsyncWeatherClient {
+    /// A client with the default endpoint, timeouts and retry policy
+    pub fn new() -> Result<AsyncWeatherClient> {
+        WeatherClientBuilder::default().build_async()
+    }
+
+    pub(crate) fn from_parts(client: Client, api: Api) -> AsyncWeatherClient {
+        AsyncWeatherClient { client, api }
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.api.base_url()
+    }
+
+    async fn send(&self, url: &str, cached: Option<&CacheEntry>) -> Result<Option<Response>> {
+        let mut attempt = 0;
+        loop {
+            let request = self.client.get(url).headers(conditional_headers(cached));
+            let error = match request.send().await {
+                Ok(response) => match Outcome::of(url, response.status(), response.headers()) {
+                    Outcome::Body => return Ok(Some(response)),
+                    Outcome::NotModified => return Ok(None),
+                    Outcome::Retry(error) => error,
+                    Outcome::Fail(error) => return Err(error),
+                },
+                Err(e) if e.is_builder() => {
+                    return Err(WeatherError::http_client("Invalid request", e));
+                }
+                Err(e) => WeatherError::network(url, e),
+            };
+
+            let Some(delay) = self.api.retry_delay(attempt, &error) else {
+                return Err(error);
+            };
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn get(&self, url: &str, cached: Option<&CacheEntry>) -> Result<Fetched> {
+        let Some(response) = self.send(url, cached).await? else {
+            return Ok(Fetched::NotModified);
+        };
+        let (etag, last_modified) = validators(response.headers());
+        let text = response
+            .text()
+            .await
+            .map_err(|e| WeatherError::network(url, e))?;
+        Ok(Fetched::Body {
+            text,
+            etag,
+            last_modified,
+        })
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .send(url, None)
+            .await?
+            .ok_or_else(|| WeatherError::EmptyResponse(url.to_string()))?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| WeatherError::network(url, e))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Run `task` against the API's cache on tokio's blocking pool, so that
+    /// reading and writing cache files does not stall the executor
+    async fn with_cache<T, F>(&self, task: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(&Api) -> T + Send + 'static,
+    {
+        let api = self.api.clone();
+        match tokio::task::spawn_blocking(move || task(&api)).await {
+            Ok(value) => value,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    async fn fetch_text(
+        &self,
+        product: &'static str,
+        ids: &str,
+        params: &[(&'static str, String)],
+    ) -> Result<String> {
+        let (ids, params) = (ids.to_string(), params.to_vec());
+        let lookup = {
+            let (ids, params) = (ids.clone(), params.clone());
+            self.with_cache(move |api| api.lookup(product, &ids, &params))
+                .await
+        };
+        match lookup {
+            Lookup::Fresh(body) => Ok(body),
+            Lookup::Fetch { url, key, cached } => {
+                let fetched = self.get(&url, cached.as_ref()).await;
+                self.with_cache(move |api| api.complete(product, &ids, key, fetched, cached))
+                    .await
+            }
+        }
+    }
+
+    async fn fetch_reports<T: Report>(
+        &self,
+        product: &'static str,
+        icao: &str,
+        params: &[(&'static str, String)],
+    ) -> Result<Vec<T>> {
+        let response_text = self
+            .fetch_text(product, &icao.to_uppercase(), params)
+            .await?;
+        self.api.parse_reports(icao, &response_text)
+    }
+
+    /// Fetch the latest METAR for a station
+    pub async fn fetch_weather_data(&self, icao: &str) -> Result<MetarData> {
+        first_or_no_data(icao, self.fetch_reports("metar", icao, &[]).await?)
+    }
+
+    /// Fetch the latest METAR for several stations in a single request; see
+    /// [`WeatherClient::fetch_weather_batch`](crate::WeatherClient::fetch_weather_batch)
+    pub async fn fetch_weather_batch(
+        &self,
+        icaos: &[&str],
+    ) -> Result<BTreeMap<String, Result<MetarData>>> {
+        let ids: Vec<String> = icaos.iter().map(|icao| icao.to_string()).collect();
+        let mut batch = self
+            .with_cache(move |api| {
+                let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+                api.batch(&ids)
+            })
+            .await;
+        if let Some(id) = batch.single_to_revalidate(&self.api) {
+            let fetched = self.fetch_reports("metar", &id, &[]).await;
+            batch.complete_single(&id, fetched)?;
+        } else if !batch.to_fetch.is_empty() {
+            let url = self.api.url("metar", &batch.to_fetch.join(","), &[]);
+            let fetched = self.get(&url, None).await;
+            batch = self
+                .with_cache(move |api| api.complete_batch(&mut batch, fetched).map(|()| batch))
+                .await?;
+        }
+        Ok(batch.finish())
+    }
+
+    /// Fetch the latest METARs from the `count` reporting stations nearest
+    /// to `position`, nearest first
+    pub async fn fetch_nearby_weather(
+        &self,
+        position: &Coordinates,
+        count: usize,
+    ) -> Result<Vec<(NearbyStation, MetarData)>> {
+        let mut nearby = Vec::new();
+        for radius_km in NEARBY_SEARCH_RADII_KM {
+            let reports = self
+                .fetch_area(&MetarQuery::radius(*position, radius_km))
+                .await?;
+            nearby = nearest_reports(position, &reports, count);
+            if nearby.len() >= count {
+                break;
+            }
+        }
+        Ok(nearby)
+    }
+
+    /// Fetch the latest METAR from the reporting station nearest to `position`
+    pub async fn fetch_nearest_weather(
+        &self,
+        position: &Coordinates,
+    ) -> Result<(NearbyStation, MetarData)> {
+        nearest_or_no_data(position, self.fetch_nearby_weather(position, 1).await?)
+    }
+
+    /// Fetch the latest METAR from every station in an area
+    pub async fn fetch_area(&self, query: &MetarQuery) -> Result<Vec<MetarData>> {
+        let (ids, params) = query.request();
+        let text = self.fetch_text("metar", &ids, &params).await?;
+        self.api.area_reports(query, &text)
+    }
+
+    /// Download a bulk METAR file to query locally
+    pub async fn fetch_bulk_metars(&self, url: &str) -> Result<BulkMetars> {
+        BulkMetars::parse(&self.get_bytes(url).await?)
+    }
+
+    /// Fetch every METAR issued for a station in the last `hours` hours, newest first
+    pub async fn fetch_weather_history(&self, icao: &str, hours: u32) -> Result<Vec<MetarData>> {
+        let history = self
+            .fetch_reports("metar", icao, &[("hours", hours.to_string())])
+            .await?;
+        history_newest_first(icao, history)
+    }
+
+    /// Fetch the current terminal aerodrome forecast for a station
+    pub async fn fetch_taf(&self, icao: &str) -> Result<TafData> {
+        first_or_no_data(icao, self.fetch_reports("taf", icao, &[]).await?)
+    }
+}
+
+/// Async version of [`crate::fetch_weather_data`]
+pub async fn fetch_weather_data(icao: &str) -> Result<MetarData> {
+    AsyncWeatherClient::new()?.fetch_weather_data(icao).await
+}
+
+/// Async version of [`crate::fetch_weather_batch`]
+pub async fn fetch_weather_batch(icaos: &[&str]) -> Result<BTreeMap<String, Result<MetarData>>> {
+    AsyncWeatherClient::new()?.fetch_weather_batch(icaos).await
+}
+
+/// Async version of [`crate::fetch_nearest_weather`]
+pub async fn fetch_nearest_weather(position: &Coordinates) -> Result<(NearbyStation, MetarData)> {
+    AsyncWeatherClient::new()?
+        .fetch_nearest_weather(position)
+        .await
+}
+
+/// Async version of [`crate::fetch_area`]
+pub async fn fetch_area(query: &MetarQuery) -> Result<Vec<MetarData>> {
+    AsyncWeatherClient::new()?.fetch_area(query).await
+}
+
+/// Async version of [`crate::fetch_bulk_metars`]
+pub async fn fetch_bulk_metars(url: &str) -> Result<BulkMetars> {
+    AsyncWeatherClient::new()?.fetch_bulk_metars(url).await
+}
+
+/// Async version of [`crate::fetch_weather_history`]
+pub async fn fetch_weather_history(icao: &str, hours: u32) -> Result<Vec<MetarData>> {
+    AsyncWeatherClient::new()?
+        .fetch_weather_history(icao, hours)
+        .await
+}
+
+/// Async version of [`crate::fetch_taf`]
+pub async fn fetch_taf(icao: &str) -> Result<TafData> {
+    AsyncWeatherClient::new()?.fetch_taf(icao).await
+}
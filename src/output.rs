@@ -0,0 +1,268 @@
+//! Machine-readable output of decoded observations: JSON, YAML, CSV and
+//! GeoJSON, for piping into jq or dashboards.
+
+use crate::metar::Observation;
+use crate::{
+    DerivedQuantities, FlightCategory, MetarData, Result, WeatherError, WeatherPhenomenon, WmoCode,
+    parse_present_weather, parse_wmo_codes,
+};
+use serde::Serialize;
+use std::io::Write;
+
+/// A machine-readable format for reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    /// One row per report with the most used fields
+    Csv,
+    /// A FeatureCollection with a point per station
+    Geojson,
+}
+
+/// A report with everything decoded and derived from it. The API fields
+/// keep their API names; the rest are snake case.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedReport<'a> {
+    #[serde(flatten)]
+    pub metar: &'a MetarData,
+    pub station_name: Option<String>,
+    /// `None` when the raw text could not be decoded, e.g. from IWXXM
+    pub observation: Option<&'a Observation>,
+    pub flight_category: Option<FlightCategory>,
+    /// Feet above ground level
+    pub ceiling: Option<u32>,
+    pub derived: DerivedQuantities,
+    pub present_weather: Vec<WeatherPhenomenon>,
+    pub wmo_codes: Vec<WmoCode>,
+}
+
+impl DecodedReport<'_> {
+    pub fn new(metar: &MetarData) -> DecodedReport<'_> {
+        let wx = metar.wx_string.as_deref().unwrap_or_default();
+        DecodedReport {
+            metar,
+            station_name: metar.station_name(),
+            observation: metar.observation().ok(),
+            flight_category: metar.flight_category(),
+            ceiling: metar.ceiling(),
+            derived: metar.derived(),
+            present_weather: parse_present_weather(wx),
+            wmo_codes: parse_wmo_codes(wx),
+        }
+    }
+}
+
+/// Columns of the CSV output
+const CSV_HEADER: [&str; 20] = [
+    "station",
+    "name",
+    "observation_time",
+    "latitude",
+    "longitude",
+    "elevation_m",
+    "temperature_c",
+    "dewpoint_c",
+    "wind_direction_deg",
+    "wind_speed_kt",
+    "wind_gust_kt",
+    "visibility_sm",
+    "altimeter_hpa",
+    "ceiling_ft",
+    "flight_category",
+    "relative_humidity",
+    "density_altitude_ft",
+    "weather",
+    "wmo_codes",
+    "raw_text",
+];
+
+/// Write `reports` to `out` in `format`
+pub fn write_reports<'a>(
+    reports: impl IntoIterator<Item = &'a MetarData>,
+    format: OutputFormat,
+    out: impl Write,
+) -> Result<()> {
+    let decoded: Vec<DecodedReport> = reports.into_iter().map(DecodedReport::new).collect();
+    let written = match format {
+        OutputFormat::Json => write_json(&decoded, out),
+        OutputFormat::Yaml => serde_yaml::to_writer(out, &decoded).map_err(std::io::Error::other),
+        OutputFormat::Csv => write_csv(&decoded, out),
+        OutputFormat::Geojson => write_json(&feature_collection(&decoded), out),
+    };
+    written.map_err(|e| WeatherError::io("Failed to write output", e))
+}
+
+fn write_json(value: &impl Serialize, mut out: impl Write) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut out, value)?;
+    writeln!(out)
+}
+
+fn write_csv(reports: &[DecodedReport], out: impl Write) -> std::io::Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(CSV_HEADER)?;
+    let text = |value: Option<String>| value.unwrap_or_default();
+    let number = |value: Option<f64>| value.map(|v| format!("{:.1}", v)).unwrap_or_default();
+    for report in reports {
+        let metar = report.metar;
+        let wind = metar.wind();
+        let codes: Vec<&str> = report.wmo_codes.iter().map(WmoCode::code).collect();
+        writer.write_record([
+            metar.icao_id.clone(),
+            text(report.station_name.clone()),
+            text(metar.report_time.clone()),
+            text(metar.lat.map(|v| v.to_string())),
+            text(metar.lon.map(|v| v.to_string())),
+            text(metar.elev.map(|v| v.to_string())),
+            number(metar.temperature()),
+            number(metar.dewpoint()),
+            text(
+                wind.as_ref()
+                    .and_then(|w| w.direction)
+                    .map(|d| d.to_string()),
+            ),
+            number(wind.as_ref().map(|w| w.speed_knots())),
+            number(wind.as_ref().and_then(|w| w.gust_knots())),
+            number(metar.visibility().map(|v| v.statute_miles())),
+            number(metar.altimeter().map(|a| a.hpa())),
+            text(report.ceiling.map(|c| c.to_string())),
+            text(report.flight_category.map(|c| c.code().to_string())),
+            number(report.derived.relative_humidity),
+            number(report.derived.density_altitude),
+            text(metar.wx_string.clone()),
+            codes.join(" "),
+            metar.raw_ob.clone(),
+        ])?;
+    }
+    writer.flush()
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+struct FeatureCollection<'a> {
+    features: Vec<Feature<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+struct Feature<'a> {
+    id: &'a str,
+    /// Null for reports without a position, as GeoJSON allows
+    geometry: Option<Point>,
+    properties: &'a DecodedReport<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+struct Point {
+    /// Longitude first
+    coordinates: [f64; 2],
+}
+
+fn feature_collection<'a>(reports: &'a [DecodedReport<'a>]) -> FeatureCollection<'a> {
+    let features = reports
+        .iter()
+        .map(|report| Feature {
+            id: &report.metar.icao_id,
+            geometry: report
+                .metar
+                .lat
+                .zip(report.metar.lon)
+                .map(|(lat, lon)| Point {
+                    coordinates: [lon, lat],
+                }),
+            properties: report,
+        })
+        .collect();
+    FeatureCollection { features }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn reports() -> Vec<MetarData> {
+        let placed =
+            MetarData::from_raw("KSJC 171753Z 31010G18KT 3SM -RA BR BKN008 OVC015 12/10 A2992")
+                .unwrap();
+        // Not in the station database, so without a position
+        let unplaced = MetarData::from_raw("KXYZ 171755Z 00000KT 10SM CLR 15/05 A3001").unwrap();
+        vec![placed, unplaced]
+    }
+
+    fn write(format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write_reports(&reports(), format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn writes_csv_rows_matching_the_header() {
+        let csv = write(OutputFormat::Csv);
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        assert_eq!(reader.headers().unwrap(), &CSV_HEADER[..]);
+        let rows: Vec<csv::StringRecord> = reader.records().map(|row| row.unwrap()).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == CSV_HEADER.len()));
+
+        let column = |row: &csv::StringRecord, name| {
+            let index = CSV_HEADER
+                .iter()
+                .position(|&header| header == name)
+                .unwrap();
+            row[index].to_string()
+        };
+        assert_eq!(column(&rows[0], "station"), "KSJC");
+        assert_eq!(column(&rows[0], "wind_gust_kt"), "18.0");
+        assert_eq!(column(&rows[0], "ceiling_ft"), "800");
+        assert_eq!(column(&rows[0], "flight_category"), "IFR");
+        assert_eq!(column(&rows[0], "weather"), "-RA BR");
+        assert_eq!(column(&rows[1], "latitude"), "");
+        assert_eq!(column(&rows[1], "ceiling_ft"), "");
+        assert_eq!(
+            column(&rows[1], "raw_text"),
+            "KXYZ 171755Z 00000KT 10SM CLR 15/05 A3001"
+        );
+    }
+
+    #[test]
+    fn writes_geojson_points_longitude_first() {
+        let geojson: Value = serde_json::from_str(&write(OutputFormat::Geojson)).unwrap();
+        assert_eq!(geojson["type"], "FeatureCollection");
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+
+        let sjc = crate::find_station("KSJC").unwrap();
+        assert_eq!(features[0]["type"], "Feature");
+        assert_eq!(features[0]["id"], "KSJC");
+        assert_eq!(features[0]["geometry"]["type"], "Point");
+        assert_eq!(
+            features[0]["geometry"]["coordinates"],
+            serde_json::json!([sjc.lon, sjc.lat])
+        );
+        assert_eq!(features[0]["properties"]["flight_category"], "IFR");
+
+        assert_eq!(features[1]["id"], "KXYZ");
+        assert!(features[1]["geometry"].is_null());
+    }
+
+    #[test]
+    fn writes_json_and_yaml_with_api_and_decoded_fields() {
+        let json: Value = serde_json::from_str(&write(OutputFormat::Json)).unwrap();
+        let reports = json.as_array().unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0]["icaoId"], "KSJC");
+        assert_eq!(reports[0]["ceiling"], 800);
+        assert_eq!(reports[0]["flight_category"], "IFR");
+        assert!(reports[0]["observation"].is_object());
+        assert!(reports[1]["station_name"].is_null());
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&write(OutputFormat::Yaml)).unwrap();
+        let reports = yaml.as_sequence().unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0]["icaoId"].as_str(), Some("KSJC"));
+        assert_eq!(reports[0]["ceiling"].as_u64(), Some(800));
+        assert!(reports[1]["station_name"].is_null());
+    }
+}
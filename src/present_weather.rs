@@ -0,0 +1,303 @@
+//! Token-based decoding of METAR present weather groups such as `-FZRA` or `VCTS`.
+
+use crate::WmoCode;
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Intensity {
+    Light,    // -
+    Moderate, // no prefix
+    Heavy,    // +
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Descriptor {
+    Shallow,      // MI
+    Partial,      // PR
+    Patches,      // BC
+    LowDrifting,  // DR
+    Blowing,      // BL
+    Showers,      // SH
+    Thunderstorm, // TS
+    Freezing,     // FZ
+}
+
+impl Descriptor {
+    pub fn code(&self) -> &str {
+        match self {
+            Descriptor::Shallow => "MI",
+            Descriptor::Partial => "PR",
+            Descriptor::Patches => "BC",
+            Descriptor::LowDrifting => "DR",
+            Descriptor::Blowing => "BL",
+            Descriptor::Showers => "SH",
+            Descriptor::Thunderstorm => "TS",
+            Descriptor::Freezing => "FZ",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            Descriptor::Shallow => "Shallow",
+            Descriptor::Partial => "Partial",
+            Descriptor::Patches => "Patches",
+            Descriptor::LowDrifting => "Low Drifting",
+            Descriptor::Blowing => "Blowing",
+            Descriptor::Showers => "Showers",
+            Descriptor::Thunderstorm => "Thunderstorm",
+            Descriptor::Freezing => "Freezing",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Descriptor> {
+        match code {
+            "MI" => Some(Descriptor::Shallow),
+            "PR" => Some(Descriptor::Partial),
+            "BC" => Some(Descriptor::Patches),
+            "DR" => Some(Descriptor::LowDrifting),
+            "BL" => Some(Descriptor::Blowing),
+            "SH" => Some(Descriptor::Showers),
+            "TS" => Some(Descriptor::Thunderstorm),
+            "FZ" => Some(Descriptor::Freezing),
+            _ => None,
+        }
+    }
+}
+
+/// One present weather group from a METAR, e.g. `+TSRA` or `VCSH`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WeatherPhenomenon {
+    pub intensity: Intensity,
+    pub descriptor: Option<Descriptor>,
+    /// VC, occurring within 5-10 statute miles but not at the station
+    pub in_vicinity: bool,
+    /// RE, recent weather that has ended
+    pub recent: bool,
+    pub phenomena: Vec<WmoCode>,
+}
+
+impl WeatherPhenomenon {
+    /// Parse a single weather group; returns `None` if it is not one
+    pub fn parse(token: &str) -> Option<WeatherPhenomenon> {
+        let (intensity, body) = if let Some(b) = token.strip_prefix('-') {
+            (Intensity::Light, b)
+        } else if let Some(b) = token.strip_prefix('+') {
+            (Intensity::Heavy, b)
+        } else {
+            (Intensity::Moderate, token)
+        };
+
+        let (in_vicinity, recent, body) = if let Some(b) = body.strip_prefix("VC") {
+            (true, false, b)
+        } else if let Some(b) = body.strip_prefix("RE") {
+            (false, true, b)
+        } else {
+            (false, false, body)
+        };
+
+        if body.is_empty() || !body.is_ascii() || !body.len().is_multiple_of(2) {
+            return None;
+        }
+
+        let mut descriptor = None;
+        let mut phenomena = Vec::new();
+        for (i, start) in (0..body.len()).step_by(2).enumerate() {
+            let code = &body[start..start + 2];
+            if i == 0
+                && let Some(d) = Descriptor::from_code(code)
+            {
+                descriptor = Some(d);
+            } else {
+                phenomena.push(WmoCode::from_code(code)?);
+            }
+        }
+
+        // Only TS and SH stand alone without a phenomenon (e.g. TS, VCSH)
+        if phenomena.is_empty()
+            && !matches!(
+                descriptor,
+                Some(Descriptor::Thunderstorm) | Some(Descriptor::Showers)
+            )
+        {
+            return None;
+        }
+
+        Some(WeatherPhenomenon {
+            intensity,
+            descriptor,
+            in_vicinity,
+            recent,
+            phenomena,
+        })
+    }
+
+    /// Reassemble the METAR group, e.g. `-FZRA`
+    pub fn code(&self) -> String {
+        let mut code = String::new();
+        match self.intensity {
+            Intensity::Light => code.push('-'),
+            Intensity::Heavy => code.push('+'),
+            Intensity::Moderate => {}
+        }
+        if self.in_vicinity {
+            code.push_str("VC");
+        }
+        if self.recent {
+            code.push_str("RE");
+        }
+        if let Some(d) = self.descriptor {
+            code.push_str(d.code());
+        }
+        for p in &self.phenomena {
+            code.push_str(p.code());
+        }
+        code
+    }
+
+    /// Plain-English description suitable for display and speech,
+    /// e.g. "light freezing rain" or "thunderstorm in the vicinity"
+    pub fn description(&self) -> String {
+        let intensity = match self.intensity {
+            Intensity::Light => "light ",
+            Intensity::Heavy => "heavy ",
+            Intensity::Moderate => "",
+        };
+
+        let phenomena =
+            if self.intensity == Intensity::Heavy && self.phenomena == [WmoCode::FunnelCloud] {
+                "tornado or waterspout".to_string()
+            } else {
+                join_phenomena(&self.phenomena)
+            };
+
+        let mut text = match self.descriptor {
+            Some(Descriptor::Thunderstorm) if phenomena.is_empty() => "thunderstorm".to_string(),
+            Some(Descriptor::Thunderstorm) => {
+                format!("thunderstorm with {}{}", intensity, phenomena)
+            }
+            Some(Descriptor::Showers) if phenomena.is_empty() => format!("{}showers", intensity),
+            Some(Descriptor::Showers) => format!("{}{} showers", intensity, phenomena),
+            Some(Descriptor::Patches) => format!("{}patches of {}", intensity, phenomena),
+            Some(d) => format!(
+                "{}{} {}",
+                intensity,
+                d.description().to_lowercase(),
+                phenomena
+            ),
+            None if phenomena.starts_with("tornado") => phenomena,
+            None => format!("{}{}", intensity, phenomena),
+        };
+
+        if self.recent {
+            text = format!("recent {}", text);
+        }
+        if self.in_vicinity {
+            text.push_str(" in the vicinity");
+        }
+        text
+    }
+}
+
+impl fmt::Display for WeatherPhenomenon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.code(), self.description())
+    }
+}
+
+fn join_phenomena(phenomena: &[WmoCode]) -> String {
+    let names: Vec<String> = phenomena
+        .iter()
+        .map(|p| p.description().to_lowercase())
+        .collect();
+    match names.len() {
+        0 => String::new(),
+        1 => names[0].clone(),
+        n => format!("{} and {}", names[..n - 1].join(", "), names[n - 1]),
+    }
+}
+
+/// Split a weather string such as `"-SHRA BR VCTS"` into structured phenomena,
+/// preserving the reported order. Unrecognized groups are skipped.
+pub fn parse_present_weather(wx_string: &str) -> Vec<WeatherPhenomenon> {
+    wx_string
+        .split_whitespace()
+        .filter_map(WeatherPhenomenon::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe(token: &str) -> String {
+        WeatherPhenomenon::parse(token).unwrap().description()
+    }
+
+    #[test]
+    fn parses_intensity_descriptor_and_phenomena() {
+        let wx = WeatherPhenomenon::parse("-FZRA").unwrap();
+        assert_eq!(wx.intensity, Intensity::Light);
+        assert_eq!(wx.descriptor, Some(Descriptor::Freezing));
+        assert_eq!(wx.phenomena, [WmoCode::Rain]);
+        assert!(!wx.in_vicinity && !wx.recent);
+
+        let wx = WeatherPhenomenon::parse("+SHRASN").unwrap();
+        assert_eq!(wx.intensity, Intensity::Heavy);
+        assert_eq!(wx.descriptor, Some(Descriptor::Showers));
+        assert_eq!(wx.phenomena, [WmoCode::Rain, WmoCode::Snow]);
+    }
+
+    #[test]
+    fn parses_proximity_and_recent_weather() {
+        let wx = WeatherPhenomenon::parse("VCTS").unwrap();
+        assert!(wx.in_vicinity);
+        assert_eq!(wx.descriptor, Some(Descriptor::Thunderstorm));
+        assert!(wx.phenomena.is_empty());
+
+        let wx = WeatherPhenomenon::parse("RETSRA").unwrap();
+        assert!(wx.recent);
+        assert_eq!(wx.phenomena, [WmoCode::Rain]);
+    }
+
+    #[test]
+    fn reassembles_the_group() {
+        for token in ["-FZRA", "+TSRAGR", "VCSH", "RESN", "BR", "MIFG", "+FC"] {
+            assert_eq!(WeatherPhenomenon::parse(token).unwrap().code(), token);
+        }
+    }
+
+    #[test]
+    fn describes_groups_in_plain_english() {
+        assert_eq!(describe("-FZRA"), "light freezing rain");
+        assert_eq!(describe("+TSRA"), "thunderstorm with heavy rain");
+        assert_eq!(describe("TS"), "thunderstorm");
+        assert_eq!(describe("VCSH"), "showers in the vicinity");
+        assert_eq!(describe("-SHRASN"), "light rain and snow showers");
+        assert_eq!(describe("BCFG"), "patches of fog");
+        assert_eq!(describe("RERA"), "recent rain");
+        assert_eq!(describe("+FC"), "tornado or waterspout");
+        assert_eq!(describe("BLSN"), "blowing snow");
+        assert_eq!(describe("RADZBR"), "rain, drizzle and mist");
+    }
+
+    #[test]
+    fn rejects_other_groups() {
+        for token in [
+            "BKN020", "RMK", "NOSIG", "10SM", "FZ", "XX", "-", "VC", "RAÉ",
+        ] {
+            assert_eq!(WeatherPhenomenon::parse(token), None, "{}", token);
+        }
+    }
+
+    #[test]
+    fn splits_a_weather_string_in_order() {
+        let codes: Vec<String> = parse_present_weather("-SHRA BR VCTS FOO")
+            .iter()
+            .map(WeatherPhenomenon::code)
+            .collect();
+        assert_eq!(codes, ["-SHRA", "BR", "VCTS"]);
+    }
+}
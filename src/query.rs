@@ -0,0 +1,308 @@
+//! Area queries: the latest METAR from every station in a bounding box, a
+//! state or province, or within a radius of a point.
+
+use crate::location::Coordinates;
+use crate::{MetarData, Result, WeatherError, find_station};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+const KM_PER_DEGREE_LAT: f64 = 111.2;
+
+/// A latitude/longitude rectangle. Boxes crossing the antimeridian are not supported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+}
+
+impl BoundingBox {
+    /// The box with these two opposite corners, in either order
+    pub fn new(a: Coordinates, b: Coordinates) -> BoundingBox {
+        BoundingBox {
+            min_lat: a.lat.min(b.lat),
+            min_lon: a.lon.min(b.lon),
+            max_lat: a.lat.max(b.lat),
+            max_lon: a.lon.max(b.lon),
+        }
+    }
+
+    /// The smallest box holding every point within `radius_km` of `center`
+    pub fn around(center: &Coordinates, radius_km: f64) -> BoundingBox {
+        let dlat = radius_km / KM_PER_DEGREE_LAT;
+        // Degrees of longitude shrink toward the poles
+        let dlon = dlat / center.lat.to_radians().cos().max(0.01);
+        BoundingBox {
+            min_lat: (center.lat - dlat).max(-90.0),
+            min_lon: (center.lon - dlon).max(-180.0),
+            max_lat: (center.lat + dlat).min(90.0),
+            max_lon: (center.lon + dlon).min(180.0),
+        }
+    }
+
+    pub fn contains(&self, point: &Coordinates) -> bool {
+        (self.min_lat..=self.max_lat).contains(&point.lat)
+            && (self.min_lon..=self.max_lon).contains(&point.lon)
+    }
+}
+
+/// Parses two corners as "lat0,lon0,lat1,lon1", e.g. "37,-123,38.5,-121"
+impl FromStr for BoundingBox {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || format!("Invalid bounding box '{}': expected LAT0,LON0,LAT1,LON1", s);
+        let values = s
+            .split(',')
+            .map(|v| v.trim().parse::<f64>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        let [lat0, lon0, lat1, lon1] = values[..] else {
+            return Err(invalid());
+        };
+        let out_of_range = || format!("Bounding box '{}' is out of range", s);
+        Ok(BoundingBox::new(
+            Coordinates::new(lat0, lon0).ok_or_else(out_of_range)?,
+            Coordinates::new(lat1, lon1).ok_or_else(out_of_range)?,
+        ))
+    }
+}
+
+/// In the API's `bbox` order: "min_lat,min_lon,max_lat,max_lon"
+impl fmt::Display for BoundingBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.4},{:.4},{:.4},{:.4}",
+            self.min_lat, self.min_lon, self.max_lat, self.max_lon
+        )
+    }
+}
+
+/// A set of stations to fetch the latest METARs for, selected by area
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetarQuery {
+    BoundingBox(BoundingBox),
+    /// A US state or Canadian province, by its two-letter code
+    State(String),
+    /// Stations within `radius_km` of `center`
+    Radius {
+        center: Coordinates,
+        radius_km: f64,
+    },
+}
+
+impl MetarQuery {
+    pub fn bounding_box(bbox: BoundingBox) -> MetarQuery {
+        MetarQuery::BoundingBox(bbox)
+    }
+
+    /// A state or province by its two-letter code, e.g. "CA"
+    pub fn state(code: &str) -> std::result::Result<MetarQuery, String> {
+        let code = code.trim();
+        if code.len() != 2 || !code.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return Err(format!(
+                "Invalid state or province '{}': expected a two-letter code such as CA",
+                code
+            ));
+        }
+        Ok(MetarQuery::State(code.to_uppercase()))
+    }
+
+    pub fn radius(center: Coordinates, radius_km: f64) -> MetarQuery {
+        MetarQuery::Radius { center, radius_km }
+    }
+
+    /// Stations within `radius_km` of a station in the built-in database
+    pub fn around_station(id: &str, radius_km: f64) -> Result<MetarQuery> {
+        let station = find_station(id)
+            .ok_or_else(|| WeatherError::UnknownStation(id.trim().to_uppercase()))?;
+        Ok(MetarQuery::radius(station.coordinates(), radius_km))
+    }
+
+    /// The `ids` and other parameters of the API request. A radius is
+    /// requested as its bounding box and trimmed afterwards.
+    pub(crate) fn request(&self) -> (String, Vec<(&'static str, String)>) {
+        match self {
+            MetarQuery::BoundingBox(bbox) => (String::new(), vec![("bbox", bbox.to_string())]),
+            MetarQuery::State(code) => (format!("@{}", code), Vec::new()),
+            MetarQuery::Radius { center, radius_km } => {
+                let bbox = BoundingBox::around(center, *radius_km);
+                (String::new(), vec![("bbox", bbox.to_string())])
+            }
+        }
+    }
+
+    /// The latest report from each station in an API response that lies in
+    /// the area, for those with a position
+    pub(crate) fn latest_reports(&self, reports: Vec<MetarData>) -> Vec<MetarData> {
+        let mut seen = HashSet::new();
+        reports
+            .into_iter()
+            .filter(|metar| seen.insert(metar.icao_id.to_uppercase()))
+            .filter(|metar| self.includes(metar))
+            .collect()
+    }
+
+    pub(crate) fn includes(&self, metar: &MetarData) -> bool {
+        let position = match (metar.lat, metar.lon) {
+            (Some(lat), Some(lon)) => Coordinates { lat, lon },
+            // Nothing to check against; the API selected it
+            _ => return !matches!(self, MetarQuery::Radius { .. }),
+        };
+        match self {
+            MetarQuery::BoundingBox(bbox) => bbox.contains(&position),
+            MetarQuery::State(_) => true,
+            MetarQuery::Radius { center, radius_km } => center.distance_km(&position) <= *radius_km,
+        }
+    }
+}
+
+impl fmt::Display for MetarQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetarQuery::BoundingBox(bbox) => write!(f, "bounding box {}", bbox),
+            MetarQuery::State(code) => write!(f, "state {}", code),
+            MetarQuery::Radius { center, radius_km } => {
+                write!(f, "{} km around {}", radius_km, center)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinates(lat: f64, lon: f64) -> Coordinates {
+        Coordinates::new(lat, lon).unwrap()
+    }
+
+    fn report(icao_id: &str, obs_time: i64, position: Option<(f64, f64)>) -> MetarData {
+        MetarData {
+            icao_id: icao_id.to_string(),
+            obs_time: Some(obs_time),
+            lat: position.map(|(lat, _)| lat),
+            lon: position.map(|(_, lon)| lon),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_bounding_boxes() {
+        let bbox: BoundingBox = "38.5, -121, 37,-123".parse().unwrap();
+        assert_eq!(
+            bbox,
+            BoundingBox {
+                min_lat: 37.0,
+                min_lon: -123.0,
+                max_lat: 38.5,
+                max_lon: -121.0,
+            }
+        );
+        assert_eq!(bbox.to_string(), "37.0000,-123.0000,38.5000,-121.0000");
+        assert!(bbox.contains(&coordinates(37.619, -122.3749)));
+        assert!(!bbox.contains(&coordinates(40.0, -122.0)));
+
+        for invalid in [
+            "",
+            "37,-123,38.5",
+            "37,-123,38.5,-121,0",
+            "37,west,38.5,-121",
+        ] {
+            let error = invalid.parse::<BoundingBox>().unwrap_err();
+            assert!(error.starts_with("Invalid bounding box"), "{}", error);
+        }
+        let error = "37,-123,91,-121".parse::<BoundingBox>().unwrap_err();
+        assert!(error.ends_with("is out of range"), "{}", error);
+    }
+
+    #[test]
+    fn boxes_a_radius() {
+        let bbox = BoundingBox::around(&coordinates(0.0, 10.0), 111.2);
+        assert!((bbox.min_lat + 1.0).abs() < 1e-9 && (bbox.max_lat - 1.0).abs() < 1e-9);
+        assert!((bbox.min_lon - 9.0).abs() < 1e-9 && (bbox.max_lon - 11.0).abs() < 1e-9);
+
+        // Wider in longitude away from the equator
+        let bbox = BoundingBox::around(&coordinates(60.0, 10.0), 111.2);
+        assert!((bbox.max_lon - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clamps_boxes_at_the_poles_and_antimeridian() {
+        let bbox = BoundingBox::around(&coordinates(89.5, 0.0), 300.0);
+        assert_eq!(bbox.max_lat, 90.0);
+        assert_eq!((bbox.min_lon, bbox.max_lon), (-180.0, 180.0));
+
+        let bbox = BoundingBox::around(&coordinates(-51.0, 179.5), 100.0);
+        assert_eq!(bbox.max_lon, 180.0);
+        assert!(bbox.min_lon > 178.0);
+        let bbox = BoundingBox::around(&coordinates(-14.3, -179.9), 100.0);
+        assert_eq!(bbox.min_lon, -180.0);
+    }
+
+    #[test]
+    fn validates_state_codes() {
+        assert_eq!(
+            MetarQuery::state(" ca "),
+            Ok(MetarQuery::State("CA".into()))
+        );
+        assert_eq!(MetarQuery::state("on").unwrap().request().0, "@ON");
+        for invalid in ["", "C", "CAL", "C&", "C1", "ÉA"] {
+            assert!(MetarQuery::state(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn includes_reports_in_the_area() {
+        let sfo = report("KSFO", 0, Some((37.619, -122.3749)));
+        let lax = report("KLAX", 0, Some((33.9382, -118.3866)));
+        let unplaced = report("KXYZ", 0, None);
+
+        let bbox = MetarQuery::bounding_box("37,-123,38.5,-121".parse().unwrap());
+        assert!(bbox.includes(&sfo) && !bbox.includes(&lax) && bbox.includes(&unplaced));
+
+        let state = MetarQuery::state("CA").unwrap();
+        assert!(state.includes(&sfo) && state.includes(&lax) && state.includes(&unplaced));
+
+        let radius = MetarQuery::around_station("KSJC", 60.0).unwrap();
+        assert!(radius.includes(&sfo) && !radius.includes(&lax) && !radius.includes(&unplaced));
+    }
+
+    #[test]
+    fn keeps_the_latest_report_in_the_area_per_station() {
+        let query = MetarQuery::bounding_box("37,-123,38.5,-121".parse().unwrap());
+        let reports = vec![
+            report("KSFO", 200, Some((37.619, -122.3749))),
+            report("ksfo", 100, Some((37.619, -122.3749))),
+            report("KLAX", 200, Some((33.9382, -118.3866))),
+            report("KOAK", 200, Some((37.7213, -122.2208))),
+        ];
+        let latest: Vec<(String, Option<i64>)> = query
+            .latest_reports(reports)
+            .into_iter()
+            .map(|metar| (metar.icao_id, metar.obs_time))
+            .collect();
+        assert_eq!(
+            latest,
+            [
+                ("KSFO".to_string(), Some(200)),
+                ("KOAK".to_string(), Some(200))
+            ]
+        );
+    }
+
+    #[test]
+    fn requests_radius_queries_as_boxes() {
+        let (ids, params) = MetarQuery::radius(coordinates(37.0, -122.0), 50.0).request();
+        assert!(ids.is_empty());
+        assert_eq!(params[0].0, "bbox");
+        assert!(
+            MetarQuery::around_station("QQQQ", 50.0).is_err_and(
+                |error| matches!(error, WeatherError::UnknownStation(id) if id == "QQQQ")
+            )
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Built-in station database, for resolving IATA and FAA identifiers to ICAO,
+//! suggesting corrections, and naming and locating stations without the API.
+
+use crate::MetarData;
+use crate::location::Coordinates;
+use crate::units::FEET_PER_METER;
+use std::sync::OnceLock;
+
+/// An airport or weather station from the built-in database
+#[derive(Debug, Clone, PartialEq)]
+pub struct Station {
+    pub icao: &'static str,
+    pub iata: Option<&'static str>,
+    /// FAA location identifier, for US stations
+    pub faa: Option<&'static str>,
+    pub name: &'static str,
+    pub city: Option<&'static str>,
+    /// State or province code, where one is in common use
+    pub region: Option<&'static str>,
+    /// ISO 3166 country code
+    pub country: &'static str,
+    pub lat: f64,
+    pub lon: f64,
+    pub elevation_ft: f64,
+    /// IANA time zone, e.g. `America/Los_Angeles`
+    pub timezone: Option<&'static str>,
+}
+
+impl Station {
+    pub fn elevation_m(&self) -> f64 {
+        self.elevation_ft / FEET_PER_METER
+    }
+
+    /// Name in the style the API uses, e.g. "San Francisco Intl, CA, US"
+    pub fn display_name(&self) -> String {
+        let mut parts = vec![self.name];
+        if let Some(city) = self.city
+            && !self.name.contains(city)
+        {
+            parts.push(city);
+        }
+        parts.extend(self.region);
+        parts.push(self.country);
+        parts.join(", ")
+    }
+
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            lat: self.lat,
+            lon: self.lon,
+        }
+    }
+
+    fn identifiers(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.icao).chain(self.iata).chain(self.faa)
+    }
+}
+
+static STATIONS: OnceLock<Vec<Station>> = OnceLock::new();
+
+/// Every station in the database
+pub fn stations() -> &'static [Station] {
+    STATIONS.get_or_init(|| {
+        const STATION_DATA: &str = include_str!("stations.csv");
+
+        STATION_DATA
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(parse_station)
+            .collect()
+    })
+}
+
+fn parse_station(line: &'static str) -> Option<Station> {
+    let mut fields = line.split(',').map(str::trim);
+    let mut optional = || {
+        fields
+            .next()
+            .map(|field| (!field.is_empty()).then_some(field))
+    };
+    let station = Station {
+        icao: optional()??,
+        iata: optional()?,
+        faa: optional()?,
+        name: optional()??,
+        city: optional()?,
+        region: optional()?,
+        country: optional()??,
+        lat: optional()??.parse().ok()?,
+        lon: optional()??.parse().ok()?,
+        elevation_ft: optional()??.parse().ok()?,
+        timezone: optional()?,
+    };
+    Some(station)
+}
+
+/// Look up a station by ICAO, IATA or FAA identifier, ignoring case.
+/// ICAO identifiers take precedence when codes collide.
+pub fn find_station(id: &str) -> Option<&'static Station> {
+    find_in(stations(), id)
+}
+
+fn find_in<'a>(stations: &'a [Station], id: &str) -> Option<&'a Station> {
+    let id = id.trim().to_uppercase();
+    stations
+        .iter()
+        .find(|station| station.icao == id)
+        .or_else(|| {
+            stations
+                .iter()
+                .find(|station| station.iata == Some(id.as_str()))
+        })
+        .or_else(|| {
+            stations
+                .iter()
+                .find(|station| station.faa == Some(id.as_str()))
+        })
+}
+
+/// The ICAO identifier for `id`, e.g. "KSJC" for "sjc". Three-letter codes
+/// not in the database are taken as FAA identifiers in the contiguous US,
+/// e.g. "KSMF" for "smf"; anything else is returned uppercased, for the API
+/// to judge.
+pub fn resolve_station_id(id: &str) -> String {
+    if let Some(station) = find_station(id) {
+        return station.icao.to_string();
+    }
+    let id = id.trim().to_uppercase();
+    if id.len() == 3 && id.chars().all(|c| c.is_ascii_alphabetic()) {
+        format!("K{}", id)
+    } else {
+        id
+    }
+}
+
+/// Stations whose name or city contains `query`, ignoring case
+pub fn search_stations(query: &str) -> Vec<&'static Station> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    stations()
+        .iter()
+        .filter(|station| {
+            station.name.to_lowercase().contains(&query)
+                || station
+                    .city
+                    .is_some_and(|city| city.to_lowercase().contains(&query))
+        })
+        .collect()
+}
+
+/// A reporting station and where it lies from a search position
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearbyStation {
+    pub icao: String,
+    /// Name from the report or the built-in database, e.g. "San Jose Intl, CA, US"
+    pub name: Option<String>,
+    pub distance_km: f64,
+    /// True bearing from the search position, in degrees
+    pub bearing: f64,
+}
+
+impl NearbyStation {
+    /// Where the station that issued `metar` lies from `position`, if the
+    /// report gives its location
+    pub fn of_report(position: &Coordinates, metar: &MetarData) -> Option<NearbyStation> {
+        let location = Coordinates {
+            lat: metar.lat?,
+            lon: metar.lon?,
+        };
+        Some(NearbyStation {
+            icao: metar.icao_id.to_uppercase(),
+            name: metar.station_name(),
+            distance_km: position.distance_km(&location),
+            bearing: position.bearing_to(&location),
+        })
+    }
+}
+
+/// The reports from the `count` stations closest to `position`, nearest
+/// first. Reports without a location are skipped.
+pub fn nearest_reports<'a>(
+    position: &Coordinates,
+    reports: impl IntoIterator<Item = &'a MetarData>,
+    count: usize,
+) -> Vec<(NearbyStation, MetarData)> {
+    let mut nearby: Vec<(NearbyStation, &MetarData)> = reports
+        .into_iter()
+        .filter_map(|metar| Some((NearbyStation::of_report(position, metar)?, metar)))
+        .collect();
+    nearby.sort_by(|(a, _), (b, _)| a.distance_km.total_cmp(&b.distance_km));
+    nearby.truncate(count);
+    nearby
+        .into_iter()
+        .map(|(station, metar)| (station, metar.clone()))
+        .collect()
+}
+
+/// The `count` stations in the built-in database closest to `position`,
+/// nearest first. Use [`nearest_reports`] or
+/// [`WeatherClient::fetch_nearby_weather`](crate::WeatherClient::fetch_nearby_weather)
+/// to search every reporting station.
+pub fn nearest_stations(position: &Coordinates, count: usize) -> Vec<NearbyStation> {
+    let mut nearby: Vec<NearbyStation> = stations()
+        .iter()
+        .map(|station| {
+            let location = station.coordinates();
+            NearbyStation {
+                icao: station.icao.to_string(),
+                name: Some(station.display_name()),
+                distance_km: position.distance_km(&location),
+                bearing: position.bearing_to(&location),
+            }
+        })
+        .collect();
+    nearby.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+    nearby.truncate(count);
+    nearby
+}
+
+/// Up to `limit` stations `id` may have been meant as: identifiers one
+/// character off, or, for longer input, stations whose name or city matches
+pub fn suggest_stations(id: &str, limit: usize) -> Vec<&'static Station> {
+    let id = id.trim().to_uppercase();
+    let mut scored: Vec<(usize, &'static Station)> = stations()
+        .iter()
+        .filter_map(|station| {
+            let distance = station
+                .identifiers()
+                .map(|code| edit_distance(&id, code))
+                .min()?;
+            (distance <= 1).then_some((distance, station))
+        })
+        .collect();
+    scored.sort_by_key(|(distance, station)| (*distance, station.icao));
+    let mut suggestions: Vec<&'static Station> =
+        scored.into_iter().map(|(_, station)| station).collect();
+
+    if id.len() > 4 {
+        for station in search_stations(&id) {
+            if !suggestions.contains(&station) {
+                suggestions.push(station);
+            }
+        }
+    }
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Levenshtein distance between two identifiers
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_station_lines() {
+        let station = parse_station(
+            "KSJC,SJC,SJC,Norman Y. Mineta San Jose Intl,San Jose,CA,US,37.3626,-121.9291,62,America/Los_Angeles",
+        )
+        .unwrap();
+        assert_eq!(station.icao, "KSJC");
+        assert_eq!(station.faa, Some("SJC"));
+        assert_eq!(station.city, Some("San Jose"));
+        assert_eq!(station.lon, -121.9291);
+        assert_eq!(station.timezone, Some("America/Los_Angeles"));
+        assert_eq!(
+            station.display_name(),
+            "Norman Y. Mineta San Jose Intl, CA, US"
+        );
+
+        let foreign =
+            parse_station("EGLL,LHR,,London Heathrow,London,,GB,51.4706,-0.4619,83,").unwrap();
+        assert_eq!(
+            (foreign.faa, foreign.region, foreign.timezone),
+            (None, None, None)
+        );
+        assert_eq!(foreign.display_name(), "London Heathrow, GB");
+    }
+
+    #[test]
+    fn rejects_incomplete_station_lines() {
+        // No country
+        assert_eq!(parse_station("KXYZ,,,Test Field,,,,37.0,-122.0,10,"), None);
+        assert_eq!(
+            parse_station("KXYZ,,,Test Field,,,US,north,-122.0,10,"),
+            None
+        );
+        // Missing the elevation and time zone columns
+        assert_eq!(parse_station("KXYZ,,,Test Field,,,US,37.0,-122.0"), None);
+    }
+
+    #[test]
+    fn loads_the_built_in_table() {
+        let all = stations();
+        assert!(all.len() > 100);
+        let mut icaos: Vec<&str> = all.iter().map(|station| station.icao).collect();
+        icaos.sort_unstable();
+        icaos.dedup();
+        assert_eq!(icaos.len(), all.len(), "duplicate ICAO identifiers");
+        assert!(
+            all.iter()
+                .all(|station| station.coordinates().lat.abs() <= 90.0)
+        );
+    }
+
+    #[test]
+    fn prefers_icao_then_iata_then_faa_identifiers() {
+        let station = |icao, iata, faa| Station {
+            icao,
+            iata,
+            faa,
+            name: icao,
+            city: None,
+            region: None,
+            country: "US",
+            lat: 0.0,
+            lon: 0.0,
+            elevation_ft: 0.0,
+            timezone: None,
+        };
+        let table = [
+            station("PAAA", None, Some("XYZ")),
+            station("KAAA", Some("XYZ"), Some("ABC")),
+            station("XYZZ", None, None),
+            station("KBBB", None, Some("XYZZ")),
+        ];
+        let found = |id| find_in(&table, id).map(|station| station.icao);
+        assert_eq!(found("xyzz"), Some("XYZZ"));
+        assert_eq!(found("XYZ"), Some("KAAA"));
+        assert_eq!(found(" abc "), Some("KAAA"));
+        assert_eq!(found("QQQ"), None);
+    }
+
+    #[test]
+    fn finds_stations_by_any_identifier() {
+        assert_eq!(find_station("sjc").unwrap().icao, "KSJC");
+        assert_eq!(find_station("LHR").unwrap().icao, "EGLL");
+        assert_eq!(find_station(" egll ").unwrap().icao, "EGLL");
+        assert_eq!(find_station("ZZZZ"), None);
+    }
+
+    #[test]
+    fn resolves_identifiers_to_icao() {
+        assert_eq!(resolve_station_id("sjc"), "KSJC");
+        assert_eq!(resolve_station_id("yyz"), "CYYZ");
+        assert_eq!(resolve_station_id("egll"), "EGLL");
+        // Not in the table: three letters are taken as a US airport
+        assert_eq!(resolve_station_id("qqq"), "KQQQ");
+        assert_eq!(resolve_station_id("e16"), "KE16");
+        assert_eq!(resolve_station_id("x99"), "X99");
+        assert_eq!(resolve_station_id(" zzzz "), "ZZZZ");
+    }
+
+    #[test]
+    fn suggests_identifiers_one_character_off() {
+        let suggested = |id, limit| -> Vec<&str> {
+            suggest_stations(id, limit)
+                .iter()
+                .map(|station| station.icao)
+                .collect()
+        };
+        assert_eq!(suggested("KSFP", 1), ["KSBP"]);
+        assert!(suggested("KSFP", 5).contains(&"KSFO"));
+        assert_eq!(suggested("LHX", 3), ["EGLL", "KLAX", "KPHX"]);
+        assert!(suggested("QQQQ", 3).is_empty());
+        // Longer input is searched for in names and cities
+        assert!(suggested("heathrow", 3).contains(&"EGLL"));
+    }
+
+    #[test]
+    fn measures_edit_distance() {
+        assert_eq!(edit_distance("KSFO", "KSFO"), 0);
+        assert_eq!(edit_distance("KSFO", "KSFX"), 1);
+        assert_eq!(edit_distance("SFO", "KSFO"), 1);
+        assert_eq!(edit_distance("KSFO", "KSOF"), 2);
+        assert_eq!(edit_distance("", "EGLL"), 4);
+    }
+}
@@ -0,0 +1,529 @@
+//! Terminal Aerodrome Forecast (TAF) data and decoder.
+
+use crate::WeatherPhenomenon;
+use crate::metar::{
+    CloudLayer, DayTime, Visibility, Wind, all_digits, is_station, parse_cloud_layer,
+    parse_day_time, parse_visibility, parse_wind,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// A TAF as returned by the aviationweather.gov data API
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TafData {
+    #[serde(rename = "icaoId")]
+    pub icao_id: String,
+    #[serde(rename = "issueTime")]
+    pub issue_time: Option<String>,
+    #[serde(rename = "bulletinTime")]
+    pub bulletin_time: Option<String>,
+    /// Start of the validity period in seconds since the Unix epoch
+    #[serde(rename = "validTimeFrom")]
+    pub valid_time_from: Option<i64>,
+    /// End of the validity period in seconds since the Unix epoch
+    #[serde(rename = "validTimeTo")]
+    pub valid_time_to: Option<i64>,
+    #[serde(rename = "rawTAF")]
+    pub raw_taf: String,
+    #[serde(rename = "mostRecent")]
+    pub most_recent: Option<i64>,
+    pub remarks: Option<String>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub elev: Option<f64>,
+    pub name: Option<String>,
+}
+
+impl TafData {
+    /// Decode the raw TAF text into forecast groups
+    pub fn taf(&self) -> Result<Taf, TafParseError> {
+        Taf::parse(&self.raw_taf)
+    }
+
+    /// Station name from the API, or from the station database if the API
+    /// gave none
+    pub fn station_name(&self) -> Option<String> {
+        self.name
+            .clone()
+            .or_else(|| crate::find_station(&self.icao_id).map(crate::Station::display_name))
+    }
+
+    /// Start of the validity period, used to anchor the day-of-month times in the TAF
+    pub fn valid_from(&self) -> Option<DateTime<Utc>> {
+        self.valid_time_from
+            .and_then(|t| DateTime::from_timestamp(t, 0))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TafParseError {
+    Empty,
+    MissingStation,
+    InvalidStation(String),
+    MissingValidity,
+    InvalidValidity(String),
+    NilReport(String),
+}
+
+impl fmt::Display for TafParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TafParseError::Empty => write!(f, "TAF is empty"),
+            TafParseError::MissingStation => write!(f, "TAF has no station identifier"),
+            TafParseError::InvalidStation(s) => write!(f, "Invalid station identifier: {}", s),
+            TafParseError::MissingValidity => write!(f, "TAF has no validity period"),
+            TafParseError::InvalidValidity(s) => write!(f, "Invalid validity period: {}", s),
+            TafParseError::NilReport(s) => write!(f, "NIL TAF for station {}", s),
+        }
+    }
+}
+
+impl std::error::Error for TafParseError {}
+
+/// A `DDHH/DDHH` period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidityPeriod {
+    pub from: DayTime,
+    pub to: DayTime,
+}
+
+impl ValidityPeriod {
+    /// Resolve both ends to absolute times near `reference`
+    pub fn resolve(&self, reference: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let from = self.from.resolve(reference)?;
+        let to = self.to.resolve(from)?;
+        Some((from, to))
+    }
+}
+
+impl fmt::Display for ValidityPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}/{:02}{:02}Z to {:02}/{:02}{:02}Z",
+            self.from.day,
+            self.from.hour,
+            self.from.minute,
+            self.to.day,
+            self.to.hour,
+            self.to.minute
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeIndicator {
+    /// Conditions at the start of the TAF, before any change group
+    Initial,
+    /// FM, a rapid and permanent change at the given time
+    From,
+    /// BECMG, a gradual change during the period
+    Becoming,
+    /// TEMPO, temporary fluctuations during the period
+    Temporary,
+    /// PROB30/PROB40
+    Probability(u8),
+    /// PROB30 TEMPO/PROB40 TEMPO
+    ProbabilityTemporary(u8),
+}
+
+impl fmt::Display for ChangeIndicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeIndicator::Initial => write!(f, "Initial"),
+            ChangeIndicator::From => write!(f, "From"),
+            ChangeIndicator::Becoming => write!(f, "Becoming"),
+            ChangeIndicator::Temporary => write!(f, "Temporary"),
+            ChangeIndicator::Probability(p) => write!(f, "{}% probability", p),
+            ChangeIndicator::ProbabilityTemporary(p) => {
+                write!(f, "{}% probability, temporary", p)
+            }
+        }
+    }
+}
+
+/// One forecast period of a TAF and the conditions expected during it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastGroup {
+    pub change: ChangeIndicator,
+    pub period: ValidityPeriod,
+    pub wind: Option<Wind>,
+    pub visibility: Option<Visibility>,
+    pub cavok: bool,
+    pub weather: Vec<WeatherPhenomenon>,
+    /// NSW, the end of significant weather
+    pub no_significant_weather: bool,
+    pub sky: Vec<CloudLayer>,
+    /// Low-level wind shear group such as `WS020/24045KT`
+    pub wind_shear: Option<String>,
+    pub unparsed: Vec<String>,
+}
+
+impl ForecastGroup {
+    fn new(change: ChangeIndicator, period: ValidityPeriod) -> ForecastGroup {
+        ForecastGroup {
+            change,
+            period,
+            wind: None,
+            visibility: None,
+            cavok: false,
+            weather: Vec::new(),
+            no_significant_weather: false,
+            sky: Vec::new(),
+            wind_shear: None,
+            unparsed: Vec::new(),
+        }
+    }
+
+    fn add_token(&mut self, token: &str) {
+        if token == "CAVOK" {
+            self.cavok = true;
+        } else if token == "NSW" {
+            self.no_significant_weather = true;
+        } else if token.starts_with("WS") && token.contains('/') {
+            self.wind_shear = Some(token.to_string());
+        } else if self.wind.is_none()
+            && let Some(wind) = parse_wind(token)
+        {
+            self.wind = Some(wind);
+        } else if self.visibility.is_none()
+            && let Some(vis) = parse_visibility(token)
+        {
+            self.visibility = Some(vis);
+        } else if let Some(phenomenon) = WeatherPhenomenon::parse(token) {
+            self.weather.push(phenomenon);
+        } else if let Some(layer) = parse_cloud_layer(token) {
+            self.sky.push(layer);
+        } else {
+            self.unparsed.push(token.to_string());
+        }
+    }
+}
+
+/// A decoded TAF
+#[derive(Debug, Clone, PartialEq)]
+pub struct Taf {
+    pub station: String,
+    pub issue_time: Option<DayTime>,
+    pub amended: bool,
+    pub corrected: bool,
+    pub validity: ValidityPeriod,
+    /// Forecast groups in the order they appear, starting with the initial conditions
+    pub groups: Vec<ForecastGroup>,
+    pub remarks: Option<String>,
+}
+
+impl Taf {
+    pub fn parse(raw: &str) -> Result<Taf, TafParseError> {
+        let tokens: Vec<&str> = raw
+            .split_whitespace()
+            .map(|t| t.trim_end_matches('='))
+            .filter(|t| !t.is_empty())
+            .collect();
+        if tokens.is_empty() {
+            return Err(TafParseError::Empty);
+        }
+
+        let mut i = 0;
+        let mut amended = false;
+        let mut corrected = false;
+        while i < tokens.len() && matches!(tokens[i], "TAF" | "AMD" | "COR") {
+            match tokens[i] {
+                "AMD" => amended = true,
+                "COR" => corrected = true,
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let station = *tokens.get(i).ok_or(TafParseError::MissingStation)?;
+        if !is_station(station) {
+            return Err(TafParseError::InvalidStation(station.to_string()));
+        }
+        i += 1;
+
+        let issue_time = tokens.get(i).and_then(|t| parse_day_time(t));
+        if issue_time.is_some() {
+            i += 1;
+        }
+
+        let validity_token = *tokens.get(i).ok_or(TafParseError::MissingValidity)?;
+        if validity_token == "NIL" {
+            return Err(TafParseError::NilReport(station.to_string()));
+        }
+        let validity = parse_period(validity_token)
+            .ok_or_else(|| TafParseError::InvalidValidity(validity_token.to_string()))?;
+        i += 1;
+
+        let mut groups = vec![ForecastGroup::new(ChangeIndicator::Initial, validity)];
+        let mut remarks = None;
+        while i < tokens.len() {
+            let token = tokens[i];
+            if token == "RMK" {
+                let text = tokens[i + 1..].join(" ");
+                if !text.is_empty() {
+                    remarks = Some(text);
+                }
+                break;
+            }
+
+            if let Some(from) = token.strip_prefix("FM").and_then(parse_fm_time) {
+                let period = ValidityPeriod {
+                    from,
+                    to: validity.to,
+                };
+                groups.push(ForecastGroup::new(ChangeIndicator::From, period));
+            } else if let Some(change) = parse_change_keyword(token) {
+                let mut change = change;
+                if let ChangeIndicator::Probability(p) = change
+                    && tokens.get(i + 1) == Some(&"TEMPO")
+                {
+                    change = ChangeIndicator::ProbabilityTemporary(p);
+                    i += 1;
+                }
+                let period = match tokens.get(i + 1).and_then(|t| parse_period(t)) {
+                    Some(period) => {
+                        i += 1;
+                        period
+                    }
+                    None => validity,
+                };
+                groups.push(ForecastGroup::new(change, period));
+            } else if let Some(group) = groups.last_mut() {
+                group.add_token(token);
+            }
+            i += 1;
+        }
+
+        // Each FM group (and the initial conditions) lasts until the next FM group
+        let fm_starts: Vec<(usize, DayTime)> = groups
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.change == ChangeIndicator::From)
+            .map(|(idx, g)| (idx, g.period.from))
+            .collect();
+        for (idx, group) in groups.iter_mut().enumerate() {
+            if matches!(
+                group.change,
+                ChangeIndicator::Initial | ChangeIndicator::From
+            ) && let Some((_, next)) = fm_starts.iter().find(|(fm_idx, _)| *fm_idx > idx)
+            {
+                group.period.to = *next;
+            }
+        }
+
+        Ok(Taf {
+            station: station.to_string(),
+            issue_time,
+            amended,
+            corrected,
+            validity,
+            groups,
+            remarks,
+        })
+    }
+
+    /// Forecast groups in effect at any point between `start` and `end`
+    pub fn groups_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<&ForecastGroup> {
+        self.groups
+            .iter()
+            .filter(|group| match group.period.resolve(start) {
+                Some((from, to)) => from < end && to > start,
+                None => false,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Taf {
+    type Err = TafParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Taf::parse(s)
+    }
+}
+
+fn parse_period(s: &str) -> Option<ValidityPeriod> {
+    let (from, to) = s.split_once('/')?;
+    if from.len() != 4 || to.len() != 4 || !all_digits(from) || !all_digits(to) {
+        return None;
+    }
+    let day_hour = |t: &str| -> Option<DayTime> {
+        let time = DayTime {
+            day: t[0..2].parse().ok()?,
+            hour: t[2..4].parse().ok()?,
+            minute: 0,
+        };
+        (time.day >= 1 && time.day <= 31 && time.hour <= 24).then_some(time)
+    };
+    Some(ValidityPeriod {
+        from: day_hour(from)?,
+        to: day_hour(to)?,
+    })
+}
+
+fn parse_fm_time(s: &str) -> Option<DayTime> {
+    if s.len() != 6 || !all_digits(s) {
+        return None;
+    }
+    parse_day_time(&format!("{}Z", s))
+}
+
+fn parse_change_keyword(s: &str) -> Option<ChangeIndicator> {
+    match s {
+        "BECMG" => Some(ChangeIndicator::Becoming),
+        "TEMPO" => Some(ChangeIndicator::Temporary),
+        "PROB30" => Some(ChangeIndicator::Probability(30)),
+        "PROB40" => Some(ChangeIndicator::Probability(40)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metar::{CloudCover, DistanceUnit};
+    use chrono::TimeZone;
+
+    const KSFO: &str = "TAF KSFO 171720Z 1718/1824 29012KT P6SM FEW015 SCT200 \
+        FM180200 30008KT P6SM SKC \
+        FM181000 VRB04KT 4SM BR OVC008 \
+        TEMPO 1812/1816 2SM BR OVC004 \
+        PROB30 1818/1822 3SM -SHRA BKN020 \
+        BECMG 1820/1822 27010KT";
+
+    const EGLL: &str = "TAF AMD EGLL 171658Z 1718/1824 24012KT 9999 SCT035 \
+        PROB40 TEMPO 1806/1810 3000 RA BKN012 \
+        BECMG 1812/1815 27015G25KT CAVOK \
+        TEMPO 1818/1824 4000 SHRA NSW=";
+
+    fn day_hour(day: u8, hour: u8) -> DayTime {
+        DayTime {
+            day,
+            hour,
+            minute: 0,
+        }
+    }
+
+    #[test]
+    fn decodes_header_and_validity() {
+        let taf = Taf::parse(KSFO).unwrap();
+        assert_eq!(taf.station, "KSFO");
+        assert_eq!(
+            taf.issue_time,
+            Some(DayTime {
+                day: 17,
+                hour: 17,
+                minute: 20
+            })
+        );
+        assert!(!taf.amended);
+        assert_eq!(taf.validity.from, day_hour(17, 18));
+        assert_eq!(taf.validity.to, day_hour(18, 24));
+        assert_eq!(taf.groups.len(), 6);
+    }
+
+    #[test]
+    fn from_groups_last_until_the_next_one() {
+        let taf = Taf::parse(KSFO).unwrap();
+        let initial = &taf.groups[0];
+        assert_eq!(initial.change, ChangeIndicator::Initial);
+        assert_eq!(initial.period.to, day_hour(18, 2));
+        assert_eq!(initial.wind.as_ref().unwrap().speed, 12);
+        assert_eq!(initial.sky.len(), 2);
+
+        let first = &taf.groups[1];
+        assert_eq!(first.change, ChangeIndicator::From);
+        assert_eq!(first.period.from, day_hour(18, 2));
+        assert_eq!(first.period.to, day_hour(18, 10));
+        assert_eq!(first.sky[0].cover, CloudCover::SkyClear);
+
+        let second = &taf.groups[2];
+        assert_eq!(second.period.from, day_hour(18, 10));
+        assert_eq!(second.period.to, day_hour(18, 24));
+        assert!(second.wind.as_ref().unwrap().is_variable());
+        assert_eq!(second.visibility.as_ref().unwrap().distance, 4.0);
+        assert_eq!(second.weather[0].code(), "BR");
+    }
+
+    #[test]
+    fn decodes_tempo_prob_and_becmg_groups() {
+        let taf = Taf::parse(KSFO).unwrap();
+        let tempo = &taf.groups[3];
+        assert_eq!(tempo.change, ChangeIndicator::Temporary);
+        assert_eq!(tempo.period.from, day_hour(18, 12));
+        assert_eq!(tempo.period.to, day_hour(18, 16));
+        assert_eq!(tempo.sky[0].base, Some(400));
+
+        let prob = &taf.groups[4];
+        assert_eq!(prob.change, ChangeIndicator::Probability(30));
+        assert_eq!(prob.weather[0].code(), "-SHRA");
+
+        let becmg = &taf.groups[5];
+        assert_eq!(becmg.change, ChangeIndicator::Becoming);
+        assert_eq!(becmg.period.from, day_hour(18, 20));
+        assert_eq!(becmg.wind.as_ref().unwrap().direction, Some(270));
+        assert!(taf.groups.iter().all(|g| g.unparsed.is_empty()));
+    }
+
+    #[test]
+    fn decodes_prob_tempo_cavok_and_nsw() {
+        let taf = Taf::parse(EGLL).unwrap();
+        assert!(taf.amended);
+        assert_eq!(taf.groups.len(), 4);
+
+        let prob = &taf.groups[1];
+        assert_eq!(prob.change, ChangeIndicator::ProbabilityTemporary(40));
+        assert_eq!(prob.period.from, day_hour(18, 6));
+        let vis = prob.visibility.as_ref().unwrap();
+        assert_eq!(vis.distance, 3000.0);
+        assert_eq!(vis.unit, DistanceUnit::Meters);
+
+        let becmg = &taf.groups[2];
+        assert!(becmg.cavok);
+        assert_eq!(becmg.wind.as_ref().unwrap().gust, Some(25));
+
+        let tempo = &taf.groups[3];
+        assert!(tempo.no_significant_weather);
+        assert_eq!(tempo.weather[0].code(), "SHRA");
+        assert!(taf.groups.iter().all(|g| g.unparsed.is_empty()));
+    }
+
+    #[test]
+    fn keeps_wind_shear_and_remarks() {
+        let taf = Taf::parse(
+            "TAF KDEN 171730Z 1718/1824 18015KT P6SM SCT080 WS020/24045KT RMK NXT FCST BY 00Z",
+        )
+        .unwrap();
+        assert_eq!(taf.groups[0].wind_shear.as_deref(), Some("WS020/24045KT"));
+        assert_eq!(taf.remarks.as_deref(), Some("NXT FCST BY 00Z"));
+    }
+
+    #[test]
+    fn reports_nil_and_malformed_tafs() {
+        assert_eq!(
+            Taf::parse("TAF KXYZ 171720Z NIL"),
+            Err(TafParseError::NilReport("KXYZ".to_string()))
+        );
+        assert_eq!(Taf::parse(""), Err(TafParseError::Empty));
+        assert_eq!(
+            Taf::parse("TAF KXYZ 171720Z 1718/1899"),
+            Err(TafParseError::InvalidValidity("1718/1899".to_string()))
+        );
+        assert_eq!(
+            Taf::parse("TAF KXYZ 171720Z"),
+            Err(TafParseError::MissingValidity)
+        );
+    }
+
+    #[test]
+    fn selects_groups_in_effect() {
+        let taf = Taf::parse(KSFO).unwrap();
+        let start = Utc.with_ymd_and_hms(2025, 6, 18, 11, 0, 0).unwrap();
+        let groups = taf.groups_between(start, start + chrono::Duration::hours(2));
+        let changes: Vec<ChangeIndicator> = groups.iter().map(|g| g.change).collect();
+        assert_eq!(changes, [ChangeIndicator::From, ChangeIndicator::Temporary]);
+    }
+}
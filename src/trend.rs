@@ -0,0 +1,333 @@
+//! Trend analysis over a series of observations from one station.
+
+use crate::MetarData;
+use crate::units::Units;
+
+/// Pressure change per hour (0.06 inHg) at which METAR reports PRESRR/PRESFR
+const RAPID_PRESSURE_CHANGE_HPA_PER_HOUR: f64 = 2.0;
+const STEADY_PRESSURE_HPA: f64 = 0.5;
+const STEADY_TEMPERATURE_C: f64 = 1.0;
+const STEADY_WIND_KT: f64 = 5.0;
+const STEADY_CEILING_FT: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tendency {
+    RisingRapidly,
+    Rising,
+    Steady,
+    Falling,
+    FallingRapidly,
+}
+
+/// First and last value of a quantity over the analyzed period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Change {
+    pub from: f64,
+    pub to: f64,
+}
+
+impl Change {
+    pub fn delta(&self) -> f64 {
+        self.to - self.from
+    }
+
+    fn tendency(&self, steady_threshold: f64) -> Tendency {
+        let delta = self.delta();
+        if delta.abs() < steady_threshold {
+            Tendency::Steady
+        } else if delta > 0.0 {
+            Tendency::Rising
+        } else {
+            Tendency::Falling
+        }
+    }
+}
+
+/// Changes between the oldest and newest observations in a history
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendReport {
+    pub station: String,
+    pub observations: usize,
+    /// Time between the oldest and newest observation
+    pub span_hours: f64,
+    /// Degrees Celsius
+    pub temperature: Option<Change>,
+    /// Degrees Celsius
+    pub dewpoint: Option<Change>,
+    /// Altimeter setting in hectopascals
+    pub pressure: Option<Change>,
+    /// Sustained wind speed in knots
+    pub wind_speed: Option<Change>,
+    /// Lowest ceiling in feet at the start and end; `None` means no ceiling
+    pub ceiling: Option<(Option<u32>, Option<u32>)>,
+}
+
+impl TrendReport {
+    pub fn temperature_tendency(&self) -> Option<Tendency> {
+        self.temperature.map(|c| c.tendency(STEADY_TEMPERATURE_C))
+    }
+
+    pub fn pressure_tendency(&self) -> Option<Tendency> {
+        let change = self.pressure?;
+        let rate = if self.span_hours > 0.0 {
+            change.delta().abs() / self.span_hours
+        } else {
+            0.0
+        };
+        Some(match change.tendency(STEADY_PRESSURE_HPA) {
+            Tendency::Rising if rate >= RAPID_PRESSURE_CHANGE_HPA_PER_HOUR => {
+                Tendency::RisingRapidly
+            }
+            Tendency::Falling if rate >= RAPID_PRESSURE_CHANGE_HPA_PER_HOUR => {
+                Tendency::FallingRapidly
+            }
+            tendency => tendency,
+        })
+    }
+
+    pub fn wind_tendency(&self) -> Option<Tendency> {
+        self.wind_speed.map(|c| c.tendency(STEADY_WIND_KT))
+    }
+
+    /// Rising means the ceiling is lifting (or clearing), falling means lowering
+    pub fn ceiling_tendency(&self) -> Option<Tendency> {
+        let (from, to) = self.ceiling?;
+        Some(match (from, to) {
+            (None, None) => Tendency::Steady,
+            (Some(_), None) => Tendency::Rising,
+            (None, Some(_)) => Tendency::Falling,
+            (Some(from), Some(to)) => {
+                let delta = to as i64 - from as i64;
+                if delta.abs() < STEADY_CEILING_FT {
+                    Tendency::Steady
+                } else if delta > 0 {
+                    Tendency::Rising
+                } else {
+                    Tendency::Falling
+                }
+            }
+        })
+    }
+
+    /// Human-readable summary lines in the given units, e.g.
+    /// "Pressure falling rapidly (-6.8 hPa, 1015 hPa to 1008 hPa)"
+    pub fn describe(&self, units: &Units) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        if let (Some(tendency), Some(change)) = (self.pressure_tendency(), self.pressure) {
+            let word = match tendency {
+                Tendency::RisingRapidly => "rising rapidly",
+                Tendency::Rising => "rising",
+                Tendency::Steady => "steady",
+                Tendency::Falling => "falling",
+                Tendency::FallingRapidly => "falling rapidly",
+            };
+            lines.push(format!(
+                "Pressure {} ({:+.*} {}, {} to {})",
+                word,
+                units.pressure.decimals().max(1),
+                units.pressure.from_hpa(change.delta()),
+                units.pressure.abbreviation(),
+                units.format_pressure(change.from),
+                units.format_pressure(change.to)
+            ));
+        }
+
+        if let (Some(tendency), Some(change)) = (self.temperature_tendency(), self.temperature) {
+            let word = match tendency {
+                Tendency::Steady => "steady",
+                Tendency::Falling | Tendency::FallingRapidly => "falling",
+                _ => "rising",
+            };
+            lines.push(format!(
+                "Temperature {} ({:+.1}{}, {} to {})",
+                word,
+                units.temperature.difference_from_celsius(change.delta()),
+                units.temperature.symbol(),
+                units.format_temperature(change.from),
+                units.format_temperature(change.to)
+            ));
+        }
+
+        if let Some(dewpoint) = self.dewpoint {
+            lines.push(format!(
+                "Dewpoint {:+.1}{} ({} to {})",
+                units.temperature.difference_from_celsius(dewpoint.delta()),
+                units.temperature.symbol(),
+                units.format_temperature(dewpoint.from),
+                units.format_temperature(dewpoint.to)
+            ));
+        }
+
+        if let (Some(tendency), Some(change)) = (self.wind_tendency(), self.wind_speed) {
+            let word = match tendency {
+                Tendency::Steady => "steady",
+                Tendency::Falling | Tendency::FallingRapidly => "decreasing",
+                _ => "increasing",
+            };
+            let unit = units.wind_speed.abbreviation();
+            lines.push(format!(
+                "Wind {} ({} {} to {} {})",
+                word,
+                units.wind_speed(change.from),
+                unit,
+                units.wind_speed(change.to),
+                unit
+            ));
+        }
+
+        if let (Some(tendency), Some((from, to))) = (self.ceiling_tendency(), self.ceiling) {
+            let word = match tendency {
+                Tendency::Steady => "steady",
+                Tendency::Falling | Tendency::FallingRapidly => "lowering",
+                _ => "rising",
+            };
+            let height = |c: Option<u32>| match c {
+                Some(ft) => units.format_altitude(ft as f64),
+                None => "none".to_string(),
+            };
+            lines.push(format!(
+                "Ceiling {} ({} to {})",
+                word,
+                height(from),
+                height(to)
+            ));
+        }
+
+        lines
+    }
+}
+
+fn change_of(oldest: Option<f64>, newest: Option<f64>) -> Option<Change> {
+    Some(Change {
+        from: oldest?,
+        to: newest?,
+    })
+}
+
+/// Compare the oldest and newest observations in `history`.
+///
+/// The history may be in any order; observations are ordered by their
+/// observation time. Returns `None` if fewer than two observations are given.
+pub fn analyze_trends(history: &[MetarData]) -> Option<TrendReport> {
+    if history.len() < 2 {
+        return None;
+    }
+
+    let mut ordered: Vec<&MetarData> = history.iter().collect();
+    ordered.sort_by_key(|metar| metar.obs_time);
+    let oldest = ordered[0];
+    let newest = ordered[ordered.len() - 1];
+
+    let span_hours = match (oldest.obs_time, newest.obs_time) {
+        (Some(from), Some(to)) => (to - from) as f64 / 3600.0,
+        _ => 0.0,
+    };
+
+    Some(TrendReport {
+        station: newest.icao_id.clone(),
+        observations: history.len(),
+        span_hours,
+        temperature: change_of(oldest.temperature(), newest.temperature()),
+        dewpoint: change_of(oldest.dewpoint(), newest.dewpoint()),
+        pressure: change_of(
+            oldest.altimeter().map(|a| a.hpa()),
+            newest.altimeter().map(|a| a.hpa()),
+        ),
+        wind_speed: change_of(
+            oldest.wind().map(|w| w.speed_knots()),
+            newest.wind().map(|w| w.speed_knots()),
+        ),
+        ceiling: Some((oldest.ceiling(), newest.ceiling())),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A report decoded from `raw`, observed `hour` hours into the period
+    pub(crate) fn report(raw: &str, hour: i64) -> MetarData {
+        MetarData {
+            obs_time: Some(1_750_000_000 + hour * 3600),
+            ..MetarData::from_raw(raw).unwrap()
+        }
+    }
+
+    #[test]
+    fn needs_two_observations() {
+        let one = [report("KSFO 171256Z 29012KT 10SM FEW015 16/11 A3002", 0)];
+        assert_eq!(analyze_trends(&one), None);
+        assert_eq!(analyze_trends(&[]), None);
+    }
+
+    #[test]
+    fn compares_oldest_and_newest_in_any_order() {
+        let history = [
+            report("KSFO 171556Z 29025KT 10SM BKN008 12/10 A2980", 3),
+            report("KSFO 171356Z 29015KT 10SM SCT020 15/11 A2995", 1),
+            report("KSFO 171256Z 29010KT 10SM FEW015 18/11 A3002", 0),
+        ];
+        let trends = analyze_trends(&history).unwrap();
+        assert_eq!(trends.station, "KSFO");
+        assert_eq!(trends.observations, 3);
+        assert_eq!(trends.span_hours, 3.0);
+        assert_eq!(
+            trends.temperature,
+            Some(Change {
+                from: 18.0,
+                to: 12.0
+            })
+        );
+        assert_eq!(trends.temperature_tendency(), Some(Tendency::Falling));
+        assert_eq!(trends.wind_tendency(), Some(Tendency::Rising));
+        assert_eq!(trends.ceiling, Some((None, Some(800))));
+        assert_eq!(trends.ceiling_tendency(), Some(Tendency::Falling));
+    }
+
+    #[test]
+    fn distinguishes_rapid_pressure_changes() {
+        // 0.22 inHg (7.5 hPa) in two hours is rapid; over six hours it is not
+        let rapid = [
+            report("KORD 171256Z 18010KT 10SM OVC030 10/05 A3000", 0),
+            report("KORD 171456Z 18010KT 10SM OVC030 10/05 A2978", 2),
+        ];
+        let trends = analyze_trends(&rapid).unwrap();
+        assert_eq!(trends.pressure_tendency(), Some(Tendency::FallingRapidly));
+
+        let slow = [
+            report("KORD 171256Z 18010KT 10SM OVC030 10/05 A2978", 0),
+            report("KORD 171856Z 18010KT 10SM OVC030 10/05 A3000", 6),
+        ];
+        let trends = analyze_trends(&slow).unwrap();
+        assert_eq!(trends.pressure_tendency(), Some(Tendency::Rising));
+        assert_eq!(trends.temperature_tendency(), Some(Tendency::Steady));
+        assert_eq!(trends.ceiling_tendency(), Some(Tendency::Steady));
+    }
+
+    #[test]
+    fn describes_the_changes() {
+        let history = [
+            report("KORD 171256Z 18010KT 10SM CLR 10/05 A3000", 0),
+            report("KORD 171456Z 18010KT 10SM OVC015 12/06 A2978", 2),
+        ];
+        let trends = analyze_trends(&history).unwrap();
+        assert_eq!(
+            trends.describe(&Units::aviation()),
+            [
+                "Pressure falling rapidly (-7.5 hPa, 1016 hPa to 1008 hPa)",
+                "Temperature rising (+2.0°C, 10.0°C to 12.0°C)",
+                "Dewpoint +1.0°C (5.0°C to 6.0°C)",
+                "Wind steady (10 kt to 10 kt)",
+                "Ceiling lowering (none to 1500 ft)",
+            ]
+        );
+        assert_eq!(
+            trends.describe(&Units::imperial())[..2],
+            [
+                "Pressure falling rapidly (-0.22 inHg, 30.00 inHg to 29.78 inHg)",
+                "Temperature rising (+3.6°F, 50.0°F to 53.6°F)",
+            ]
+        );
+    }
+}
@@ -1,4 +1,14 @@
-use crate::{MetarData, celsius_to_fahrenheit, expand_abbreviations, parse_wmo_codes};
+use crate::metar::{Bound, CloudCover, CloudLayer, DayTime, Visibility};
+use crate::taf::{ChangeIndicator, ForecastGroup};
+use crate::trend::{Tendency, analyze_trends};
+use crate::tts::atis::{InformationLetters, derived_letter};
+use crate::tts::language::{Language, Term};
+use crate::tts::phraseology::{Phraseology, Wording, phonetic_letter};
+use crate::tts::templates::AnnouncementTemplate;
+use crate::units::{FEET_PER_METER, Units, format_number};
+use crate::{DecodedReport, MetarData, Result, TafData, WeatherPhenomenon, expand_abbreviations};
+use chrono::{Datelike, Timelike};
+use serde::Serialize;
 
 #[derive(Debug, Clone, clap::ValueEnum)]
 pub enum AnnouncementFormat {
@@ -10,121 +20,703 @@ pub enum AnnouncementFormat {
     Detailed,
     /// Aviation radio style
     Aviation,
+    /// Current conditions followed by recent trends
+    Trend,
+    /// ATIS/AWOS broadcast with an information letter
+    Atis,
 }
 
-fn spell_out_icao(icao: &str) -> String {
-    icao.chars()
-        .map(|c| c.to_string())
-        .collect::<Vec<_>>()
-        .join(" ")
+impl AnnouncementFormat {
+    /// Aviation reads identifiers and numbers the way pilots do; the others
+    /// read them plainly
+    pub fn default_phraseology(&self) -> Phraseology {
+        match self {
+            AnnouncementFormat::Aviation | AnnouncementFormat::Atis => Phraseology::Aviation,
+            _ => Phraseology::Plain,
+        }
+    }
 }
 
-pub fn generate_weather_announcement(metar: &MetarData, format: &AnnouncementFormat) -> String {
-    match format {
-        AnnouncementFormat::Speech | AnnouncementFormat::Brief => {
-            let mut announcement = format!("Weather for {}... ", spell_out_icao(&metar.icao_id));
+/// Settings shared by all announcement formats
+#[derive(Debug, Clone, Default)]
+pub struct AnnouncementOptions {
+    pub units: Units,
+    /// Overrides the format's default phraseology
+    pub phraseology: Option<Phraseology>,
+    /// Letters for the ATIS format; stations without one get a letter
+    /// derived from the observation time
+    pub information_letters: InformationLetters,
+    /// Replaces the format's built-in template for METAR announcements
+    pub template: Option<AnnouncementTemplate>,
+    /// Language of every announcement
+    pub language: Language,
+}
 
-            if let Some(ref name) = metar.name {
-                announcement.push_str(&format!("{}... ", expand_abbreviations(name)));
-            }
+impl AnnouncementOptions {
+    pub fn phraseology(&self, format: &AnnouncementFormat) -> Phraseology {
+        self.phraseology
+            .unwrap_or_else(|| format.default_phraseology())
+    }
 
-            if let Some(temp_c) = metar.temp {
-                let temp_f = celsius_to_fahrenheit(temp_c);
-                announcement.push_str(&format!(
-                    "Temperature... {} degrees fahrenheit... ",
-                    temp_f.round() as i32
-                ));
-            }
+    /// The phraseology for `format` in the announcement language
+    pub fn wording(&self, format: &AnnouncementFormat) -> Wording {
+        self.phraseology(format).in_language(self.language)
+    }
+}
 
-            if let Some(ref wx) = metar.wx_string {
-                let codes = parse_wmo_codes(wx);
-                if !codes.is_empty() {
-                    announcement.push_str("Current conditions... ");
-                    let conditions: Vec<String> =
-                        codes.iter().map(|c| c.description().to_string()).collect();
-                    announcement.push_str(&conditions.join("... "));
-                    announcement.push_str("...");
-                } else {
-                    announcement.push_str("Clear conditions...");
-                }
-            } else {
-                announcement.push_str("Clear conditions...");
-            }
+/// Density altitude this far above the field elevation is called out in detailed reports
+const HIGH_DENSITY_ALTITUDE_FT: f64 = 1000.0;
 
-            announcement
-        }
+fn spoken_visibility(vis: &Visibility, units: &Units, language: Language) -> String {
+    let value = units.visibility.convert(vis);
+    let distance = language.decimal(&format_number(value));
+    let text = format!(
+        "{} {}",
+        distance,
+        language.visibility_unit(&units.visibility, value)
+    );
+    match vis.bound {
+        Some(Bound::LessThan) => language.phrase(Term::LessThan, &text),
+        Some(Bound::GreaterThan) => language.phrase(Term::OrMore, &text),
+        None => text,
+    }
+}
 
-        AnnouncementFormat::Detailed => {
-            let mut announcement = format!(
-                "Detailed weather report for {}... ",
-                spell_out_icao(&metar.icao_id)
-            );
+fn spoken_height(feet: f64, units: &Units, phrases: Wording) -> String {
+    format!(
+        "{} {}",
+        phrases.height(units.altitude(feet)),
+        phrases.language.altitude_unit(&units.altitude)
+    )
+}
 
-            if let Some(ref name) = metar.name {
-                announcement.push_str(&format!("{}... ", expand_abbreviations(name)));
-            }
+fn spoken_cloud_layer(layer: &CloudLayer, units: &Units, phrases: Wording) -> String {
+    let language = phrases.language;
+    let mut text = language.cloud_cover(layer.cover);
+    if let Some(base) = layer.base {
+        text.push_str(&format!(
+            " {} {}",
+            language.term(Term::LayerAt),
+            spoken_height(base as f64, units, phrases)
+        ));
+    }
+    if let Some(cloud_type) = layer.cloud_type {
+        text.push_str(&format!(" {}", language.cloud_type(cloud_type)));
+    }
+    text
+}
+
+/// Whole degrees in `units`, e.g. "minus 3"
+fn spoken_degrees(celsius: f64, units: &Units, phrases: Wording) -> String {
+    phrases.number(units.temperature.from_celsius(celsius).round() as i64)
+}
+
+fn spoken_temperature(celsius: f64, units: &Units, phrases: Wording) -> String {
+    let language = phrases.language;
+    format!(
+        "{} {} {}",
+        spoken_degrees(celsius, units, phrases),
+        language.term(Term::Degrees),
+        language.temperature_unit(&units.temperature)
+    )
+}
+
+/// Just the value of an altimeter setting, e.g. "two niner niner two"
+fn spoken_altimeter(hpa: f64, units: &Units, phrases: Wording) -> String {
+    phrases.altimeter(units.pressure.from_hpa(hpa), units.pressure.decimals())
+}
+
+fn spoken_pressure(hpa: f64, units: &Units, phrases: Wording) -> String {
+    format!(
+        "{} {}",
+        spoken_altimeter(hpa, units, phrases),
+        phrases.language.pressure_unit(&units.pressure)
+    )
+}
 
-            announcement.push_str(&format!("Raw METAR... {}... ", metar.raw_ob));
-
-            if let Some(temp_c) = metar.temp {
-                let temp_f = celsius_to_fahrenheit(temp_c);
-                announcement.push_str(&format!(
-                    "Temperature... {} degrees fahrenheit... {} degrees celsius... ",
-                    temp_f.round() as i32,
-                    temp_c.round() as i32
-                ));
-            } else {
-                announcement.push_str("Temperature... not available... ");
+/// The lowest ceiling layer, e.g. "800 feet overcast"
+fn spoken_ceiling(metar: &MetarData, units: &Units, phrases: Wording) -> Option<String> {
+    let ceiling = metar.ceiling()?;
+    let layer = metar
+        .sky()
+        .into_iter()
+        .find(|layer| layer.cover.is_ceiling() && layer.base == Some(ceiling))?;
+    let language = phrases.language;
+    let height = spoken_height(ceiling as f64, units, phrases);
+    Some(match (language, layer.cover) {
+        (_, CloudCover::VerticalVisibility) => language.phrase(Term::IndefiniteCeiling, &height),
+        (Language::En, cover) => format!("{} {}", height, language.cloud_cover(cover)),
+        (_, cover) => format!(
+            "{} {} {}",
+            language.cloud_cover(cover),
+            language.term(Term::LayerAt),
+            height
+        ),
+    })
+}
+
+/// Remarks worth reading out, without the coded groups for automated
+/// station type, sea level pressure, exact temperatures and maintenance
+fn spoken_remarks(remarks: &str) -> Option<String> {
+    let coded = |group: &str| {
+        matches!(group, "AO1" | "AO2" | "$")
+            || (group.starts_with("SLP") && group.len() == 6)
+            || (group.starts_with('T')
+                && group.len() == 9
+                && group[1..].bytes().all(|b| b.is_ascii_digit()))
+    };
+    let groups: Vec<&str> = remarks.split_whitespace().filter(|g| !coded(g)).collect();
+    (!groups.is_empty()).then(|| groups.join(" "))
+}
+
+/// Day and time of the observation, from the raw report or the API's timestamp
+fn observation_time(metar: &MetarData) -> Option<DayTime> {
+    if let Ok(obs) = metar.observation() {
+        return Some(obs.time);
+    }
+    let time = chrono::DateTime::from_timestamp(metar.obs_time?, 0)?;
+    Some(DayTime {
+        day: time.day() as u8,
+        hour: time.hour() as u8,
+        minute: time.minute() as u8,
+    })
+}
+
+/// A cloud layer as a template sees it
+#[derive(Serialize)]
+struct SpokenLayer {
+    #[serde(flatten)]
+    layer: CloudLayer,
+    spoken: String,
+    /// Whether this is the layer that sets the ceiling
+    is_ceiling: bool,
+}
+
+#[derive(Serialize)]
+struct SpokenPhenomenon<'a> {
+    #[serde(flatten)]
+    phenomenon: &'a WeatherPhenomenon,
+    description: String,
+    code: String,
+}
+
+#[derive(Serialize)]
+struct SpokenCategory<'a> {
+    code: &'a str,
+    /// Letter by letter, e.g. "V F R"
+    spelled: String,
+    description: String,
+}
+
+/// What an announcement template is given: the whole decoded report, and
+/// each part of it worded for speech in the chosen units and phraseology.
+/// Anything the report lacks is none.
+#[derive(Serialize)]
+struct AnnouncementContext<'a> {
+    report: &'a DecodedReport<'a>,
+    station: String,
+    name: Option<String>,
+    /// Observation time, e.g. "one seven five six zulu"
+    time: Option<String>,
+    automated: bool,
+    temperature: Option<String>,
+    dewpoint: Option<String>,
+    /// Just the number, as read on the radio
+    temperature_degrees: Option<String>,
+    dewpoint_degrees: Option<String>,
+    wind: Option<String>,
+    visibility: Option<String>,
+    cavok: bool,
+    sky: Vec<SpokenLayer>,
+    ceiling: Option<String>,
+    phenomena: Vec<SpokenPhenomenon<'a>>,
+    altimeter: Option<String>,
+    /// Without the unit, as read on the radio
+    altimeter_value: Option<String>,
+    sea_level_pressure: Option<String>,
+    relative_humidity: Option<i32>,
+    heat_index: Option<String>,
+    wind_chill: Option<String>,
+    /// Only when it is well above the field elevation
+    density_altitude: Option<String>,
+    flight_category: Option<SpokenCategory<'a>>,
+    weather: Option<&'a str>,
+    raw: Option<&'a str>,
+    remarks: Option<String>,
+    /// The ATIS information letter as a word, e.g. "Bravo"
+    information: &'static str,
+}
+
+fn announcement_context<'a>(
+    report: &'a DecodedReport<'a>,
+    units: &Units,
+    phrases: Wording,
+    letters: &InformationLetters,
+) -> AnnouncementContext<'a> {
+    let metar = report.metar;
+    let language = phrases.language;
+    let temperature = |celsius: f64| spoken_temperature(celsius, units, phrases);
+    let degrees = |celsius: f64| spoken_degrees(celsius, units, phrases);
+    let pressure = |hpa: f64| spoken_pressure(hpa, units, phrases);
+    let derived = &report.derived;
+    let letter = letters
+        .letter(metar)
+        .unwrap_or_else(|| derived_letter(metar));
+
+    AnnouncementContext {
+        report,
+        station: phrases.identifier(&metar.icao_id),
+        name: report.station_name.as_deref().map(expand_abbreviations),
+        time: observation_time(metar).map(|time| spoken_zulu(&time, phrases)),
+        automated: report.observation.as_ref().is_some_and(|obs| obs.auto),
+        temperature: metar.temperature().map(temperature),
+        dewpoint: metar.dewpoint().map(temperature),
+        temperature_degrees: metar.temperature().map(degrees),
+        dewpoint_degrees: metar.dewpoint().map(degrees),
+        wind: metar.wind().map(|wind| phrases.wind(&wind, units)),
+        visibility: metar
+            .visibility()
+            .map(|vis| spoken_visibility(&vis, units, language)),
+        cavok: report.observation.as_ref().is_some_and(|obs| obs.cavok),
+        sky: metar
+            .sky()
+            .into_iter()
+            .map(|layer| SpokenLayer {
+                spoken: spoken_cloud_layer(&layer, units, phrases),
+                is_ceiling: layer.cover.is_ceiling() && layer.base == report.ceiling,
+                layer,
+            })
+            .collect(),
+        ceiling: spoken_ceiling(metar, units, phrases),
+        phenomena: report
+            .present_weather
+            .iter()
+            .map(|phenomenon| SpokenPhenomenon {
+                phenomenon,
+                description: language.phenomenon(phenomenon),
+                code: phenomenon.code(),
+            })
+            .collect(),
+        altimeter: metar.altimeter().map(|altimeter| pressure(altimeter.hpa())),
+        altimeter_value: metar
+            .altimeter()
+            .map(|altimeter| spoken_altimeter(altimeter.hpa(), units, phrases)),
+        sea_level_pressure: metar.slp.map(pressure),
+        relative_humidity: derived.relative_humidity.map(|rh| rh.round() as i32),
+        heat_index: derived.heat_index.map(temperature),
+        wind_chill: derived.wind_chill.map(temperature),
+        density_altitude: derived
+            .density_altitude
+            .zip(metar.elev)
+            .filter(|(da, elev)| da - elev * FEET_PER_METER >= HIGH_DENSITY_ALTITUDE_FT)
+            .map(|(da, _)| {
+                let rounded = (da / 100.0).round() * 100.0;
+                format!(
+                    "{} {}",
+                    units.altitude(rounded),
+                    language.altitude_unit(&units.altitude)
+                )
+            }),
+        flight_category: report
+            .flight_category
+            .as_ref()
+            .map(|category| SpokenCategory {
+                code: category.code(),
+                spelled: Wording::default().identifier(category.code()),
+                description: language.flight_category(*category),
+            }),
+        weather: metar.wx_string.as_deref(),
+        raw: Some(metar.raw_ob.as_str()).filter(|raw| !raw.is_empty()),
+        remarks: report
+            .observation
+            .as_ref()
+            .and_then(|obs| obs.remarks.as_deref())
+            .and_then(spoken_remarks),
+        information: phonetic_letter(letter).unwrap_or_default(),
+    }
+}
+
+/// Announce `metar` with the template in `options`, or the format's built-in
+/// one. Fails if the template does not render.
+pub fn generate_weather_announcement(
+    metar: &MetarData,
+    format: &AnnouncementFormat,
+    options: &AnnouncementOptions,
+) -> Result<String> {
+    let report = DecodedReport::new(metar);
+    let context = announcement_context(
+        &report,
+        &options.units,
+        options.wording(format),
+        &options.information_letters,
+    );
+    match &options.template {
+        Some(template) => template.render(&context),
+        None => AnnouncementTemplate::builtin(format, options.language).render(&context),
+    }
+}
+
+/// Announce the newest observation in `history` followed by how conditions
+/// have changed over the period, e.g. "Pressure falling rapidly..."
+pub fn generate_trend_announcement(
+    history: &[MetarData],
+    options: &AnnouncementOptions,
+) -> Result<String> {
+    let Some(latest) = history.iter().max_by_key(|metar| metar.obs_time) else {
+        return Ok(String::new());
+    };
+    let language = options.language;
+    let mut announcement =
+        generate_weather_announcement(latest, &AnnouncementFormat::Trend, options)?;
+    announcement.push(' ');
+
+    let Some(report) = analyze_trends(history) else {
+        announcement.push_str(language.term(Term::NoTrend));
+        return Ok(announcement);
+    };
+
+    let hours = report.span_hours.round().max(1.0) as i64;
+    let period = if hours == 1 {
+        language.term(Term::LastHour).to_string()
+    } else {
+        language.phrase(Term::LastHours, &hours.to_string())
+    };
+
+    let pressure = match report.pressure_tendency() {
+        Some(Tendency::RisingRapidly) => Some(Term::PressureRisingRapidly),
+        Some(Tendency::Rising) => Some(Term::PressureRising),
+        Some(Tendency::Steady) => Some(Term::PressureSteady),
+        Some(Tendency::Falling) => Some(Term::PressureFalling),
+        Some(Tendency::FallingRapidly) => Some(Term::PressureFallingRapidly),
+        None => None,
+    };
+    if let Some(term) = pressure {
+        announcement.push_str(&format!("{} ", language.term(term)));
+    }
+
+    if let (Some(tendency), Some(change)) = (report.temperature_tendency(), report.temperature) {
+        let degrees = options
+            .units
+            .temperature
+            .difference_from_celsius(change.delta())
+            .abs()
+            .round() as i32;
+        let unit = language.term(if degrees == 1 {
+            Term::Degree
+        } else {
+            Term::Degrees
+        });
+        let change = format!("{} {}", degrees, unit);
+        let text = match tendency {
+            Tendency::Steady => language.term(Term::TemperatureSteady).to_string(),
+            Tendency::Rising | Tendency::RisingRapidly => {
+                language.phrase_with(Term::TemperatureUp, &[&change, &period])
+            }
+            Tendency::Falling | Tendency::FallingRapidly => {
+                language.phrase_with(Term::TemperatureDown, &[&change, &period])
             }
+        };
+        announcement.push_str(&format!("{} ", text));
+    }
 
-            if let Some(ref wx) = metar.wx_string {
-                announcement.push_str(&format!("Weather string... {}... ", wx));
-                let codes = parse_wmo_codes(wx);
-                if !codes.is_empty() {
-                    announcement.push_str("Weather codes found... ");
-                    let code_descriptions: Vec<String> = codes
-                        .iter()
-                        .map(|c| format!("{} ({})", c.description(), c.code()))
-                        .collect();
-                    announcement.push_str(&code_descriptions.join("... "));
-                    announcement.push_str("...");
-                } else {
-                    announcement.push_str("No weather codes found...");
+    match report.wind_tendency() {
+        Some(Tendency::Rising | Tendency::RisingRapidly) => {
+            announcement.push_str(&format!("{} ", language.term(Term::WindIncreasing)))
+        }
+        Some(Tendency::Falling | Tendency::FallingRapidly) => {
+            announcement.push_str(&format!("{} ", language.term(Term::WindDecreasing)))
+        }
+        _ => {}
+    }
+
+    match report.ceiling_tendency() {
+        Some(Tendency::Rising | Tendency::RisingRapidly) => {
+            announcement.push_str(&format!("{} ", language.term(Term::CeilingRising)))
+        }
+        Some(Tendency::Falling | Tendency::FallingRapidly) => {
+            announcement.push_str(&format!("{} ", language.term(Term::CeilingLowering)))
+        }
+        _ => {}
+    }
+
+    Ok(announcement.trim_end().to_string())
+}
+
+/// Join per-station announcements into one regional roundup with spoken transitions
+pub fn combine_roundup(announcements: &[String], language: Language) -> String {
+    match announcements {
+        [] => String::new(),
+        [single] => single.clone(),
+        _ => {
+            let mut roundup = format!(
+                "{} ",
+                language.phrase(Term::RoundupStart, &announcements.len().to_string())
+            );
+            for (i, announcement) in announcements.iter().enumerate() {
+                if i > 0 {
+                    roundup.push_str(&format!(" {} ", language.term(Term::RoundupNext)));
                 }
-            } else {
-                announcement
-                    .push_str("Weather... clear or not reported... No weather codes found...");
+                roundup.push_str(announcement.trim_end());
             }
+            roundup.push_str(&format!(" {}", language.term(Term::RoundupEnd)));
+            roundup
+        }
+    }
+}
 
-            announcement
+/// Announce several stations in one roundup, in the order given
+pub fn generate_roundup_announcement(
+    metars: &[MetarData],
+    format: &AnnouncementFormat,
+    options: &AnnouncementOptions,
+) -> Result<String> {
+    let announcements = metars
+        .iter()
+        .map(|metar| generate_weather_announcement(metar, format, options))
+        .collect::<Result<Vec<String>>>()?;
+    Ok(combine_roundup(&announcements, options.language))
+}
+
+fn spoken_zulu(time: &DayTime, phrases: Wording) -> String {
+    let time = format!("{:02}{:02}", time.hour, time.minute);
+    match phrases.phraseology {
+        Phraseology::Plain => format!("{} zulu", time),
+        _ => format!("{} zulu", phrases.digits(&time)),
+    }
+}
+
+fn spoken_forecast_group(group: &ForecastGroup, units: &Units, phrases: Wording) -> String {
+    let language = phrases.language;
+    let from = spoken_zulu(&group.period.from, phrases);
+    let to = spoken_zulu(&group.period.to, phrases);
+    let change = match group.change {
+        ChangeIndicator::Initial => None,
+        ChangeIndicator::From => Some(language.phrase(Term::FromTime, &from)),
+        ChangeIndicator::Becoming => {
+            Some(language.phrase_with(Term::BecomingBetween, &[&from, &to]))
         }
+        ChangeIndicator::Temporary => {
+            Some(language.phrase_with(Term::TemporarilyBetween, &[&from, &to]))
+        }
+        ChangeIndicator::Probability(p) => {
+            Some(language.phrase_with(Term::ChanceBetween, &[&p.to_string(), &from, &to]))
+        }
+        ChangeIndicator::ProbabilityTemporary(p) => Some(language.phrase_with(
+            Term::ChanceOfTemporaryBetween,
+            &[&p.to_string(), &from, &to],
+        )),
+    };
+    let mut text = change.map(|change| change + " ").unwrap_or_default();
 
-        AnnouncementFormat::Aviation => {
-            let mut announcement = format!("{} weather... ", spell_out_icao(&metar.icao_id));
+    if let Some(ref wind) = group.wind {
+        let wind = phrases.wind(wind, units);
+        text.push_str(&format!("{} ", language.phrase(Term::WindIs, &wind)));
+    }
+    if group.cavok {
+        text.push_str(&format!("{} ", language.term(Term::CeilingAndVisibilityOk)));
+    } else if let Some(ref vis) = group.visibility {
+        let vis = spoken_visibility(vis, units, language);
+        text.push_str(&format!("{} ", language.phrase(Term::VisibilityIs, &vis)));
+    }
+    for phenomenon in &group.weather {
+        text.push_str(&format!("{}... ", language.phenomenon(phenomenon)));
+    }
+    if group.no_significant_weather {
+        text.push_str(&format!("{} ", language.term(Term::NoSignificantWeather)));
+    }
+    if !group.sky.is_empty() {
+        let layers: Vec<String> = group
+            .sky
+            .iter()
+            .map(|layer| spoken_cloud_layer(layer, units, phrases))
+            .collect();
+        let sky = layers.join("... ");
+        text.push_str(&format!("{} ", language.phrase(Term::SkyIs, &sky)));
+    }
+    if group.wind_shear.is_some() {
+        text.push_str(&format!("{} ", language.term(Term::LowLevelWindShear)));
+    }
+    text
+}
 
-            if let Some(temp_c) = metar.temp {
-                let temp_f = celsius_to_fahrenheit(temp_c);
-                announcement.push_str(&format!(
-                    "Temperature {} degrees... ",
-                    temp_f.round() as i32
-                ));
-            }
+/// Summarize the forecast for the next `hours` hours
+pub fn generate_taf_announcement(
+    taf_data: &TafData,
+    format: &AnnouncementFormat,
+    hours: u32,
+    options: &AnnouncementOptions,
+) -> String {
+    let phrases = options.wording(format);
+    let language = phrases.language;
+    let station = phrases.identifier(&taf_data.icao_id);
+    let mut announcement = match format {
+        AnnouncementFormat::Aviation => language.phrase(Term::StationForecast, &station),
+        _ => language.phrase(Term::ForecastFor, &station),
+    };
+    announcement.push(' ');
 
-            if let Some(ref wx) = metar.wx_string {
-                let codes = parse_wmo_codes(wx);
-                if !codes.is_empty() {
-                    for code in codes {
-                        announcement.push_str(&format!("{}... ", code.description()));
-                    }
-                } else {
-                    announcement.push_str("Clear... ");
-                }
-            } else {
-                announcement.push_str("Clear... ");
+    if !matches!(format, AnnouncementFormat::Aviation)
+        && let Some(name) = taf_data.station_name()
+    {
+        announcement.push_str(&format!("{}... ", expand_abbreviations(&name)));
+    }
+
+    match taf_data.taf() {
+        Ok(taf) => {
+            let now = chrono::Utc::now();
+            let groups = taf.groups_between(now, now + chrono::Duration::hours(hours as i64));
+            announcement.push_str(&format!(
+                "{} ",
+                language.phrase(Term::NextHours, &hours.to_string())
+            ));
+            if groups.is_empty() {
+                announcement.push_str(&format!("{} ", language.term(Term::NoForecastForPeriod)));
+            }
+            for group in groups {
+                announcement.push_str(&spoken_forecast_group(group, &options.units, phrases));
             }
+        }
+        Err(_) => announcement.push_str(&format!("{} ", language.term(Term::ForecastUnavailable))),
+    }
 
-            announcement.push_str("End weather...");
-            announcement
+    match format {
+        AnnouncementFormat::Detailed => {
+            announcement.push_str(&language.phrase(Term::RawTaf, &taf_data.raw_taf));
         }
+        AnnouncementFormat::Aviation => announcement.push_str(language.term(Term::EndForecast)),
+        _ => {}
+    }
+
+    announcement
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Taf;
+    use crate::trend::tests::report;
+
+    fn options(language: Language) -> AnnouncementOptions {
+        AnnouncementOptions {
+            language,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn announces_trends_in_the_chosen_language() {
+        let history = [
+            report("KSFO 171556Z 29012KT 10SM FEW015 14/11 A3010", 0),
+            report("KSFO 171656Z 29016KT 10SM BKN030 15/11 A3006", 1),
+            report("KSFO 171756Z 29022KT 10SM BKN012 18/11 A3002", 2),
+        ];
+        let english = generate_trend_announcement(&history, &options(Language::En)).unwrap();
+        assert!(english.ends_with(
+            "Pressure falling... Temperature up 7 degrees in the last 2 hours... \
+             Wind increasing... Ceiling lowering..."
+        ));
+        let french = generate_trend_announcement(&history, &options(Language::Fr)).unwrap();
+        assert!(french.starts_with("Météo pour K S F O..."));
+        assert!(french.ends_with(
+            "Pression en baisse... Température en hausse de 7 degrés au cours des 2 dernières heures... \
+             Vent en augmentation... Plafond en baisse..."
+        ));
+    }
+
+    #[test]
+    fn reads_forecast_groups_in_the_chosen_language() {
+        let taf = Taf::parse(
+            "TAF KSFO 171720Z 1718/1824 29012KT P6SM FEW015 \
+             BECMG 1802/1804 VRB05KT 6SM BR BKN008 \
+             PROB30 1810/1814 2SM -RA",
+        )
+        .unwrap();
+        let units = Units::default();
+        let spoken = |group: usize, language: Language| {
+            spoken_forecast_group(
+                &taf.groups[group],
+                &units,
+                Phraseology::Plain.in_language(language),
+            )
+        };
+        assert_eq!(
+            spoken(1, Language::En),
+            "Becoming between 0200 zulu and 0400 zulu... Wind variable at 5 knots... \
+             Visibility 6 miles... mist... Sky broken at 800 feet... "
+        );
+        assert_eq!(
+            spoken(1, Language::Es),
+            "Cambiando entre 0200 zulu y 0400 zulu... Viento variable a 5 nudos... \
+             Visibilidad 6 millas... neblina... Cielo nuboso a 800 pies... "
+        );
+        assert_eq!(
+            spoken(2, Language::De),
+            "Wahrscheinlichkeit 30 Prozent zwischen 1000 zulu und 1400 zulu... \
+             Sicht 2 Meilen... leichter Regen... "
+        );
+    }
+
+    const SHOWERS: &str =
+        "KSFO 171756Z 31015G25KT 280V340 3/4SM -SHRA BR BKN008 OVC015 12/11 A2990";
+
+    fn announce(format: AnnouncementFormat) -> String {
+        generate_weather_announcement(&report(SHOWERS, 0), &format, &options(Language::En)).unwrap()
+    }
+
+    #[test]
+    fn speaks_every_observation_element() {
+        assert_eq!(
+            announce(AnnouncementFormat::Speech),
+            "Weather for K S F O... San Francisco International, California, U S... \
+             Temperature... 54 degrees fahrenheit... Dewpoint... 52 degrees fahrenheit... \
+             Wind... 310 degrees at 15 knots gusting 25, variable between 280 and 340 degrees... \
+             Visibility... 0.75 mile... Sky... broken at 800 feet... overcast at 1500 feet... \
+             Current conditions... light rain showers... mist... \
+             Altimeter... 29.90 inches of mercury..."
+        );
+    }
+
+    #[test]
+    fn briefs_the_ceiling_instead_of_every_layer() {
+        assert_eq!(
+            announce(AnnouncementFormat::Brief),
+            "Weather for K S F O... San Francisco International, California, U S... \
+             Temperature... 54 degrees fahrenheit... \
+             Wind... 310 degrees at 15 knots gusting 25, variable between 280 and 340 degrees... \
+             Visibility... 0.75 mile... Ceiling... 800 feet broken... \
+             Current conditions... light rain showers... mist... \
+             Altimeter... 29.90 inches of mercury..."
+        );
+    }
+
+    #[test]
+    fn details_the_raw_report_and_derived_values() {
+        assert_eq!(
+            announce(AnnouncementFormat::Detailed),
+            "Detailed weather report for K S F O... San Francisco International, California, U S... \
+             Raw METAR... KSFO 171756Z 31015G25KT 280V340 3/4SM -SHRA BR BKN008 OVC015 12/11 A2990... \
+             Temperature... 54 degrees fahrenheit... Dewpoint... 52 degrees fahrenheit... \
+             Wind... 310 degrees at 15 knots gusting 25, variable between 280 and 340 degrees... \
+             Visibility... 0.75 mile... Sky condition... broken at 800 feet... overcast at 1500 feet... \
+             Ceiling... 800 feet broken... Altimeter... 29.90 inches of mercury... \
+             Relative humidity... 94 percent... \
+             Flight category... L I F R... low instrument flight rules... \
+             Weather string... -SHRA BR... \
+             Weather codes found... light rain showers (-SHRA)... mist (BR)..."
+        );
+    }
+
+    #[test]
+    fn reads_aviation_numbers_digit_by_digit() {
+        assert_eq!(
+            announce(AnnouncementFormat::Aviation),
+            "Kilo Sierra Foxtrot Oscar weather... \
+             Wind three one zero at one five gusting two five, \
+             variable between two eight zero and three four zero... \
+             Visibility 0.75 mile... light rain showers... mist... \
+             Ceiling broken at eight hundred feet... overcast at one thousand five hundred feet... \
+             Temperature five four degrees, dewpoint five two... \
+             Altimeter two niner niner zero... L I F R conditions... End weather..."
+        );
     }
 }
@@ -0,0 +1,194 @@
+//! ATIS information letters, which advance through the alphabet each time a
+//! station's observation changes and are kept on disk so that a looping
+//! broadcast keeps its letter between runs.
+
+use crate::{MetarData, Result, WeatherError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Information {
+    letter: char,
+    /// What the letter was issued for: the raw report, or its time if there is none
+    observation: String,
+}
+
+/// The current information letter for each station
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InformationLetters {
+    stations: BTreeMap<String, Information>,
+}
+
+fn observation_key(metar: &MetarData) -> String {
+    if metar.raw_ob.is_empty() {
+        metar.obs_time.unwrap_or_default().to_string()
+    } else {
+        metar.raw_ob.clone()
+    }
+}
+
+/// The letter for an observation with no history: one per hour of the day,
+/// which matches stations that issue a new ATIS with each routine METAR
+pub fn derived_letter(metar: &MetarData) -> char {
+    let hours = metar.obs_time.unwrap_or_default().div_euclid(3600);
+    (b'A' + hours.rem_euclid(26) as u8) as char
+}
+
+fn next_letter(letter: char) -> char {
+    match letter {
+        'A'..='Y' => (letter as u8 + 1) as char,
+        _ => 'A',
+    }
+}
+
+impl InformationLetters {
+    /// Read the letters saved by [`InformationLetters::save`]; a missing file
+    /// means no station has a letter yet
+    pub fn load(path: impl AsRef<Path>) -> Result<InformationLetters> {
+        let path = path.as_ref();
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(InformationLetters::default());
+            }
+            Err(e) => {
+                return Err(WeatherError::io(
+                    format!("Failed to read {}", path.display()),
+                    e,
+                ));
+            }
+        };
+        serde_json::from_str(&text).map_err(|e| WeatherError::invalid_data("ATIS letter", e))
+    }
+
+    /// Write the letters, replacing the file atomically
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| WeatherError::io(format!("Failed to create {}", dir.display()), e))?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| {
+            WeatherError::io("Failed to encode ATIS letters", std::io::Error::other(e))
+        })?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, text)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| WeatherError::io(format!("Failed to write {}", path.display()), e))
+    }
+
+    /// Record the observation being broadcast for its station, advancing the
+    /// letter if it differs from the last one. Returns the letter.
+    pub fn update(&mut self, metar: &MetarData) -> char {
+        let observation = observation_key(metar);
+        let letter = match self.stations.get(&metar.icao_id) {
+            Some(info) if info.observation == observation => return info.letter,
+            Some(info) => next_letter(info.letter),
+            None => derived_letter(metar),
+        };
+        self.stations.insert(
+            metar.icao_id.clone(),
+            Information {
+                letter,
+                observation,
+            },
+        );
+        letter
+    }
+
+    /// The letter for this observation, if it is the one last recorded for its station
+    pub fn letter(&self, metar: &MetarData) -> Option<char> {
+        self.stations
+            .get(&metar.icao_id)
+            .filter(|info| info.observation == observation_key(metar))
+            .map(|info| info.letter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(raw: &str, obs_time: i64) -> MetarData {
+        MetarData {
+            icao_id: "KSFO".to_string(),
+            raw_ob: raw.to_string(),
+            obs_time: Some(obs_time),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn derives_a_letter_from_the_hour() {
+        assert_eq!(derived_letter(&report("", 0)), 'A');
+        assert_eq!(derived_letter(&report("", 3599)), 'A');
+        assert_eq!(derived_letter(&report("", 3600)), 'B');
+        assert_eq!(derived_letter(&report("", 25 * 3600)), 'Z');
+        assert_eq!(derived_letter(&report("", 26 * 3600)), 'A');
+        assert_eq!(derived_letter(&report("", -3600)), 'Z');
+    }
+
+    #[test]
+    fn advances_through_the_alphabet() {
+        assert_eq!(next_letter('A'), 'B');
+        assert_eq!(next_letter('Y'), 'Z');
+        assert_eq!(next_letter('Z'), 'A');
+    }
+
+    #[test]
+    fn advances_only_when_the_observation_changes() {
+        let first = report("KSFO 171656Z 29012KT 10SM FEW015 14/11 A3010", 25 * 3600);
+        let repeated = first.clone();
+        let next = report("KSFO 171756Z 29016KT 10SM BKN030 15/11 A3006", 26 * 3600);
+
+        let mut letters = InformationLetters::default();
+        assert_eq!(letters.letter(&first), None);
+        assert_eq!(letters.update(&first), 'Z');
+        assert_eq!(letters.update(&repeated), 'Z');
+        assert_eq!(letters.letter(&first), Some('Z'));
+        // Wraps from Z back to A
+        assert_eq!(letters.update(&next), 'A');
+        assert_eq!(letters.letter(&first), None);
+        assert_eq!(letters.update(&first), 'B');
+    }
+
+    #[test]
+    fn keys_reports_without_text_by_time() {
+        let mut letters = InformationLetters::default();
+        assert_eq!(letters.update(&report("", 3600)), 'B');
+        assert_eq!(letters.update(&report("", 3600)), 'B');
+        assert_eq!(letters.update(&report("", 7200)), 'C');
+    }
+
+    #[test]
+    fn saves_and_loads_letters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("atis.json");
+        assert_eq!(
+            InformationLetters::load(&path).unwrap(),
+            InformationLetters::default()
+        );
+
+        let mut letters = InformationLetters::default();
+        letters.update(&report("KSFO 171656Z 29012KT 10SM FEW015 14/11 A3010", 0));
+        letters.update(&report(
+            "KSFO 171756Z 29016KT 10SM BKN030 15/11 A3006",
+            3600,
+        ));
+        letters.save(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+        let loaded = InformationLetters::load(&path).unwrap();
+        assert_eq!(loaded, letters);
+        assert_eq!(
+            loaded.letter(&report(
+                "KSFO 171756Z 29016KT 10SM BKN030 15/11 A3006",
+                3600
+            )),
+            Some('B')
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(InformationLetters::load(&path).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::tts::{AudioFormat, TtsBackend, TtsError, Voice};
+use crate::tts::{AudioFormat, Language, TtsBackend, TtsError, Voice};
 
 // eSpeak audio output mode constants
 const AUDIO_OUTPUT_PLAYBACK: u32 = 0;
@@ -50,6 +50,20 @@ impl EspeakVoice {
             gap: 15,
         }
     }
+
+    /// The same voice variant speaking `language`, e.g. "es+f3"
+    pub fn with_language(mut self, language: Language) -> Self {
+        let variant = self.name.find('+').map_or("", |i| &self.name[i..]);
+        let name = match language {
+            Language::En => "en-us",
+            Language::Es => "es",
+            Language::Fr => "fr",
+            Language::De => "de",
+            Language::Pt => "pt-br",
+        };
+        self.name = format!("{}{}", name, variant);
+        self
+    }
 }
 
 impl From<Voice> for EspeakVoice {
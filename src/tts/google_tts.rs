@@ -1,4 +1,4 @@
-use crate::tts::{AudioFormat, TtsBackend, TtsError, Voice};
+use crate::tts::{AudioFormat, Language, TtsBackend, TtsError, Voice};
 use base64::Engine;
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +14,22 @@ pub enum GoogleVoice {
     UkFemale,
     /// UK English male neural voice
     UkMale,
+    /// Spanish (Spain) female neural voice
+    EsFemale,
+    /// Spanish (Spain) male neural voice
+    EsMale,
+    /// French female neural voice
+    FrFemale,
+    /// French male neural voice
+    FrMale,
+    /// German female neural voice
+    DeFemale,
+    /// German male neural voice
+    DeMale,
+    /// Brazilian Portuguese female neural voice
+    PtFemale,
+    /// Brazilian Portuguese male neural voice
+    PtMale,
 }
 
 impl GoogleVoice {
@@ -23,6 +39,14 @@ impl GoogleVoice {
             GoogleVoice::UsMale => "en-US-Neural2-D",
             GoogleVoice::UkFemale => "en-GB-Neural2-A",
             GoogleVoice::UkMale => "en-GB-Neural2-B",
+            GoogleVoice::EsFemale => "es-ES-Neural2-A",
+            GoogleVoice::EsMale => "es-ES-Neural2-B",
+            GoogleVoice::FrFemale => "fr-FR-Neural2-A",
+            GoogleVoice::FrMale => "fr-FR-Neural2-B",
+            GoogleVoice::DeFemale => "de-DE-Neural2-A",
+            GoogleVoice::DeMale => "de-DE-Neural2-B",
+            GoogleVoice::PtFemale => "pt-BR-Neural2-A",
+            GoogleVoice::PtMale => "pt-BR-Neural2-B",
         }
     }
 
@@ -30,6 +54,26 @@ impl GoogleVoice {
         match self {
             GoogleVoice::Default | GoogleVoice::UsFemale | GoogleVoice::UsMale => "en-US",
             GoogleVoice::UkFemale | GoogleVoice::UkMale => "en-GB",
+            GoogleVoice::EsFemale | GoogleVoice::EsMale => "es-ES",
+            GoogleVoice::FrFemale | GoogleVoice::FrMale => "fr-FR",
+            GoogleVoice::DeFemale | GoogleVoice::DeMale => "de-DE",
+            GoogleVoice::PtFemale | GoogleVoice::PtMale => "pt-BR",
+        }
+    }
+
+    /// `voice` speaking `language`; the default voice is female
+    pub fn for_language(voice: Voice, language: Language) -> Self {
+        let male = matches!(voice, Voice::UsMale);
+        match (language, male) {
+            (Language::En, _) => voice.into(),
+            (Language::Es, false) => GoogleVoice::EsFemale,
+            (Language::Es, true) => GoogleVoice::EsMale,
+            (Language::Fr, false) => GoogleVoice::FrFemale,
+            (Language::Fr, true) => GoogleVoice::FrMale,
+            (Language::De, false) => GoogleVoice::DeFemale,
+            (Language::De, true) => GoogleVoice::DeMale,
+            (Language::Pt, false) => GoogleVoice::PtFemale,
+            (Language::Pt, true) => GoogleVoice::PtMale,
         }
     }
 }
@@ -47,11 +91,19 @@ impl From<Voice> for GoogleVoice {
 pub struct GoogleTts {
     api_key: String,
     voice: GoogleVoice,
+    /// Shared by every async request, so parallel synthesis reuses connections
+    #[cfg(feature = "async")]
+    client: reqwest::Client,
 }
 
 impl GoogleTts {
     pub fn new(api_key: String, voice: GoogleVoice) -> Self {
-        Self { api_key, voice }
+        Self {
+            api_key,
+            voice,
+            #[cfg(feature = "async")]
+            client: reqwest::Client::new(),
+        }
     }
 
     fn audio_format_to_google_encoding(&self, format: &AudioFormat) -> Result<&str, TtsError> {
@@ -103,14 +155,19 @@ struct TtsResponse {
     audio_content: String,
 }
 
-impl TtsBackend for GoogleTts {
-    fn synthesize(&self, text: &str, format: &AudioFormat) -> Result<Vec<u8>, TtsError> {
-        // For telephony formats, always use WAV and convert to raw
-        let (google_format, needs_conversion) = if format.is_telephony_format() {
-            (&AudioFormat::Wav, true)
+impl GoogleTts {
+    /// The format to request from Google; telephony formats are requested
+    /// as WAV and converted afterwards
+    fn google_format(format: &AudioFormat) -> &AudioFormat {
+        if format.is_telephony_format() {
+            &AudioFormat::Wav
         } else {
-            (format, false)
-        };
+            format
+        }
+    }
+
+    fn request(&self, text: &str, format: &AudioFormat) -> Result<TtsRequest, TtsError> {
+        let google_format = Self::google_format(format);
 
         let encoding = self.audio_format_to_google_encoding(google_format)?;
 
@@ -130,15 +187,42 @@ impl TtsBackend for GoogleTts {
                 sample_rate_hertz: sample_rate,
             },
         };
+        Ok(request)
+    }
 
-        let client = reqwest::blocking::Client::new();
-        let url = format!(
+    fn url(&self) -> String {
+        format!(
             "https://texttospeech.googleapis.com/v1/text:synthesize?key={}",
             self.api_key
-        );
+        )
+    }
 
+    /// Decode the returned audio and convert it to the requested format
+    fn decode(response: TtsResponse, format: &AudioFormat) -> Result<Vec<u8>, TtsError> {
+        let audio_data = base64::engine::general_purpose::STANDARD
+            .decode(&response.audio_content)
+            .map_err(|e| TtsError::SynthesisError(format!("Failed to decode audio: {}", e)))?;
+
+        // Convert format if needed using centralized conversion
+        if format.is_telephony_format() {
+            crate::tts::TtsPlayer::convert_audio_format(
+                &audio_data,
+                Self::google_format(format),
+                format,
+            )
+        } else {
+            Ok(audio_data)
+        }
+    }
+}
+
+impl TtsBackend for GoogleTts {
+    fn synthesize(&self, text: &str, format: &AudioFormat) -> Result<Vec<u8>, TtsError> {
+        let request = self.request(text, format)?;
+
+        let client = reqwest::blocking::Client::new();
         let response = client
-            .post(&url)
+            .post(self.url())
             .json(&request)
             .send()
             .map_err(|e| TtsError::SynthesisError(format!("HTTP request failed: {}", e)))?;
@@ -157,16 +241,7 @@ impl TtsBackend for GoogleTts {
             .json()
             .map_err(|e| TtsError::SynthesisError(format!("Failed to parse response: {}", e)))?;
 
-        let audio_data = base64::engine::general_purpose::STANDARD
-            .decode(&tts_response.audio_content)
-            .map_err(|e| TtsError::SynthesisError(format!("Failed to decode audio: {}", e)))?;
-
-        // Convert format if needed using centralized conversion
-        if needs_conversion {
-            crate::tts::TtsPlayer::convert_audio_format(&audio_data, google_format, format)
-        } else {
-            Ok(audio_data)
-        }
+        Self::decode(tts_response, format)
     }
 
     fn speak(&self, text: &str) -> Result<(), TtsError> {
@@ -179,3 +254,40 @@ impl TtsBackend for GoogleTts {
         "Google Cloud TTS"
     }
 }
+
+#[cfg(feature = "async")]
+impl crate::tts::AsyncTtsBackend for GoogleTts {
+    async fn synthesize_async(
+        &self,
+        text: &str,
+        format: &AudioFormat,
+    ) -> Result<Vec<u8>, TtsError> {
+        let request = self.request(text, format)?;
+
+        let response = self
+            .client
+            .post(self.url())
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| TtsError::SynthesisError(format!("HTTP request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(TtsError::SynthesisError(format!(
+                "Google TTS API error: {}",
+                error_text
+            )));
+        }
+
+        let tts_response: TtsResponse = response
+            .json()
+            .await
+            .map_err(|e| TtsError::SynthesisError(format!("Failed to parse response: {}", e)))?;
+
+        Self::decode(tts_response, format)
+    }
+}
@@ -0,0 +1,1086 @@
+//! Words for announcements in languages other than English: numbers, units,
+//! clouds and present weather. English uses the descriptions found throughout
+//! the crate, so its announcements read as they always have.
+
+use crate::metar::{CloudCover, CloudType};
+use crate::present_weather::{Descriptor, Intensity};
+use crate::units::{AltitudeUnit, PressureUnit, TemperatureUnit, VisibilityUnit, WindSpeedUnit};
+use crate::{FlightCategory, WeatherPhenomenon, WmoCode};
+
+/// A language announcements can be spoken in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Language {
+    /// English
+    #[default]
+    En,
+    /// Spanish
+    Es,
+    /// French
+    Fr,
+    /// German
+    De,
+    /// Portuguese (Brazil)
+    Pt,
+}
+
+/// Grammatical gender and number, which adjectives agree with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Gender {
+    Masculine,
+    Feminine,
+    Neuter,
+    MasculinePlural,
+    FemininePlural,
+}
+
+/// An adjective's forms in the order of [`Gender`]
+type Adjective = [&'static str; 5];
+
+fn agree(adjective: &Adjective, gender: Gender) -> &'static str {
+    adjective[gender as usize]
+}
+
+/// How a descriptor such as FZ or SH changes the phenomenon it qualifies
+enum Modifier {
+    Adjective(Adjective),
+    /// The phenomenon goes in place of `{}`, making a phrase of this gender
+    Phrase(&'static str, Gender),
+}
+
+/// Words and phrases used in announcements; phrases have `{}` where the rest goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Term {
+    Minus,
+    And,
+    Calm,
+    Degrees,
+    /// Joins a wind direction to its speed
+    WindAt,
+    Variable,
+    Gusting,
+    VariableBetween,
+    /// Joins a cloud cover to the height of its base
+    LayerAt,
+    LessThan,
+    OrMore,
+    Thousand,
+    IndefiniteCeiling,
+    Recent,
+    InTheVicinity,
+    ThunderstormWith,
+    RoundupStart,
+    RoundupNext,
+    RoundupEnd,
+    Degree,
+    NoTrend,
+    LastHour,
+    LastHours,
+    PressureRisingRapidly,
+    PressureRising,
+    PressureSteady,
+    PressureFalling,
+    PressureFallingRapidly,
+    TemperatureSteady,
+    /// The change in degrees, then the period
+    TemperatureUp,
+    TemperatureDown,
+    WindIncreasing,
+    WindDecreasing,
+    CeilingRising,
+    CeilingLowering,
+    /// The station as read on the radio
+    StationForecast,
+    ForecastFor,
+    NextHours,
+    NoForecastForPeriod,
+    ForecastUnavailable,
+    RawTaf,
+    EndForecast,
+    /// Forecast change groups take the times they start and end at
+    FromTime,
+    BecomingBetween,
+    TemporarilyBetween,
+    /// The probability in percent, then the times
+    ChanceBetween,
+    ChanceOfTemporaryBetween,
+    WindIs,
+    CeilingAndVisibilityOk,
+    VisibilityIs,
+    NoSignificantWeather,
+    SkyIs,
+    LowLevelWindShear,
+}
+
+impl Language {
+    /// The ISO 639-1 code, e.g. "es"
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Es => "es",
+            Language::Fr => "fr",
+            Language::De => "de",
+            Language::Pt => "pt",
+        }
+    }
+
+    pub(crate) fn term(&self, term: Term) -> &'static str {
+        use Language::*;
+        match (self, term) {
+            (En, Term::Minus) => "minus",
+            (Es | Pt, Term::Minus) => "menos",
+            (Fr, Term::Minus) => "moins",
+            (De, Term::Minus) => "minus",
+            (En, Term::And) => "and",
+            (Es, Term::And) => "y",
+            (Fr, Term::And) => "et",
+            (De, Term::And) => "und",
+            (Pt, Term::And) => "e",
+            (En, Term::Calm) => "calm",
+            (Es, Term::Calm) => "calma",
+            (Fr, Term::Calm) => "calme",
+            (De, Term::Calm) => "still",
+            (Pt, Term::Calm) => "calmo",
+            (En, Term::Degrees) => "degrees",
+            (Es, Term::Degrees) => "grados",
+            (Fr, Term::Degrees) => "degrés",
+            (De, Term::Degrees) => "Grad",
+            (Pt, Term::Degrees) => "graus",
+            (En, Term::WindAt) => "at",
+            (Es | Pt, Term::WindAt) => "a",
+            (Fr, Term::WindAt) => "à",
+            (De, Term::WindAt) => "mit",
+            (En, Term::Variable) => "variable",
+            (Es | Fr, Term::Variable) => "variable",
+            (De, Term::Variable) => "umlaufend",
+            (Pt, Term::Variable) => "variável",
+            (En, Term::Gusting) => "gusting",
+            (Es, Term::Gusting) => "con rachas de",
+            (Fr, Term::Gusting) => "avec rafales à",
+            (De, Term::Gusting) => "in Böen",
+            (Pt, Term::Gusting) => "com rajadas de",
+            (En, Term::VariableBetween) => "variable between",
+            (Es | Fr, Term::VariableBetween) => "variable entre",
+            (De, Term::VariableBetween) => "wechselnd zwischen",
+            (Pt, Term::VariableBetween) => "variável entre",
+            (En, Term::LayerAt) => "at",
+            (Es | Pt, Term::LayerAt) => "a",
+            (Fr, Term::LayerAt) => "à",
+            (De, Term::LayerAt) => "in",
+            (En, Term::LessThan) => "less than {}",
+            (Es | Pt, Term::LessThan) => "menos de {}",
+            (Fr, Term::LessThan) => "moins de {}",
+            (De, Term::LessThan) => "weniger als {}",
+            (En, Term::OrMore) => "{} or more",
+            (Es, Term::OrMore) => "{} o más",
+            (Fr, Term::OrMore) => "{} ou plus",
+            (De, Term::OrMore) => "{} oder mehr",
+            (Pt, Term::OrMore) => "{} ou mais",
+            (En, Term::Thousand) => "thousand",
+            (Es | Pt, Term::Thousand) => "mil",
+            (Fr, Term::Thousand) => "mille",
+            (De, Term::Thousand) => "tausend",
+            (En, Term::IndefiniteCeiling) => "indefinite, vertical visibility {}",
+            (Es, Term::IndefiniteCeiling) => "indefinido, visibilidad vertical {}",
+            (Fr, Term::IndefiniteCeiling) => "indéfini, visibilité verticale {}",
+            (De, Term::IndefiniteCeiling) => "unbestimmt, Vertikalsicht {}",
+            (Pt, Term::IndefiniteCeiling) => "indefinido, visibilidade vertical {}",
+            (En, Term::Recent) => "recent {}",
+            (Es, Term::Recent) => "{} reciente",
+            (Fr, Term::Recent) => "{}, récemment",
+            (De, Term::Recent) => "{}, kürzlich",
+            (Pt, Term::Recent) => "{} recente",
+            (En, Term::InTheVicinity) => "{} in the vicinity",
+            (Es, Term::InTheVicinity) => "{} en las proximidades",
+            (Fr, Term::InTheVicinity) => "{} au voisinage",
+            (De, Term::InTheVicinity) => "{} in der Umgebung",
+            (Pt, Term::InTheVicinity) => "{} nas proximidades",
+            (En, Term::ThunderstormWith) => "thunderstorm with {}",
+            (Es, Term::ThunderstormWith) => "tormenta con {}",
+            (Fr, Term::ThunderstormWith) => "orage avec {}",
+            (De, Term::ThunderstormWith) => "Gewitter und {}",
+            (Pt, Term::ThunderstormWith) => "trovoada com {}",
+            (En, Term::RoundupStart) => "Regional weather roundup for {} stations...",
+            (Es, Term::RoundupStart) => "Resumen meteorológico regional de {} estaciones...",
+            (Fr, Term::RoundupStart) => "Bulletin météo régional pour {} stations...",
+            (De, Term::RoundupStart) => "Regionaler Wetterüberblick für {} Stationen...",
+            (Pt, Term::RoundupStart) => "Resumo meteorológico regional de {} estações...",
+            (En, Term::RoundupNext) => "Next...",
+            (Es, Term::RoundupNext) => "Siguiente...",
+            (Fr, Term::RoundupNext) => "Station suivante...",
+            (De, Term::RoundupNext) => "Weiter...",
+            (Pt, Term::RoundupNext) => "Próxima...",
+            (En, Term::RoundupEnd) => "That concludes the regional roundup...",
+            (Es, Term::RoundupEnd) => "Con esto concluye el resumen regional...",
+            (Fr, Term::RoundupEnd) => "Ceci conclut le bulletin régional...",
+            (De, Term::RoundupEnd) => "Damit endet der regionale Wetterüberblick...",
+            (Pt, Term::RoundupEnd) => "Assim termina o resumo regional...",
+            (En, Term::Degree) => "degree",
+            (Es, Term::Degree) => "grado",
+            (Fr, Term::Degree) => "degré",
+            (De, Term::Degree) => "Grad",
+            (Pt, Term::Degree) => "grau",
+            (En, Term::NoTrend) => "No trend information available...",
+            (Es, Term::NoTrend) => "No hay información de tendencia disponible...",
+            (Fr, Term::NoTrend) => "Aucune information de tendance disponible...",
+            (De, Term::NoTrend) => "Keine Trendinformation verfügbar...",
+            (Pt, Term::NoTrend) => "Nenhuma informação de tendência disponível...",
+            (En, Term::LastHour) => "in the last hour",
+            (Es, Term::LastHour) => "en la última hora",
+            (Fr, Term::LastHour) => "au cours de la dernière heure",
+            (De, Term::LastHour) => "in der letzten Stunde",
+            (Pt, Term::LastHour) => "na última hora",
+            (En, Term::LastHours) => "in the last {} hours",
+            (Es, Term::LastHours) => "en las últimas {} horas",
+            (Fr, Term::LastHours) => "au cours des {} dernières heures",
+            (De, Term::LastHours) => "in den letzten {} Stunden",
+            (Pt, Term::LastHours) => "nas últimas {} horas",
+            (En, Term::PressureRisingRapidly) => "Pressure rising rapidly...",
+            (Es, Term::PressureRisingRapidly) => "Presión en rápido ascenso...",
+            (Fr, Term::PressureRisingRapidly) => "Pression en hausse rapide...",
+            (De, Term::PressureRisingRapidly) => "Luftdruck stark steigend...",
+            (Pt, Term::PressureRisingRapidly) => "Pressão subindo rapidamente...",
+            (En, Term::PressureRising) => "Pressure rising...",
+            (Es, Term::PressureRising) => "Presión en ascenso...",
+            (Fr, Term::PressureRising) => "Pression en hausse...",
+            (De, Term::PressureRising) => "Luftdruck steigend...",
+            (Pt, Term::PressureRising) => "Pressão subindo...",
+            (En, Term::PressureSteady) => "Pressure steady...",
+            (Es, Term::PressureSteady) => "Presión estable...",
+            (Fr, Term::PressureSteady) => "Pression stable...",
+            (De, Term::PressureSteady) => "Luftdruck gleichbleibend...",
+            (Pt, Term::PressureSteady) => "Pressão estável...",
+            (En, Term::PressureFalling) => "Pressure falling...",
+            (Es, Term::PressureFalling) => "Presión en descenso...",
+            (Fr, Term::PressureFalling) => "Pression en baisse...",
+            (De, Term::PressureFalling) => "Luftdruck fallend...",
+            (Pt, Term::PressureFalling) => "Pressão caindo...",
+            (En, Term::PressureFallingRapidly) => "Pressure falling rapidly...",
+            (Es, Term::PressureFallingRapidly) => "Presión en rápido descenso...",
+            (Fr, Term::PressureFallingRapidly) => "Pression en baisse rapide...",
+            (De, Term::PressureFallingRapidly) => "Luftdruck stark fallend...",
+            (Pt, Term::PressureFallingRapidly) => "Pressão caindo rapidamente...",
+            (En, Term::TemperatureSteady) => "Temperature steady...",
+            (Es, Term::TemperatureSteady) => "Temperatura estable...",
+            (Fr, Term::TemperatureSteady) => "Température stable...",
+            (De, Term::TemperatureSteady) => "Temperatur gleichbleibend...",
+            (Pt, Term::TemperatureSteady) => "Temperatura estável...",
+            (En, Term::TemperatureUp) => "Temperature up {} {}...",
+            (Es, Term::TemperatureUp) => "Temperatura subió {} {}...",
+            (Fr, Term::TemperatureUp) => "Température en hausse de {} {}...",
+            (De, Term::TemperatureUp) => "Temperatur plus {} {}...",
+            (Pt, Term::TemperatureUp) => "Temperatura subiu {} {}...",
+            (En, Term::TemperatureDown) => "Temperature down {} {}...",
+            (Es, Term::TemperatureDown) => "Temperatura bajó {} {}...",
+            (Fr, Term::TemperatureDown) => "Température en baisse de {} {}...",
+            (De, Term::TemperatureDown) => "Temperatur minus {} {}...",
+            (Pt, Term::TemperatureDown) => "Temperatura caiu {} {}...",
+            (En, Term::WindIncreasing) => "Wind increasing...",
+            (Es, Term::WindIncreasing) => "Viento en aumento...",
+            (Fr, Term::WindIncreasing) => "Vent en augmentation...",
+            (De, Term::WindIncreasing) => "Wind zunehmend...",
+            (Pt, Term::WindIncreasing) => "Vento aumentando...",
+            (En, Term::WindDecreasing) => "Wind decreasing...",
+            (Es, Term::WindDecreasing) => "Viento en disminución...",
+            (Fr, Term::WindDecreasing) => "Vent en diminution...",
+            (De, Term::WindDecreasing) => "Wind abnehmend...",
+            (Pt, Term::WindDecreasing) => "Vento diminuindo...",
+            (En, Term::CeilingRising) => "Ceiling rising...",
+            (Es, Term::CeilingRising) => "Techo en ascenso...",
+            (Fr, Term::CeilingRising) => "Plafond en hausse...",
+            (De, Term::CeilingRising) => "Wolkenuntergrenze steigend...",
+            (Pt, Term::CeilingRising) => "Teto subindo...",
+            (En, Term::CeilingLowering) => "Ceiling lowering...",
+            (Es, Term::CeilingLowering) => "Techo en descenso...",
+            (Fr, Term::CeilingLowering) => "Plafond en baisse...",
+            (De, Term::CeilingLowering) => "Wolkenuntergrenze sinkend...",
+            (Pt, Term::CeilingLowering) => "Teto baixando...",
+            (En, Term::StationForecast) => "{} forecast...",
+            (Es, Term::StationForecast) => "Pronóstico {}...",
+            (Fr, Term::StationForecast) => "Prévision {}...",
+            (De, Term::StationForecast) => "{} Vorhersage...",
+            (Pt, Term::StationForecast) => "Previsão {}...",
+            (En, Term::ForecastFor) => "Forecast for {}...",
+            (Es, Term::ForecastFor) => "Pronóstico para {}...",
+            (Fr, Term::ForecastFor) => "Prévision pour {}...",
+            (De, Term::ForecastFor) => "Vorhersage für {}...",
+            (Pt, Term::ForecastFor) => "Previsão para {}...",
+            (En, Term::NextHours) => "For the next {} hours...",
+            (Es, Term::NextHours) => "Para las próximas {} horas...",
+            (Fr, Term::NextHours) => "Pour les {} prochaines heures...",
+            (De, Term::NextHours) => "Für die nächsten {} Stunden...",
+            (Pt, Term::NextHours) => "Para as próximas {} horas...",
+            (En, Term::NoForecastForPeriod) => "No forecast available for this period...",
+            (Es, Term::NoForecastForPeriod) => "No hay pronóstico disponible para este período...",
+            (Fr, Term::NoForecastForPeriod) => "Aucune prévision disponible pour cette période...",
+            (De, Term::NoForecastForPeriod) => "Keine Vorhersage für diesen Zeitraum verfügbar...",
+            (Pt, Term::NoForecastForPeriod) => "Nenhuma previsão disponível para este período...",
+            (En, Term::ForecastUnavailable) => "Forecast not available...",
+            (Es, Term::ForecastUnavailable) => "Pronóstico no disponible...",
+            (Fr, Term::ForecastUnavailable) => "Prévision non disponible...",
+            (De, Term::ForecastUnavailable) => "Vorhersage nicht verfügbar...",
+            (Pt, Term::ForecastUnavailable) => "Previsão não disponível...",
+            (En, Term::RawTaf) => "Raw TAF... {}...",
+            (Es, Term::RawTaf) => "TAF original... {}...",
+            (Fr, Term::RawTaf) => "TAF brut... {}...",
+            (De, Term::RawTaf) => "TAF im Klartext... {}...",
+            (Pt, Term::RawTaf) => "TAF original... {}...",
+            (En, Term::EndForecast) => "End forecast...",
+            (Es, Term::EndForecast) => "Fin del pronóstico...",
+            (Fr, Term::EndForecast) => "Fin de la prévision...",
+            (De, Term::EndForecast) => "Ende der Vorhersage...",
+            (Pt, Term::EndForecast) => "Fim da previsão...",
+            (En, Term::FromTime) => "From {}...",
+            (Es, Term::FromTime) => "A partir de las {}...",
+            (Fr, Term::FromTime) => "À partir de {}...",
+            (De, Term::FromTime) => "Ab {}...",
+            (Pt, Term::FromTime) => "A partir das {}...",
+            (En, Term::BecomingBetween) => "Becoming between {} and {}...",
+            (Es, Term::BecomingBetween) => "Cambiando entre {} y {}...",
+            (Fr, Term::BecomingBetween) => "Devenant entre {} et {}...",
+            (De, Term::BecomingBetween) => "Übergehend zwischen {} und {}...",
+            (Pt, Term::BecomingBetween) => "Tornando-se entre {} e {}...",
+            (En, Term::TemporarilyBetween) => "Temporarily between {} and {}...",
+            (Es, Term::TemporarilyBetween) => "Temporalmente entre {} y {}...",
+            (Fr, Term::TemporarilyBetween) => "Temporairement entre {} et {}...",
+            (De, Term::TemporarilyBetween) => "Zeitweise zwischen {} und {}...",
+            (Pt, Term::TemporarilyBetween) => "Temporariamente entre {} e {}...",
+            (En, Term::ChanceBetween) => "{} percent chance between {} and {}...",
+            (Es, Term::ChanceBetween) => "Probabilidad del {} por ciento entre {} y {}...",
+            (Fr, Term::ChanceBetween) => "Probabilité de {} pour cent entre {} et {}...",
+            (De, Term::ChanceBetween) => "Wahrscheinlichkeit {} Prozent zwischen {} und {}...",
+            (Pt, Term::ChanceBetween) => "Probabilidade de {} por cento entre {} e {}...",
+            (En, Term::ChanceOfTemporaryBetween) => {
+                "{} percent chance of temporary conditions between {} and {}..."
+            }
+            (Es, Term::ChanceOfTemporaryBetween) => {
+                "Probabilidad del {} por ciento de condiciones temporales entre {} y {}..."
+            }
+            (Fr, Term::ChanceOfTemporaryBetween) => {
+                "Probabilité de {} pour cent de conditions temporaires entre {} et {}..."
+            }
+            (De, Term::ChanceOfTemporaryBetween) => {
+                "Wahrscheinlichkeit {} Prozent für zeitweise Bedingungen zwischen {} und {}..."
+            }
+            (Pt, Term::ChanceOfTemporaryBetween) => {
+                "Probabilidade de {} por cento de condições temporárias entre {} e {}..."
+            }
+            (En, Term::WindIs) => "Wind {}...",
+            (Es, Term::WindIs) => "Viento {}...",
+            (Fr, Term::WindIs) => "Vent {}...",
+            (De, Term::WindIs) => "Wind {}...",
+            (Pt, Term::WindIs) => "Vento {}...",
+            (En, Term::CeilingAndVisibilityOk) => "Ceiling and visibility OK...",
+            (Es, Term::CeilingAndVisibilityOk) => "Techo y visibilidad OK...",
+            (Fr, Term::CeilingAndVisibilityOk) => "Plafond et visibilité OK...",
+            (De, Term::CeilingAndVisibilityOk) => "Wolken und Sicht OK...",
+            (Pt, Term::CeilingAndVisibilityOk) => "Teto e visibilidade OK...",
+            (En, Term::VisibilityIs) => "Visibility {}...",
+            (Es, Term::VisibilityIs) => "Visibilidad {}...",
+            (Fr, Term::VisibilityIs) => "Visibilité {}...",
+            (De, Term::VisibilityIs) => "Sicht {}...",
+            (Pt, Term::VisibilityIs) => "Visibilidade {}...",
+            (En, Term::NoSignificantWeather) => "No significant weather...",
+            (Es, Term::NoSignificantWeather) => "Sin tiempo significativo...",
+            (Fr, Term::NoSignificantWeather) => "Pas de temps significatif...",
+            (De, Term::NoSignificantWeather) => "Keine signifikanten Wettererscheinungen...",
+            (Pt, Term::NoSignificantWeather) => "Sem tempo significativo...",
+            (En, Term::SkyIs) => "Sky {}...",
+            (Es, Term::SkyIs) => "Cielo {}...",
+            (Fr, Term::SkyIs) => "Ciel {}...",
+            (De, Term::SkyIs) => "Bewölkung {}...",
+            (Pt, Term::SkyIs) => "Céu {}...",
+            (En, Term::LowLevelWindShear) => "Low level wind shear...",
+            (Es, Term::LowLevelWindShear) => "Cizalladura del viento a baja altura...",
+            (Fr, Term::LowLevelWindShear) => "Cisaillement du vent à basse altitude...",
+            (De, Term::LowLevelWindShear) => "Windscherung in geringer Höhe...",
+            (Pt, Term::LowLevelWindShear) => "Cortante de vento em baixa altitude...",
+        }
+    }
+
+    /// A phrase from [`Language::term`] with `text` in place of its `{}`
+    pub(crate) fn phrase(&self, term: Term, text: &str) -> String {
+        self.term(term).replacen("{}", text, 1)
+    }
+
+    /// A phrase from [`Language::term`] with `parts` in place of its `{}`s, in order
+    pub(crate) fn phrase_with(&self, term: Term, parts: &[&str]) -> String {
+        parts
+            .iter()
+            .fold(self.term(term).to_string(), |text, part| {
+                text.replacen("{}", part, 1)
+            })
+    }
+
+    /// A single digit as a word; English radio digits such as "niner" are
+    /// left to [`crate::tts::Wording`]
+    pub fn digit(&self, d: u32) -> &'static str {
+        let words = match self {
+            Language::En => [
+                "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+            ],
+            Language::Es => [
+                "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+            ],
+            Language::Fr => [
+                "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+            ],
+            // "zwo" rather than "zwei", as on the radio
+            Language::De => [
+                "null", "eins", "zwo", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+            ],
+            Language::Pt => [
+                "zero", "um", "dois", "três", "quatro", "cinco", "seis", "sete", "oito", "nove",
+            ],
+        };
+        words.get(d as usize).copied().unwrap_or_default()
+    }
+
+    /// Hundreds from one to nine as a word, e.g. "quinientos" for 5
+    pub fn hundreds(&self, h: u32) -> String {
+        let words = match self {
+            Language::En => return format!("{} hundred", self.digit(h)),
+            Language::Es => [
+                "cien",
+                "doscientos",
+                "trescientos",
+                "cuatrocientos",
+                "quinientos",
+                "seiscientos",
+                "setecientos",
+                "ochocientos",
+                "novecientos",
+            ],
+            Language::Fr => [
+                "cent",
+                "deux cents",
+                "trois cents",
+                "quatre cents",
+                "cinq cents",
+                "six cents",
+                "sept cents",
+                "huit cents",
+                "neuf cents",
+            ],
+            Language::De => [
+                "einhundert",
+                "zweihundert",
+                "dreihundert",
+                "vierhundert",
+                "fünfhundert",
+                "sechshundert",
+                "siebenhundert",
+                "achthundert",
+                "neunhundert",
+            ],
+            Language::Pt => [
+                "cem",
+                "duzentos",
+                "trezentos",
+                "quatrocentos",
+                "quinhentos",
+                "seiscentos",
+                "setecentos",
+                "oitocentos",
+                "novecentos",
+            ],
+        };
+        (h as usize)
+            .checked_sub(1)
+            .and_then(|i| words.get(i))
+            .map(|word| word.to_string())
+            .unwrap_or_default()
+    }
+
+    /// A number formatted with a decimal point, written the way this
+    /// language does, e.g. "16,1" in Spanish
+    pub fn decimal(&self, number: &str) -> String {
+        match self {
+            Language::En => number.to_string(),
+            _ => number.replace('.', ","),
+        }
+    }
+
+    pub fn temperature_unit<'a>(&self, unit: &'a TemperatureUnit) -> &'a str {
+        match (self, unit) {
+            (Language::En, unit) => unit.name(),
+            (Language::Es | Language::Pt, TemperatureUnit::Celsius) => "celsius",
+            (Language::Fr | Language::De, TemperatureUnit::Celsius) => "Celsius",
+            (Language::Es | Language::Pt, TemperatureUnit::Fahrenheit) => "fahrenheit",
+            (Language::Fr | Language::De, TemperatureUnit::Fahrenheit) => "Fahrenheit",
+        }
+    }
+
+    pub fn wind_speed_unit<'a>(&self, unit: &'a WindSpeedUnit) -> &'a str {
+        use WindSpeedUnit::*;
+        match (self, unit) {
+            (Language::En, unit) => unit.name(),
+            (Language::Es, Knots) => "nudos",
+            (Language::Es, MilesPerHour) => "millas por hora",
+            (Language::Es, KilometersPerHour) => "kilómetros por hora",
+            (Language::Es, MetersPerSecond) => "metros por segundo",
+            (Language::Fr, Knots) => "nœuds",
+            (Language::Fr, MilesPerHour) => "miles par heure",
+            (Language::Fr, KilometersPerHour) => "kilomètres par heure",
+            (Language::Fr, MetersPerSecond) => "mètres par seconde",
+            (Language::De, Knots) => "Knoten",
+            (Language::De, MilesPerHour) => "Meilen pro Stunde",
+            (Language::De, KilometersPerHour) => "Kilometer pro Stunde",
+            (Language::De, MetersPerSecond) => "Meter pro Sekunde",
+            (Language::Pt, Knots) => "nós",
+            (Language::Pt, MilesPerHour) => "milhas por hora",
+            (Language::Pt, KilometersPerHour) => "quilômetros por hora",
+            (Language::Pt, MetersPerSecond) => "metros por segundo",
+        }
+    }
+
+    /// The unit for a visibility of `value`, singular or plural
+    pub fn visibility_unit<'a>(&self, unit: &'a VisibilityUnit, value: f64) -> &'a str {
+        use VisibilityUnit::*;
+        // French and Portuguese keep the singular below two; the others only for one
+        let singular = match self {
+            Language::Fr | Language::Pt => value < 2.0,
+            _ => value == 1.0,
+        };
+        match (self, unit) {
+            (Language::En, unit) => unit.name(value),
+            (Language::Es, StatuteMiles) if singular => "milla",
+            (Language::Es, StatuteMiles) => "millas",
+            (Language::Es, Kilometers) if singular => "kilómetro",
+            (Language::Es, Kilometers) => "kilómetros",
+            (Language::Es, Meters) => "metros",
+            (Language::Fr, StatuteMiles) if singular => "mille",
+            (Language::Fr, StatuteMiles) => "milles",
+            (Language::Fr, Kilometers) if singular => "kilomètre",
+            (Language::Fr, Kilometers) => "kilomètres",
+            (Language::Fr, Meters) => "mètres",
+            (Language::De, StatuteMiles) if singular => "Meile",
+            (Language::De, StatuteMiles) => "Meilen",
+            (Language::De, Kilometers) => "Kilometer",
+            (Language::De, Meters) => "Meter",
+            (Language::Pt, StatuteMiles) if singular => "milha",
+            (Language::Pt, StatuteMiles) => "milhas",
+            (Language::Pt, Kilometers) if singular => "quilômetro",
+            (Language::Pt, Kilometers) => "quilômetros",
+            (Language::Pt, Meters) => "metros",
+        }
+    }
+
+    pub fn pressure_unit<'a>(&self, unit: &'a PressureUnit) -> &'a str {
+        use PressureUnit::*;
+        match (self, unit) {
+            (Language::En, unit) => unit.name(),
+            (Language::Es, InchesOfMercury) => "pulgadas de mercurio",
+            (Language::Es, Hectopascals) => "hectopascales",
+            (Language::Es, MillimetersOfMercury) => "milímetros de mercurio",
+            (Language::Fr, InchesOfMercury) => "pouces de mercure",
+            (Language::Fr, Hectopascals) => "hectopascals",
+            (Language::Fr, MillimetersOfMercury) => "millimètres de mercure",
+            (Language::De, InchesOfMercury) => "Zoll Quecksilbersäule",
+            (Language::De, Hectopascals) => "Hektopascal",
+            (Language::De, MillimetersOfMercury) => "Millimeter Quecksilbersäule",
+            (Language::Pt, InchesOfMercury) => "polegadas de mercúrio",
+            (Language::Pt, Hectopascals) => "hectopascais",
+            (Language::Pt, MillimetersOfMercury) => "milímetros de mercúrio",
+        }
+    }
+
+    pub fn altitude_unit<'a>(&self, unit: &'a AltitudeUnit) -> &'a str {
+        match (self, unit) {
+            (Language::En, unit) => unit.name(),
+            (Language::Es, AltitudeUnit::Feet) => "pies",
+            (Language::Fr, AltitudeUnit::Feet) => "pieds",
+            (Language::De, AltitudeUnit::Feet) => "Fuß",
+            (Language::Pt, AltitudeUnit::Feet) => "pés",
+            (Language::Es | Language::Pt, AltitudeUnit::Meters) => "metros",
+            (Language::Fr, AltitudeUnit::Meters) => "mètres",
+            (Language::De, AltitudeUnit::Meters) => "Meter",
+        }
+    }
+
+    /// Cloud cover as spoken, e.g. "broken" or "nuages fragmentés"
+    pub fn cloud_cover(&self, cover: CloudCover) -> String {
+        use CloudCover::*;
+        let text = match (self, cover) {
+            (Language::En, cover) => return cover.description().to_lowercase(),
+            (Language::Es, SkyClear | Clear) => "despejado",
+            (Language::Es, NoSignificantCloud) => "sin nubes significativas",
+            (Language::Es, NoCloudDetected) => "sin nubes detectadas",
+            (Language::Es, Few) => "pocas nubes",
+            (Language::Es, Scattered) => "nubes dispersas",
+            (Language::Es, Broken) => "nuboso",
+            (Language::Es, Overcast) => "cubierto",
+            (Language::Es, VerticalVisibility) => "visibilidad vertical",
+            (Language::Fr, SkyClear | Clear) => "ciel clair",
+            (Language::Fr, NoSignificantCloud) => "pas de nuages significatifs",
+            (Language::Fr, NoCloudDetected) => "pas de nuages détectés",
+            (Language::Fr, Few) => "quelques nuages",
+            (Language::Fr, Scattered) => "nuages épars",
+            (Language::Fr, Broken) => "nuages fragmentés",
+            (Language::Fr, Overcast) => "couvert",
+            (Language::Fr, VerticalVisibility) => "visibilité verticale",
+            (Language::De, SkyClear | Clear) => "wolkenlos",
+            (Language::De, NoSignificantCloud) => "keine signifikanten Wolken",
+            (Language::De, NoCloudDetected) => "keine Wolken erkannt",
+            (Language::De, Few) => "wenige Wolken",
+            (Language::De, Scattered) => "aufgelockerte Bewölkung",
+            (Language::De, Broken) => "durchbrochene Bewölkung",
+            (Language::De, Overcast) => "bedeckt",
+            (Language::De, VerticalVisibility) => "Vertikalsicht",
+            (Language::Pt, SkyClear | Clear) => "céu claro",
+            (Language::Pt, NoSignificantCloud) => "sem nuvens significativas",
+            (Language::Pt, NoCloudDetected) => "sem nuvens detectadas",
+            (Language::Pt, Few) => "poucas nuvens",
+            (Language::Pt, Scattered) => "nuvens esparsas",
+            (Language::Pt, Broken) => "nublado",
+            (Language::Pt, Overcast) => "encoberto",
+            (Language::Pt, VerticalVisibility) => "visibilidade vertical",
+        };
+        text.to_string()
+    }
+
+    pub fn cloud_type(&self, cloud_type: CloudType) -> String {
+        let text = match (self, cloud_type) {
+            (Language::En, cloud_type) => return cloud_type.description().to_lowercase(),
+            (Language::De, CloudType::Cumulonimbus) => "Cumulonimbus",
+            (_, CloudType::Cumulonimbus) => "cumulonimbus",
+            (Language::Es, CloudType::ToweringCumulus) => "cúmulos de gran desarrollo",
+            (Language::Fr, CloudType::ToweringCumulus) => "cumulus bourgeonnants",
+            (Language::De, CloudType::ToweringCumulus) => "aufgetürmte Cumulus",
+            (Language::Pt, CloudType::ToweringCumulus) => "cúmulos congestos",
+        };
+        text.to_string()
+    }
+
+    /// Flight category description in lower case, e.g. "instrument flight rules"
+    pub fn flight_category(&self, category: FlightCategory) -> String {
+        use FlightCategory::*;
+        let text = match (self, category) {
+            (Language::En, category) => return category.description().to_lowercase(),
+            (Language::Es, Vfr) => "reglas de vuelo visual",
+            (Language::Es, Mvfr) => "reglas de vuelo visual marginales",
+            (Language::Es, Ifr) => "reglas de vuelo por instrumentos",
+            (Language::Es, Lifr) => "reglas de vuelo por instrumentos bajas",
+            (Language::Fr, Vfr) => "règles de vol à vue",
+            (Language::Fr, Mvfr) => "règles de vol à vue marginales",
+            (Language::Fr, Ifr) => "règles de vol aux instruments",
+            (Language::Fr, Lifr) => "règles de vol aux instruments basses",
+            (Language::De, Vfr) => "Sichtflugregeln",
+            (Language::De, Mvfr) => "eingeschränkte Sichtflugregeln",
+            (Language::De, Ifr) => "Instrumentenflugregeln",
+            (Language::De, Lifr) => "niedrige Instrumentenflugregeln",
+            (Language::Pt, Vfr) => "regras de voo visual",
+            (Language::Pt, Mvfr) => "regras de voo visual marginais",
+            (Language::Pt, Ifr) => "regras de voo por instrumentos",
+            (Language::Pt, Lifr) => "regras de voo por instrumentos baixas",
+        };
+        text.to_string()
+    }
+
+    /// The name of a phenomenon, e.g. "llovizna" for DZ
+    pub fn weather_code(&self, code: &WmoCode) -> String {
+        match self {
+            Language::En => code.description().to_lowercase(),
+            _ => self.weather_noun(code).0.to_string(),
+        }
+    }
+
+    fn weather_noun(&self, code: &WmoCode) -> (&'static str, Gender) {
+        use Gender::*;
+        use WmoCode::*;
+        match (self, code) {
+            (Language::En, _) => ("", Neuter),
+            (Language::Es, Rain) => ("lluvia", Feminine),
+            (Language::Es, Snow) => ("nieve", Feminine),
+            (Language::Es, Drizzle) => ("llovizna", Feminine),
+            (Language::Es, SnowGrains) => ("cinarra", Feminine),
+            (Language::Es, IceCrystals) => ("cristales de hielo", MasculinePlural),
+            (Language::Es, IcePellets) => ("hielo granulado", Masculine),
+            (Language::Es, Hail) => ("granizo", Masculine),
+            (Language::Es, SmallHail) => ("granizo pequeño", Masculine),
+            (Language::Es, UnknownPrecipitation) => ("precipitación desconocida", Feminine),
+            (Language::Es, Fog) => ("niebla", Feminine),
+            (Language::Es, Mist) => ("neblina", Feminine),
+            (Language::Es, Haze) => ("calima", Feminine),
+            (Language::Es, Smoke) => ("humo", Masculine),
+            (Language::Es, VolcanicAsh) => ("ceniza volcánica", Feminine),
+            (Language::Es, Dust) => ("polvo", Masculine),
+            (Language::Es, Sand) => ("arena", Feminine),
+            (Language::Es, Spray) => ("rociones", MasculinePlural),
+            (Language::Es, Thunderstorm) => ("tormenta", Feminine),
+            (Language::Es, Squall) => ("turbonada", Feminine),
+            (Language::Es, FunnelCloud) => ("nube de embudo", Feminine),
+            (Language::Es, Sandstorm) => ("tempestad de arena", Feminine),
+            (Language::Es, Duststorm) => ("tempestad de polvo", Feminine),
+            (Language::Es, DustDevils) => ("remolinos de polvo", MasculinePlural),
+            (Language::Fr, Rain) => ("pluie", Feminine),
+            (Language::Fr, Snow) => ("neige", Feminine),
+            (Language::Fr, Drizzle) => ("bruine", Feminine),
+            (Language::Fr, SnowGrains) => ("neige en grains", Feminine),
+            (Language::Fr, IceCrystals) => ("cristaux de glace", MasculinePlural),
+            (Language::Fr, IcePellets) => ("granules de glace", MasculinePlural),
+            (Language::Fr, Hail) => ("grêle", Feminine),
+            (Language::Fr, SmallHail) => ("grésil", Masculine),
+            (Language::Fr, UnknownPrecipitation) => ("précipitation inconnue", Feminine),
+            (Language::Fr, Fog) => ("brouillard", Masculine),
+            (Language::Fr, Mist) => ("brume", Feminine),
+            (Language::Fr, Haze) => ("brume sèche", Feminine),
+            (Language::Fr, Smoke) => ("fumée", Feminine),
+            (Language::Fr, VolcanicAsh) => ("cendres volcaniques", FemininePlural),
+            (Language::Fr, Dust) => ("poussière", Feminine),
+            (Language::Fr, Sand) => ("sable", Masculine),
+            (Language::Fr, Spray) => ("embruns", MasculinePlural),
+            (Language::Fr, Thunderstorm) => ("orage", Masculine),
+            (Language::Fr, Squall) => ("grain", Masculine),
+            (Language::Fr, FunnelCloud) => ("nuage en entonnoir", Masculine),
+            (Language::Fr, Sandstorm) => ("tempête de sable", Feminine),
+            (Language::Fr, Duststorm) => ("tempête de poussière", Feminine),
+            (Language::Fr, DustDevils) => ("tourbillons de poussière", MasculinePlural),
+            (Language::De, Rain) => ("Regen", Masculine),
+            (Language::De, Snow) => ("Schnee", Masculine),
+            (Language::De, Drizzle) => ("Sprühregen", Masculine),
+            (Language::De, SnowGrains) => ("Schneegriesel", Masculine),
+            (Language::De, IceCrystals) => ("Eisnadeln", MasculinePlural),
+            (Language::De, IcePellets) => ("Eiskörner", MasculinePlural),
+            (Language::De, Hail) => ("Hagel", Masculine),
+            (Language::De, SmallHail) => ("Graupel", Masculine),
+            (Language::De, UnknownPrecipitation) => ("unbekannter Niederschlag", Masculine),
+            (Language::De, Fog) => ("Nebel", Masculine),
+            (Language::De, Mist) => ("feuchter Dunst", Masculine),
+            (Language::De, Haze) => ("trockener Dunst", Masculine),
+            (Language::De, Smoke) => ("Rauch", Masculine),
+            (Language::De, VolcanicAsh) => ("Vulkanasche", Feminine),
+            (Language::De, Dust) => ("Staub", Masculine),
+            (Language::De, Sand) => ("Sand", Masculine),
+            (Language::De, Spray) => ("Gischt", Feminine),
+            (Language::De, Thunderstorm) => ("Gewitter", Neuter),
+            (Language::De, Squall) => ("Böen", MasculinePlural),
+            (Language::De, FunnelCloud) => ("Trichterwolke", Feminine),
+            (Language::De, Sandstorm) => ("Sandsturm", Masculine),
+            (Language::De, Duststorm) => ("Staubsturm", Masculine),
+            (Language::De, DustDevils) => ("Staubwirbel", MasculinePlural),
+            (Language::Pt, Rain) => ("chuva", Feminine),
+            (Language::Pt, Snow) => ("neve", Feminine),
+            (Language::Pt, Drizzle) => ("chuvisco", Masculine),
+            (Language::Pt, SnowGrains) => ("grãos de neve", MasculinePlural),
+            (Language::Pt, IceCrystals) => ("cristais de gelo", MasculinePlural),
+            (Language::Pt, IcePellets) => ("pelotas de gelo", FemininePlural),
+            (Language::Pt, Hail) => ("granizo", Masculine),
+            (Language::Pt, SmallHail) => ("granizo pequeno", Masculine),
+            (Language::Pt, UnknownPrecipitation) => ("precipitação desconhecida", Feminine),
+            (Language::Pt, Fog) => ("nevoeiro", Masculine),
+            (Language::Pt, Mist) => ("névoa úmida", Feminine),
+            (Language::Pt, Haze) => ("névoa seca", Feminine),
+            (Language::Pt, Smoke) => ("fumaça", Feminine),
+            (Language::Pt, VolcanicAsh) => ("cinzas vulcânicas", FemininePlural),
+            (Language::Pt, Dust) => ("poeira", Feminine),
+            (Language::Pt, Sand) => ("areia", Feminine),
+            (Language::Pt, Spray) => ("borrifos", MasculinePlural),
+            (Language::Pt, Thunderstorm) => ("trovoada", Feminine),
+            (Language::Pt, Squall) => ("rajada de vento", Feminine),
+            (Language::Pt, FunnelCloud) => ("nuvem funil", Feminine),
+            (Language::Pt, Sandstorm) => ("tempestade de areia", Feminine),
+            (Language::Pt, Duststorm) => ("tempestade de poeira", Feminine),
+            (Language::Pt, DustDevils) => ("redemoinhos de poeira", MasculinePlural),
+        }
+    }
+
+    /// +FC, which English calls "tornado or waterspout"
+    fn tornado(&self) -> (&'static str, Gender) {
+        match self {
+            Language::En => ("tornado or waterspout", Gender::Neuter),
+            Language::Es => ("tornado o tromba marina", Gender::Masculine),
+            Language::Fr => ("tornade ou trombe marine", Gender::Feminine),
+            Language::De => ("Tornado oder Wasserhose", Gender::Masculine),
+            Language::Pt => ("tornado ou tromba d'água", Gender::Masculine),
+        }
+    }
+
+    /// SH on its own
+    fn showers(&self) -> (&'static str, Gender) {
+        match self {
+            Language::En => ("showers", Gender::Neuter),
+            Language::Es => ("chubascos", Gender::MasculinePlural),
+            Language::Fr => ("averses", Gender::FemininePlural),
+            Language::De => ("Schauer", Gender::MasculinePlural),
+            Language::Pt => ("pancadas", Gender::FemininePlural),
+        }
+    }
+
+    fn intensity(&self, intensity: Intensity) -> Option<Adjective> {
+        let adjective = match (self, intensity) {
+            (_, Intensity::Moderate) | (Language::En, _) => return None,
+            (Language::Es, Intensity::Light) => ["débil", "débil", "débil", "débiles", "débiles"],
+            (Language::Es, Intensity::Heavy) => {
+                ["fuerte", "fuerte", "fuerte", "fuertes", "fuertes"]
+            }
+            (Language::Fr, Intensity::Light) => {
+                ["faible", "faible", "faible", "faibles", "faibles"]
+            }
+            (Language::Fr, Intensity::Heavy) => ["fort", "forte", "fort", "forts", "fortes"],
+            (Language::De, Intensity::Light) => {
+                ["leichter", "leichte", "leichtes", "leichte", "leichte"]
+            }
+            (Language::De, Intensity::Heavy) => {
+                ["starker", "starke", "starkes", "starke", "starke"]
+            }
+            (Language::Pt, Intensity::Light) => ["fraco", "fraca", "fraco", "fracos", "fracas"],
+            (Language::Pt, Intensity::Heavy) => ["forte", "forte", "forte", "fortes", "fortes"],
+        };
+        Some(adjective)
+    }
+
+    /// Descriptors other than TS, which is a phrase of its own
+    fn descriptor(&self, descriptor: Descriptor) -> Modifier {
+        use Gender::*;
+        use Modifier::{Adjective, Phrase};
+        match (self, descriptor) {
+            (Language::Es, Descriptor::Shallow) => {
+                Adjective(["bajo", "baja", "bajo", "bajos", "bajas"])
+            }
+            (Language::Es, Descriptor::Partial) => {
+                Adjective(["parcial", "parcial", "parcial", "parciales", "parciales"])
+            }
+            (Language::Es, Descriptor::Patches) => Phrase("bancos de {}", MasculinePlural),
+            (Language::Es, Descriptor::LowDrifting) => Phrase("ventisca baja de {}", Feminine),
+            (Language::Es, Descriptor::Blowing) => Phrase("ventisca alta de {}", Feminine),
+            (Language::Es, Descriptor::Showers) => Phrase("chubascos de {}", MasculinePlural),
+            (Language::Es, Descriptor::Freezing) => Adjective([
+                "engelante",
+                "engelante",
+                "engelante",
+                "engelantes",
+                "engelantes",
+            ]),
+            (Language::Fr, Descriptor::Shallow) => {
+                Adjective(["mince", "mince", "mince", "minces", "minces"])
+            }
+            (Language::Fr, Descriptor::Partial) => {
+                Adjective(["partiel", "partielle", "partiel", "partiels", "partielles"])
+            }
+            (Language::Fr, Descriptor::Patches) => Phrase("bancs de {}", MasculinePlural),
+            (Language::Fr, Descriptor::LowDrifting) => Phrase("chasse basse de {}", Feminine),
+            (Language::Fr, Descriptor::Blowing) => Phrase("chasse élevée de {}", Feminine),
+            (Language::Fr, Descriptor::Showers) => Phrase("averses de {}", FemininePlural),
+            (Language::Fr, Descriptor::Freezing) => Adjective([
+                "verglaçant",
+                "verglaçante",
+                "verglaçant",
+                "verglaçants",
+                "verglaçantes",
+            ]),
+            (Language::De, Descriptor::Shallow) => {
+                Adjective(["flacher", "flache", "flaches", "flache", "flache"])
+            }
+            (Language::De, Descriptor::Partial) => Adjective([
+                "teilweiser",
+                "teilweise",
+                "teilweises",
+                "teilweise",
+                "teilweise",
+            ]),
+            // German makes compounds, e.g. "Nebelschwaden" and "Schneeschauer"
+            (Language::De, Descriptor::Patches) => Phrase("{}schwaden", MasculinePlural),
+            (Language::De, Descriptor::LowDrifting) => Phrase("{}fegen", Neuter),
+            (Language::De, Descriptor::Blowing) => Phrase("{}treiben", Neuter),
+            (Language::De, Descriptor::Showers) => Phrase("{}schauer", MasculinePlural),
+            (Language::De, Descriptor::Freezing) => Adjective([
+                "gefrierender",
+                "gefrierende",
+                "gefrierendes",
+                "gefrierende",
+                "gefrierende",
+            ]),
+            (Language::Pt, Descriptor::Shallow) => {
+                Adjective(["baixo", "baixa", "baixo", "baixos", "baixas"])
+            }
+            (Language::Pt, Descriptor::Partial) => {
+                Adjective(["parcial", "parcial", "parcial", "parciais", "parciais"])
+            }
+            (Language::Pt, Descriptor::Patches) => Phrase("bancos de {}", MasculinePlural),
+            (Language::Pt, Descriptor::LowDrifting) => Phrase("{} levantada baixa", Feminine),
+            (Language::Pt, Descriptor::Blowing) => Phrase("{} levantada alta", Feminine),
+            (Language::Pt, Descriptor::Showers) => Phrase("pancadas de {}", FemininePlural),
+            (Language::Pt, Descriptor::Freezing) => Adjective([
+                "congelante",
+                "congelante",
+                "congelante",
+                "congelantes",
+                "congelantes",
+            ]),
+            (Language::En, _) | (_, Descriptor::Thunderstorm) => Phrase("{}", Neuter),
+        }
+    }
+
+    /// `noun` with `adjective` before it in German and after it otherwise
+    fn qualify(&self, noun: &str, gender: Gender, adjective: Option<Adjective>) -> String {
+        match adjective.map(|adjective| agree(&adjective, gender)) {
+            Some(word) if *self == Language::De => format!("{} {}", word, noun),
+            Some(word) => format!("{} {}", noun, word),
+            None => noun.to_string(),
+        }
+    }
+
+    fn join(&self, words: &[&str]) -> String {
+        match words {
+            [] => String::new(),
+            [word] => word.to_string(),
+            [rest @ .., last] => format!("{} {} {}", rest.join(", "), self.term(Term::And), last),
+        }
+    }
+
+    /// A present weather group as spoken, e.g. "light freezing rain" or
+    /// "lluvia engelante débil"
+    pub fn phenomenon(&self, phenomenon: &WeatherPhenomenon) -> String {
+        if *self == Language::En {
+            return phenomenon.description();
+        }
+        let intensity = self.intensity(phenomenon.intensity);
+        let nouns: Vec<(&str, Gender)> = if phenomenon.intensity == Intensity::Heavy
+            && phenomenon.phenomena == [WmoCode::FunnelCloud]
+        {
+            vec![self.tornado()]
+        } else {
+            phenomenon
+                .phenomena
+                .iter()
+                .map(|code| self.weather_noun(code))
+                .collect()
+        };
+        let words: Vec<&str> = nouns.iter().map(|(word, _)| *word).collect();
+        let (noun, gender) = (self.join(&words), nouns.first().map(|(_, g)| *g));
+
+        let mut text = match (phenomenon.descriptor, gender) {
+            (Some(Descriptor::Thunderstorm), None) => {
+                self.weather_noun(&WmoCode::Thunderstorm).0.to_string()
+            }
+            (Some(Descriptor::Thunderstorm), Some(gender)) => self.phrase(
+                Term::ThunderstormWith,
+                &self.qualify(&noun, gender, intensity),
+            ),
+            (Some(Descriptor::Showers), None) => {
+                let (showers, gender) = self.showers();
+                self.qualify(showers, gender, intensity)
+            }
+            (Some(descriptor), gender) => {
+                let gender = gender.unwrap_or(Gender::Masculine);
+                match self.descriptor(descriptor) {
+                    Modifier::Adjective(adjective) => {
+                        let described = self.qualify(&noun, gender, Some(adjective));
+                        match intensity {
+                            Some(intensity) if *self == Language::De => {
+                                format!("{} {}", agree(&intensity, gender), described)
+                            }
+                            Some(intensity) => {
+                                format!("{} {}", described, agree(&intensity, gender))
+                            }
+                            None => described,
+                        }
+                    }
+                    Modifier::Phrase(phrase, gender) => {
+                        self.qualify(&phrase.replacen("{}", &noun, 1), gender, intensity)
+                    }
+                }
+            }
+            (None, gender) => self.qualify(&noun, gender.unwrap_or(Gender::Masculine), intensity),
+        };
+
+        if phenomenon.recent {
+            text = self.phrase(Term::Recent, &text);
+        }
+        if phenomenon.in_vicinity {
+            text = self.phrase(Term::InTheVicinity, &text);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Observation;
+
+    const REPORT: &str =
+        "LFPG 171730Z 24015KT 3000 -FZRA +TSRA VCSH BR RESN FEW025CB BKN040 M01/M03 Q1002";
+
+    fn weather(language: Language) -> Vec<String> {
+        let obs = Observation::parse(REPORT).unwrap();
+        obs.weather
+            .iter()
+            .map(|phenomenon| language.phenomenon(phenomenon))
+            .collect()
+    }
+
+    #[test]
+    fn reads_numbers() {
+        assert_eq!(Language::De.digit(2), "zwo");
+        assert_eq!(Language::Fr.digit(0), "zéro");
+        assert_eq!(Language::En.hundreds(9), "nine hundred");
+        assert_eq!(Language::Es.hundreds(9), "novecientos");
+        assert_eq!(Language::Es.hundreds(1), "cien");
+        assert_eq!(Language::De.hundreds(9), "neunhundert");
+        assert_eq!(Language::En.decimal("29.92"), "29.92");
+        assert_eq!(Language::Pt.decimal("29.92"), "29,92");
+    }
+
+    #[test]
+    fn fills_phrases_in_order() {
+        assert_eq!(
+            Language::Fr.phrase(Term::LessThan, "800 mètres"),
+            "moins de 800 mètres"
+        );
+        assert_eq!(
+            Language::Es.phrase_with(Term::ChanceBetween, &["30", "1000 zulu", "1400 zulu"]),
+            "Probabilidad del 30 por ciento entre 1000 zulu y 1400 zulu..."
+        );
+    }
+
+    #[test]
+    fn names_present_weather() {
+        assert_eq!(
+            weather(Language::En),
+            [
+                "light freezing rain",
+                "thunderstorm with heavy rain",
+                "showers in the vicinity",
+                "mist",
+                "recent snow",
+            ]
+        );
+        assert_eq!(
+            weather(Language::Es),
+            [
+                "lluvia engelante débil",
+                "tormenta con lluvia fuerte",
+                "chubascos en las proximidades",
+                "neblina",
+                "nieve reciente",
+            ]
+        );
+        assert_eq!(
+            weather(Language::De),
+            [
+                "leichter gefrierender Regen",
+                "Gewitter und starker Regen",
+                "Schauer in der Umgebung",
+                "feuchter Dunst",
+                "Schnee, kürzlich",
+            ]
+        );
+    }
+
+    #[test]
+    fn names_clouds_and_flight_categories() {
+        let obs = Observation::parse(REPORT).unwrap();
+        let few = &obs.sky[0];
+        assert_eq!(Language::Fr.cloud_cover(few.cover), "quelques nuages");
+        assert_eq!(Language::Pt.cloud_cover(obs.sky[1].cover), "nublado");
+        assert_eq!(
+            Language::De.cloud_type(few.cloud_type.unwrap()),
+            "Cumulonimbus"
+        );
+        assert_eq!(
+            Language::Fr.flight_category(FlightCategory::Ifr),
+            "règles de vol aux instruments"
+        );
+    }
+}
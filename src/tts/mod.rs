@@ -2,11 +2,22 @@ use std::error::Error;
 use std::fmt;
 
 pub mod announcements;
+pub mod atis;
 pub mod audio_conversion;
 pub mod espeak;
 pub mod google_tts;
-
-pub use announcements::{AnnouncementFormat, generate_weather_announcement};
+pub mod language;
+pub mod phraseology;
+pub mod templates;
+
+pub use announcements::{
+    AnnouncementFormat, AnnouncementOptions, combine_roundup, generate_roundup_announcement,
+    generate_taf_announcement, generate_trend_announcement, generate_weather_announcement,
+};
+pub use atis::InformationLetters;
+pub use language::Language;
+pub use phraseology::{Phraseology, Wording};
+pub use templates::AnnouncementTemplate;
 
 #[derive(Debug, Clone, clap::ValueEnum)]
 pub enum Voice {
@@ -113,6 +124,17 @@ pub trait TtsBackend {
     fn backend_name(&self) -> &str;
 }
 
+/// Backends that can synthesize without blocking, for use inside an async runtime
+#[cfg(feature = "async")]
+pub trait AsyncTtsBackend: TtsBackend {
+    /// Async version of [`TtsBackend::synthesize`]
+    fn synthesize_async(
+        &self,
+        text: &str,
+        format: &AudioFormat,
+    ) -> impl std::future::Future<Output = Result<Vec<u8>, TtsError>> + Send;
+}
+
 /// Common TTS operations shared by all backends
 pub struct TtsPlayer;
 
@@ -0,0 +1,320 @@
+//! How identifiers and numbers are read out: plainly, or the way pilots and
+//! controllers say them, e.g. "Kilo Sierra Juliett Charlie" and
+//! "two niner niner two".
+
+use crate::metar::Wind;
+use crate::tts::language::{Language, Term};
+use crate::units::Units;
+
+/// How identifiers and numbers are read out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Phraseology {
+    /// Letters spelled individually and numbers read as numbers
+    #[default]
+    Plain,
+    /// ICAO phonetic alphabet and numbers digit by digit, with "niner"
+    Aviation,
+    /// Like aviation, with the ICAO "tree", "fower" and "fife" as well
+    Icao,
+}
+
+const PHONETIC_ALPHABET: [&str; 26] = [
+    "Alfa", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel", "India", "Juliett",
+    "Kilo", "Lima", "Mike", "November", "Oscar", "Papa", "Quebec", "Romeo", "Sierra", "Tango",
+    "Uniform", "Victor", "Whiskey", "X-ray", "Yankee", "Zulu",
+];
+
+/// The phonetic alphabet word for a letter, e.g. "Kilo" for 'K'
+pub fn phonetic_letter(c: char) -> Option<&'static str> {
+    let c = c.to_ascii_uppercase();
+    c.is_ascii_uppercase()
+        .then(|| PHONETIC_ALPHABET[(c as u8 - b'A') as usize])
+}
+
+impl Phraseology {
+    fn is_plain(&self) -> bool {
+        *self == Phraseology::Plain
+    }
+
+    /// This phraseology in `language`
+    pub fn in_language(self, language: Language) -> Wording {
+        Wording {
+            phraseology: self,
+            language,
+        }
+    }
+}
+
+/// How identifiers and numbers are read out, in a given language. The
+/// radio's "niner" and "tree" are English; other languages read digits with
+/// their own words and leave plain whole numbers to the voice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Wording {
+    pub phraseology: Phraseology,
+    pub language: Language,
+}
+
+impl Wording {
+    fn is_plain(&self) -> bool {
+        self.phraseology.is_plain()
+    }
+
+    fn is_english(&self) -> bool {
+        self.language == Language::En
+    }
+
+    /// A single digit, e.g. "niner" for 9
+    pub fn digit(&self, d: u32) -> &'static str {
+        if !self.is_english() {
+            return self.language.digit(d);
+        }
+        let icao = self.phraseology == Phraseology::Icao;
+        match d {
+            3 if icao => "tree",
+            4 if icao => "fower",
+            5 if icao => "fife",
+            9 if !self.is_plain() => "niner",
+            d => self.language.digit(d),
+        }
+    }
+
+    /// Each digit in `text` as a word, skipping anything else, e.g. "two niner
+    /// niner two" for "29.92"
+    pub fn digits(&self, text: &str) -> String {
+        text.chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|d| self.digit(d))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// A station identifier or other code, letter by letter
+    pub fn identifier(&self, id: &str) -> String {
+        id.chars()
+            .map(
+                |c| match (self.is_plain(), phonetic_letter(c), c.to_digit(10)) {
+                    (false, Some(word), _) => word.to_string(),
+                    (false, None, Some(d)) => self.digit(d).to_string(),
+                    _ => c.to_string(),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// A whole number; digit by digit unless plain, e.g. "minus one five"
+    pub fn number(&self, n: i64) -> String {
+        let sign = if n < 0 {
+            format!("{} ", self.language.term(Term::Minus))
+        } else {
+            String::new()
+        };
+        if self.is_plain() {
+            format!("{}{}", sign, n.unsigned_abs())
+        } else {
+            format!("{}{}", sign, self.digits(&n.unsigned_abs().to_string()))
+        }
+    }
+
+    /// A height as controllers say it: thousands digit by digit, then
+    /// hundreds, e.g. "two thousand five hundred" or "uno cero mil quinientos"
+    pub fn height(&self, feet: i64) -> String {
+        if self.is_plain() || feet <= 0 || feet % 100 != 0 {
+            return self.number(feet);
+        }
+        let thousands = feet / 1000;
+        let hundreds = (feet % 1000) / 100;
+        let mut words = Vec::new();
+        if thousands > 0 {
+            let thousand = if self.is_english() && self.phraseology == Phraseology::Icao {
+                "tousand"
+            } else {
+                self.language.term(Term::Thousand)
+            };
+            words.push(format!("{} {}", self.number(thousands), thousand));
+        }
+        if hundreds > 0 {
+            let hundreds = hundreds as u32;
+            words.push(if self.is_english() {
+                format!("{} hundred", self.digit(hundreds))
+            } else {
+                self.language.hundreds(hundreds)
+            });
+        }
+        words.join(" ")
+    }
+
+    /// Wind in `units`, e.g. "two seven zero at one five gusting two five"
+    pub fn wind(&self, wind: &Wind, units: &Units) -> String {
+        let language = self.language;
+        if wind.is_calm() {
+            return language.term(Term::Calm).to_string();
+        }
+        let speed = units.wind_speed(wind.speed_knots());
+        let gust = wind.gust_knots().map(|g| units.wind_speed(g));
+        let at = language.term(Term::WindAt);
+        let variable = language.term(Term::Variable);
+        let gusting = language.term(Term::Gusting);
+        let between = language.term(Term::VariableBetween);
+        let and = language.term(Term::And);
+        if self.is_plain() {
+            let unit = language.wind_speed_unit(&units.wind_speed);
+            let degrees = language.term(Term::Degrees);
+            let mut text = match wind.direction {
+                Some(dir) => format!("{} {} {} {} {}", dir, degrees, at, speed, unit),
+                None => format!("{} {} {} {}", variable, at, speed, unit),
+            };
+            if let Some(gust) = gust {
+                text.push_str(&format!(" {} {}", gusting, gust));
+            }
+            if let Some((from, to)) = wind.variable_sector {
+                text.push_str(&format!(
+                    ", {} {} {} {} {}",
+                    between, from, and, to, degrees
+                ));
+            }
+            return text;
+        }
+
+        let mut text = match wind.direction {
+            Some(dir) => format!(
+                "{} {} {}",
+                self.digits(&format!("{:03}", dir)),
+                at,
+                self.number(speed)
+            ),
+            None => format!("{} {} {}", variable, at, self.number(speed)),
+        };
+        if let Some(gust) = gust {
+            text.push_str(&format!(" {} {}", gusting, self.number(gust)));
+        }
+        if let Some((from, to)) = wind.variable_sector {
+            text.push_str(&format!(
+                ", {} {} {} {}",
+                between,
+                self.digits(&format!("{:03}", from)),
+                and,
+                self.digits(&format!("{:03}", to))
+            ));
+        }
+        text
+    }
+
+    /// An altimeter setting already in the units to be read, e.g. "two niner
+    /// niner two" for 29.92 inHg or "one zero one three" for 1013 hPa
+    pub fn altimeter(&self, value: f64, decimals: usize) -> String {
+        let text = format!("{:.*}", decimals, value);
+        if self.is_plain() {
+            self.language.decimal(&text)
+        } else {
+            self.digits(&text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Observation;
+
+    const PLAIN: Wording = Wording {
+        phraseology: Phraseology::Plain,
+        language: Language::En,
+    };
+    const AVIATION: Wording = Wording {
+        phraseology: Phraseology::Aviation,
+        language: Language::En,
+    };
+    const ICAO: Wording = Wording {
+        phraseology: Phraseology::Icao,
+        language: Language::En,
+    };
+
+    fn wind(raw: &str) -> Wind {
+        Observation::parse(raw).unwrap().wind.unwrap()
+    }
+
+    #[test]
+    fn says_niner_and_tree_fower_fife() {
+        assert_eq!(PLAIN.digits("29.92"), "two nine nine two");
+        assert_eq!(AVIATION.digits("29.92"), "two niner niner two");
+        assert_eq!(AVIATION.digits("345"), "three four five");
+        assert_eq!(ICAO.digits("345"), "tree fower fife");
+        assert_eq!(ICAO.digits("1013"), "one zero one tree");
+    }
+
+    #[test]
+    fn spells_identifiers() {
+        assert_eq!(PLAIN.identifier("KSJC"), "K S J C");
+        assert_eq!(AVIATION.identifier("KSJC"), "Kilo Sierra Juliett Charlie");
+        assert_eq!(ICAO.identifier("E16"), "Echo one six");
+        assert_eq!(AVIATION.identifier("29R"), "two niner Romeo");
+        assert_eq!(phonetic_letter('x'), Some("X-ray"));
+        assert_eq!(phonetic_letter('9'), None);
+    }
+
+    #[test]
+    fn reads_numbers_digit_by_digit() {
+        assert_eq!(PLAIN.number(-15), "minus 15");
+        assert_eq!(AVIATION.number(-15), "minus one five");
+        assert_eq!(AVIATION.number(190), "one niner zero");
+    }
+
+    #[test]
+    fn reads_heights_in_thousands_and_hundreds() {
+        assert_eq!(PLAIN.height(2500), "2500");
+        assert_eq!(AVIATION.height(2500), "two thousand five hundred");
+        assert_eq!(AVIATION.height(800), "eight hundred");
+        assert_eq!(AVIATION.height(10000), "one zero thousand");
+        assert_eq!(AVIATION.height(19000), "one niner thousand");
+        assert_eq!(ICAO.height(3500), "tree tousand fife hundred");
+        // Not a round height: digit by digit
+        assert_eq!(AVIATION.height(250), "two five zero");
+    }
+
+    #[test]
+    fn reads_heights_in_other_languages() {
+        let spanish = Phraseology::Aviation.in_language(Language::Es);
+        assert_eq!(spanish.height(2500), "dos mil quinientos");
+        assert_eq!(spanish.height(10000), "uno cero mil");
+        let german = Phraseology::Icao.in_language(Language::De);
+        assert_eq!(german.height(2500), "zwo tausend fünfhundert");
+        let french = Phraseology::Aviation.in_language(Language::Fr);
+        assert_eq!(french.height(700), "sept cents");
+        assert_eq!(
+            Phraseology::Plain.in_language(Language::Pt).height(2500),
+            "2500"
+        );
+    }
+
+    #[test]
+    fn reads_wind() {
+        let units = Units::default();
+        let gusty = wind("KSFO 171756Z 28015G25KT 250V310 10SM FEW008 18/12 A3000");
+        assert_eq!(
+            PLAIN.wind(&gusty, &units),
+            "280 degrees at 15 knots gusting 25, variable between 250 and 310 degrees"
+        );
+        assert_eq!(
+            AVIATION.wind(&gusty, &units),
+            "two eight zero at one five gusting two five, variable between two five zero and three one zero"
+        );
+        assert_eq!(
+            ICAO.wind(
+                &wind("EGLL 171750Z VRB03KT 9999 FEW020 15/09 Q1008"),
+                &units
+            ),
+            "variable at tree"
+        );
+        assert_eq!(
+            AVIATION.wind(&wind("KDEN 171753Z 00000KT 10SM SKC 20/M02 A3001"), &units),
+            "calm"
+        );
+    }
+
+    #[test]
+    fn reads_altimeter_settings() {
+        assert_eq!(AVIATION.altimeter(29.92, 2), "two niner niner two");
+        assert_eq!(ICAO.altimeter(1013.0, 0), "one zero one tree");
+    }
+}
@@ -0,0 +1,216 @@
+//! Announcements rendered from Jinja-style templates. The built-in formats
+//! are templates too, and can be imported or included from a user's own,
+//! e.g. `{% import "en/radio" as radio %}{{ radio.conditions() }}`.
+
+use crate::tts::announcements::AnnouncementFormat;
+use crate::tts::language::Language;
+use crate::{Result, WeatherError};
+use minijinja::Environment;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Each language's built-in templates, named e.g. "es/brief"
+macro_rules! builtin {
+    ($language:literal) => {
+        [
+            builtin!($language, "speech"),
+            builtin!($language, "brief"),
+            builtin!($language, "detailed"),
+            builtin!($language, "aviation"),
+            builtin!($language, "atis"),
+            // Macros shared by aviation and atis
+            builtin!($language, "radio"),
+        ]
+    };
+    ($language:literal, $name:literal) => {
+        (
+            concat!($language, "/", $name),
+            include_str!(concat!("templates/", $language, "/", $name, ".txt")),
+        )
+    };
+}
+
+/// In the order of [`Language`]
+const BUILTIN: [[(&str, &str); 6]; 5] = [
+    builtin!("en"),
+    builtin!("es"),
+    builtin!("fr"),
+    builtin!("de"),
+    builtin!("pt"),
+];
+
+/// A template for METAR announcements. Runs of whitespace in the output,
+/// including line breaks, are collapsed to single spaces.
+#[derive(Debug, Clone)]
+pub struct AnnouncementTemplate {
+    source: Source,
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+    /// Rendered as parsed into the environment, by its name there
+    Builtin {
+        name: &'static str,
+        source: &'static str,
+    },
+    User(String),
+}
+
+/// The built-in templates, parsed once. A user's template is rendered
+/// against it with `render_str`, so it is never added.
+fn environment() -> Result<&'static Environment<'static>> {
+    static ENVIRONMENT: OnceLock<Result<Environment<'static>>> = OnceLock::new();
+    ENVIRONMENT
+        .get_or_init(|| {
+            let mut env = Environment::new();
+            for (name, source) in BUILTIN.into_iter().flatten() {
+                env.add_template(name, source)
+                    .map_err(WeatherError::template)?;
+            }
+            Ok(env)
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+impl AnnouncementTemplate {
+    /// A template from its source, checked for syntax errors
+    pub fn new(source: impl Into<String>) -> Result<AnnouncementTemplate> {
+        let source = source.into();
+        environment()?
+            .template_from_str(&source)
+            .map_err(WeatherError::template)?;
+        Ok(AnnouncementTemplate {
+            source: Source::User(source),
+        })
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<AnnouncementTemplate> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| WeatherError::io(format!("Failed to read {}", path.display()), e))?;
+        AnnouncementTemplate::new(source)
+    }
+
+    /// The template behind a built-in format; trend announcements start with speech
+    pub fn builtin(format: &AnnouncementFormat, language: Language) -> AnnouncementTemplate {
+        let index = match format {
+            AnnouncementFormat::Speech | AnnouncementFormat::Trend => 0,
+            AnnouncementFormat::Brief => 1,
+            AnnouncementFormat::Detailed => 2,
+            AnnouncementFormat::Aviation => 3,
+            AnnouncementFormat::Atis => 4,
+        };
+        let (name, source) = BUILTIN[language as usize][index];
+        AnnouncementTemplate {
+            source: Source::Builtin { name, source },
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        match &self.source {
+            Source::Builtin { source, .. } => source,
+            Source::User(source) => source,
+        }
+    }
+
+    pub(crate) fn render(&self, context: impl Serialize) -> Result<String> {
+        let env = environment()?;
+        let text = match &self.source {
+            Source::Builtin { name, .. } => env
+                .get_template(name)
+                .and_then(|template| template.render(context)),
+            Source::User(source) => env.render_str(source, context),
+        }
+        .map_err(WeatherError::template)?;
+        Ok(text.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trend::tests::report;
+    use crate::tts::announcements::{AnnouncementOptions, generate_weather_announcement};
+
+    const SHOWERS: &str =
+        "KSFO 171756Z 31015G25KT 280V340 3/4SM -SHRA BR BKN008 OVC015 12/11 A2990";
+
+    fn announce(template: AnnouncementTemplate) -> Result<String> {
+        let options = AnnouncementOptions {
+            template: Some(template),
+            ..Default::default()
+        };
+        generate_weather_announcement(&report(SHOWERS, 0), &AnnouncementFormat::Speech, &options)
+    }
+
+    #[test]
+    fn renders_user_templates_over_the_decoded_report() {
+        let template = AnnouncementTemplate::new(
+            "{{ station }}:
+             {% for layer in sky %}{{ layer.cover }} {{ layer.base }}{% if layer.is_ceiling %} (ceiling){% endif %},
+             {% endfor %}
+             {% for phenomenon in phenomena %}[{{ phenomenon.code }}] {{ phenomenon.description }}
+             {% endfor %}",
+        )
+        .unwrap();
+        assert_eq!(
+            announce(template).unwrap(),
+            "K S F O: broken 800 (ceiling), overcast 1500, [-SHRA] light rain showers [BR] mist"
+        );
+    }
+
+    #[test]
+    fn imports_built_in_macros() {
+        let template =
+            AnnouncementTemplate::new("{% import \"en/radio\" as radio %}{{ radio.conditions() }}")
+                .unwrap();
+        assert!(
+            announce(template)
+                .unwrap()
+                .starts_with("Wind 310 degrees at 15 knots gusting 25")
+        );
+    }
+
+    #[test]
+    fn rejects_syntax_errors_when_created() {
+        for source in ["{% if sky %}unclosed", "{{ station ", "{% for %}"] {
+            assert!(
+                matches!(
+                    AnnouncementTemplate::new(source),
+                    Err(WeatherError::Template(_))
+                ),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn reports_render_errors_as_template_errors() {
+        let template = AnnouncementTemplate::new("{% include \"en/missing\" %}").unwrap();
+        assert!(matches!(announce(template), Err(WeatherError::Template(_))));
+        let template = AnnouncementTemplate::new("{{ sky | no_such_filter }}").unwrap();
+        assert!(matches!(announce(template), Err(WeatherError::Template(_))));
+    }
+
+    #[test]
+    fn keeps_built_in_names_and_sources() {
+        for language in [
+            Language::En,
+            Language::Es,
+            Language::Fr,
+            Language::De,
+            Language::Pt,
+        ] {
+            let template = AnnouncementTemplate::builtin(&AnnouncementFormat::Aviation, language);
+            assert!(
+                matches!(template.source, Source::Builtin { name, .. } if name.ends_with("/aviation"))
+            );
+            assert!(template.source().contains("radio"));
+        }
+        let template = AnnouncementTemplate::new("{{ station }}").unwrap();
+        assert_eq!(template.source(), "{{ station }}");
+    }
+}
@@ -0,0 +1,598 @@
+//! Unit systems for displaying and speaking observations.
+//!
+//! Values are stored internally in degrees Celsius, knots, meters or statute
+//! miles (visibility), hectopascals and feet; a [`Units`] picks how each
+//! quantity is presented.
+
+use crate::celsius_to_fahrenheit;
+use crate::metar::{Bound, CloudLayer, DistanceUnit, Visibility, Wind};
+use std::fmt;
+use std::str::FromStr;
+
+// Conversion factors shared by the decoders, formatters and derived values
+pub const KMH_PER_KNOT: f64 = 1.852;
+pub const MPH_PER_KNOT: f64 = 1.150_78;
+pub const MS_PER_KNOT: f64 = 0.514_444;
+pub const MMHG_PER_HPA: f64 = 0.750_062;
+pub const HPA_PER_INHG: f64 = 33.863_886;
+pub const METERS_PER_FOOT: f64 = 0.3048;
+pub const FEET_PER_METER: f64 = 3.280_84;
+pub const METERS_PER_STATUTE_MILE: f64 = 1609.344;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    pub fn from_celsius(&self, c: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => c,
+            TemperatureUnit::Fahrenheit => celsius_to_fahrenheit(c),
+        }
+    }
+
+    /// Convert a temperature difference, such as a dewpoint spread
+    pub fn difference_from_celsius(&self, delta: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => delta,
+            TemperatureUnit::Fahrenheit => delta * 9.0 / 5.0,
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            TemperatureUnit::Celsius => "celsius",
+            TemperatureUnit::Fahrenheit => "fahrenheit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindSpeedUnit {
+    Knots,
+    MilesPerHour,
+    KilometersPerHour,
+    MetersPerSecond,
+}
+
+impl WindSpeedUnit {
+    pub fn from_knots(&self, kt: f64) -> f64 {
+        match self {
+            WindSpeedUnit::Knots => kt,
+            WindSpeedUnit::MilesPerHour => kt * MPH_PER_KNOT,
+            WindSpeedUnit::KilometersPerHour => kt * KMH_PER_KNOT,
+            WindSpeedUnit::MetersPerSecond => kt * MS_PER_KNOT,
+        }
+    }
+
+    pub fn abbreviation(&self) -> &str {
+        match self {
+            WindSpeedUnit::Knots => "kt",
+            WindSpeedUnit::MilesPerHour => "mph",
+            WindSpeedUnit::KilometersPerHour => "km/h",
+            WindSpeedUnit::MetersPerSecond => "m/s",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            WindSpeedUnit::Knots => "knots",
+            WindSpeedUnit::MilesPerHour => "miles per hour",
+            WindSpeedUnit::KilometersPerHour => "kilometers per hour",
+            WindSpeedUnit::MetersPerSecond => "meters per second",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisibilityUnit {
+    StatuteMiles,
+    Kilometers,
+    Meters,
+}
+
+impl VisibilityUnit {
+    /// Convert and round to the precision usually reported in this unit.
+    /// Values already in this unit are kept as reported.
+    pub fn convert(&self, vis: &Visibility) -> f64 {
+        match (self, vis.unit) {
+            (VisibilityUnit::StatuteMiles, DistanceUnit::StatuteMiles)
+            | (VisibilityUnit::Meters, DistanceUnit::Meters) => return vis.distance,
+            _ => {}
+        }
+        match self {
+            VisibilityUnit::StatuteMiles => round_to(vis.statute_miles(), 0.1),
+            VisibilityUnit::Kilometers => round_to(vis.meters() / 1000.0, 0.1),
+            VisibilityUnit::Meters => round_to(vis.meters(), 100.0),
+        }
+    }
+
+    pub fn abbreviation(&self) -> &str {
+        match self {
+            VisibilityUnit::StatuteMiles => "SM",
+            VisibilityUnit::Kilometers => "km",
+            VisibilityUnit::Meters => "m",
+        }
+    }
+
+    pub fn name(&self, value: f64) -> &str {
+        match self {
+            VisibilityUnit::StatuteMiles if value <= 1.0 => "mile",
+            VisibilityUnit::StatuteMiles => "miles",
+            VisibilityUnit::Kilometers if value == 1.0 => "kilometer",
+            VisibilityUnit::Kilometers => "kilometers",
+            VisibilityUnit::Meters => "meters",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureUnit {
+    InchesOfMercury,
+    Hectopascals,
+    MillimetersOfMercury,
+}
+
+impl PressureUnit {
+    pub fn from_hpa(&self, hpa: f64) -> f64 {
+        match self {
+            PressureUnit::InchesOfMercury => hpa / HPA_PER_INHG,
+            PressureUnit::Hectopascals => hpa,
+            PressureUnit::MillimetersOfMercury => hpa * MMHG_PER_HPA,
+        }
+    }
+
+    /// Decimal places an altimeter setting is given to in this unit
+    pub fn decimals(&self) -> usize {
+        match self {
+            PressureUnit::InchesOfMercury => 2,
+            PressureUnit::Hectopascals | PressureUnit::MillimetersOfMercury => 0,
+        }
+    }
+
+    pub fn abbreviation(&self) -> &str {
+        match self {
+            PressureUnit::InchesOfMercury => "inHg",
+            PressureUnit::Hectopascals => "hPa",
+            PressureUnit::MillimetersOfMercury => "mmHg",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            PressureUnit::InchesOfMercury => "inches of mercury",
+            PressureUnit::Hectopascals => "hectopascals",
+            PressureUnit::MillimetersOfMercury => "millimeters of mercury",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AltitudeUnit {
+    Feet,
+    Meters,
+}
+
+impl AltitudeUnit {
+    pub fn from_feet(&self, ft: f64) -> f64 {
+        match self {
+            AltitudeUnit::Feet => ft,
+            AltitudeUnit::Meters => ft * METERS_PER_FOOT,
+        }
+    }
+
+    pub fn abbreviation(&self) -> &str {
+        match self {
+            AltitudeUnit::Feet => "ft",
+            AltitudeUnit::Meters => "m",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            AltitudeUnit::Feet => "feet",
+            AltitudeUnit::Meters => "meters",
+        }
+    }
+}
+
+/// The unit used for each kind of quantity.
+///
+/// The default is what this tool has always shown: Fahrenheit, knots,
+/// statute miles, inches of mercury and feet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Units {
+    pub temperature: TemperatureUnit,
+    pub wind_speed: WindSpeedUnit,
+    pub visibility: VisibilityUnit,
+    pub pressure: PressureUnit,
+    pub altitude: AltitudeUnit,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Units {
+            temperature: TemperatureUnit::Fahrenheit,
+            wind_speed: WindSpeedUnit::Knots,
+            visibility: VisibilityUnit::StatuteMiles,
+            pressure: PressureUnit::InchesOfMercury,
+            altitude: AltitudeUnit::Feet,
+        }
+    }
+}
+
+impl Units {
+    /// Fahrenheit, miles per hour, statute miles, inches of mercury, feet
+    pub fn imperial() -> Units {
+        Units {
+            temperature: TemperatureUnit::Fahrenheit,
+            wind_speed: WindSpeedUnit::MilesPerHour,
+            visibility: VisibilityUnit::StatuteMiles,
+            pressure: PressureUnit::InchesOfMercury,
+            altitude: AltitudeUnit::Feet,
+        }
+    }
+
+    /// Celsius, kilometers per hour, kilometers, hectopascals, meters
+    pub fn metric() -> Units {
+        Units {
+            temperature: TemperatureUnit::Celsius,
+            wind_speed: WindSpeedUnit::KilometersPerHour,
+            visibility: VisibilityUnit::Kilometers,
+            pressure: PressureUnit::Hectopascals,
+            altitude: AltitudeUnit::Meters,
+        }
+    }
+
+    /// ICAO units: Celsius, knots, meters, hectopascals, feet
+    pub fn aviation() -> Units {
+        Units {
+            temperature: TemperatureUnit::Celsius,
+            wind_speed: WindSpeedUnit::Knots,
+            visibility: VisibilityUnit::Meters,
+            pressure: PressureUnit::Hectopascals,
+            altitude: AltitudeUnit::Feet,
+        }
+    }
+
+    /// e.g. "22.2°C"
+    pub fn format_temperature(&self, celsius: f64) -> String {
+        format!(
+            "{:.1}{}",
+            self.temperature.from_celsius(celsius),
+            self.temperature.symbol()
+        )
+    }
+
+    /// e.g. "280° at 12 kt gusting 20 kt, variable 240°-300°"
+    pub fn format_wind(&self, wind: &Wind) -> String {
+        if wind.is_calm() {
+            return "Calm".to_string();
+        }
+        let unit = self.wind_speed.abbreviation();
+        let mut text = match wind.direction {
+            Some(dir) => format!("{:03}°", dir),
+            None => "Variable".to_string(),
+        };
+        text.push_str(&format!(
+            " at {} {}",
+            self.wind_speed(wind.speed_knots()),
+            unit
+        ));
+        if let Some(gust) = wind.gust_knots() {
+            text.push_str(&format!(" gusting {} {}", self.wind_speed(gust), unit));
+        }
+        if let Some((from, to)) = wind.variable_sector {
+            text.push_str(&format!(", variable {:03}°-{:03}°", from, to));
+        }
+        text
+    }
+
+    /// Wind speed in knots converted and rounded to a whole number
+    pub fn wind_speed(&self, knots: f64) -> i64 {
+        self.wind_speed.from_knots(knots).round() as i64
+    }
+
+    /// e.g. "more than 10 SM"
+    pub fn format_visibility(&self, vis: &Visibility) -> String {
+        let prefix = match vis.bound {
+            Some(Bound::LessThan) => "less than ",
+            Some(Bound::GreaterThan) => "more than ",
+            None => "",
+        };
+        format!(
+            "{}{} {}",
+            prefix,
+            format_number(self.visibility.convert(vis)),
+            self.visibility.abbreviation()
+        )
+    }
+
+    /// A distance between places, in the unit that goes with the wind speed:
+    /// nautical miles with knots, statute miles with mph, kilometers otherwise
+    pub fn format_distance(&self, km: f64) -> String {
+        let (value, unit) = match self.wind_speed {
+            WindSpeedUnit::Knots => (km / KMH_PER_KNOT, "nm"),
+            WindSpeedUnit::MilesPerHour => (km / KMH_PER_KNOT * MPH_PER_KNOT, "mi"),
+            WindSpeedUnit::KilometersPerHour | WindSpeedUnit::MetersPerSecond => (km, "km"),
+        };
+        let decimals = if value < 10.0 { 1 } else { 0 };
+        format!("{:.*} {}", decimals, value, unit)
+    }
+
+    /// An altimeter setting, e.g. "29.92 inHg"
+    pub fn format_pressure(&self, hpa: f64) -> String {
+        format!(
+            "{:.*} {}",
+            self.pressure.decimals(),
+            self.pressure.from_hpa(hpa),
+            self.pressure.abbreviation()
+        )
+    }
+
+    /// A pressure to at least tenths, e.g. "1013.2 hPa"
+    pub fn format_pressure_precise(&self, hpa: f64) -> String {
+        format!(
+            "{:.*} {}",
+            self.pressure.decimals().max(1),
+            self.pressure.from_hpa(hpa),
+            self.pressure.abbreviation()
+        )
+    }
+
+    /// A height or altitude in feet, rounded to the nearest 10 m when metric
+    pub fn altitude(&self, feet: f64) -> i64 {
+        match self.altitude {
+            AltitudeUnit::Feet => feet.round() as i64,
+            AltitudeUnit::Meters => round_to(self.altitude.from_feet(feet), 10.0) as i64,
+        }
+    }
+
+    /// e.g. "2100 ft"
+    pub fn format_altitude(&self, feet: f64) -> String {
+        format!("{} {}", self.altitude(feet), self.altitude.abbreviation())
+    }
+
+    /// e.g. "Broken at 2500 ft (Cumulonimbus)"
+    pub fn format_cloud_layer(&self, layer: &CloudLayer) -> String {
+        let mut text = layer.cover.description().to_string();
+        if let Some(base) = layer.base {
+            text.push_str(&format!(" at {}", self.format_altitude(base as f64)));
+        }
+        if let Some(cloud_type) = layer.cloud_type {
+            text.push_str(&format!(" ({})", cloud_type.description()));
+        }
+        text
+    }
+}
+
+fn round_to(value: f64, step: f64) -> f64 {
+    (value / step).round() * step
+}
+
+pub(crate) fn format_number(value: f64) -> String {
+    let s = format!("{:.2}", value);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+impl FromStr for TemperatureUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "c" | "celsius" => Ok(TemperatureUnit::Celsius),
+            "f" | "fahrenheit" => Ok(TemperatureUnit::Fahrenheit),
+            other => Err(format!("Unknown temperature unit: {}", other)),
+        }
+    }
+}
+
+impl FromStr for WindSpeedUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "kt" | "knots" => Ok(WindSpeedUnit::Knots),
+            "mph" => Ok(WindSpeedUnit::MilesPerHour),
+            "kmh" | "km/h" | "kph" => Ok(WindSpeedUnit::KilometersPerHour),
+            "ms" | "m/s" | "mps" => Ok(WindSpeedUnit::MetersPerSecond),
+            other => Err(format!("Unknown wind speed unit: {}", other)),
+        }
+    }
+}
+
+impl FromStr for VisibilityUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sm" | "mi" | "miles" => Ok(VisibilityUnit::StatuteMiles),
+            "km" => Ok(VisibilityUnit::Kilometers),
+            "m" | "meters" => Ok(VisibilityUnit::Meters),
+            other => Err(format!("Unknown visibility unit: {}", other)),
+        }
+    }
+}
+
+impl FromStr for PressureUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "inhg" | "in" => Ok(PressureUnit::InchesOfMercury),
+            "hpa" | "mb" | "mbar" => Ok(PressureUnit::Hectopascals),
+            "mmhg" | "mm" => Ok(PressureUnit::MillimetersOfMercury),
+            other => Err(format!("Unknown pressure unit: {}", other)),
+        }
+    }
+}
+
+impl FromStr for AltitudeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ft" | "feet" => Ok(AltitudeUnit::Feet),
+            "m" | "meters" => Ok(AltitudeUnit::Meters),
+            other => Err(format!("Unknown altitude unit: {}", other)),
+        }
+    }
+}
+
+/// Parse a unit system name, optionally followed by per-quantity overrides,
+/// e.g. `metric`, `aviation,pressure=inhg` or `temperature=c,wind=kmh`.
+/// Overrides without a system name apply to the default units.
+impl FromStr for Units {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut units = Units::default();
+        for (i, part) in s.split(',').map(str::trim).enumerate() {
+            match part.split_once('=') {
+                Some((quantity, unit)) => match quantity.to_lowercase().as_str() {
+                    "temperature" | "temp" => units.temperature = unit.parse()?,
+                    "wind" | "speed" => units.wind_speed = unit.parse()?,
+                    "visibility" | "vis" => units.visibility = unit.parse()?,
+                    "pressure" => units.pressure = unit.parse()?,
+                    "altitude" | "height" => units.altitude = unit.parse()?,
+                    other => return Err(format!("Unknown quantity: {}", other)),
+                },
+                None if i == 0 => {
+                    units = match part.to_lowercase().as_str() {
+                        "default" => Units::default(),
+                        "imperial" => Units::imperial(),
+                        "metric" => Units::metric(),
+                        "aviation" => Units::aviation(),
+                        other => return Err(format!("Unknown unit system: {}", other)),
+                    }
+                }
+                None => return Err(format!("Expected QUANTITY=UNIT, found: {}", part)),
+            }
+        }
+        Ok(units)
+    }
+}
+
+impl fmt::Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {}, {}, {}, {}",
+            self.temperature.symbol(),
+            self.wind_speed.abbreviation(),
+            self.visibility.abbreviation(),
+            self.pressure.abbreviation(),
+            self.altitude.abbreviation()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Observation;
+
+    fn observation(raw: &str) -> Observation {
+        Observation::parse(raw).unwrap()
+    }
+
+    #[test]
+    fn parses_systems_and_overrides() {
+        assert_eq!("metric".parse::<Units>(), Ok(Units::metric()));
+        let units: Units = "aviation,pressure=inhg".parse().unwrap();
+        assert_eq!(units.pressure, PressureUnit::InchesOfMercury);
+        assert_eq!(units.visibility, VisibilityUnit::Meters);
+        let units: Units = "temp=c,wind=km/h".parse().unwrap();
+        assert_eq!(units.temperature, TemperatureUnit::Celsius);
+        assert_eq!(units.wind_speed, WindSpeedUnit::KilometersPerHour);
+        assert_eq!(units.altitude, AltitudeUnit::Feet);
+        assert!("nautical".parse::<Units>().is_err());
+        assert!("metric,imperial".parse::<Units>().is_err());
+        assert!("metric,wind=furlongs".parse::<Units>().is_err());
+    }
+
+    #[test]
+    fn formats_a_us_report() {
+        let obs = observation("KSFO 171756Z 28012G20KT 250V310 1 1/2SM BR BKN008 22/18 A2992");
+        let units = Units::default();
+        assert_eq!(units.format_temperature(22.0), "71.6°F");
+        assert_eq!(
+            units.format_wind(obs.wind.as_ref().unwrap()),
+            "280° at 12 kt gusting 20 kt, variable 250°-310°"
+        );
+        assert_eq!(
+            units.format_visibility(obs.visibility.as_ref().unwrap()),
+            "1.5 SM"
+        );
+        assert_eq!(
+            units.format_pressure(obs.altimeter.unwrap().hpa()),
+            "29.92 inHg"
+        );
+        assert_eq!(units.format_cloud_layer(&obs.sky[0]), "Broken at 800 ft");
+    }
+
+    #[test]
+    fn formats_an_icao_report_in_other_units() {
+        let obs = observation("EGLL 171750Z 24008MPS 9999 FEW020CB 15/09 Q1008");
+        let wind = obs.wind.as_ref().unwrap();
+        let visibility = obs.visibility.as_ref().unwrap();
+
+        let aviation = Units::aviation();
+        assert_eq!(aviation.format_wind(wind), "240° at 16 kt");
+        assert_eq!(aviation.format_visibility(visibility), "more than 10000 m");
+        assert_eq!(aviation.format_pressure(1008.0), "1008 hPa");
+
+        let metric = Units::metric();
+        assert_eq!(metric.format_wind(wind), "240° at 29 km/h");
+        assert_eq!(metric.format_visibility(visibility), "more than 10 km");
+        assert_eq!(
+            metric.format_cloud_layer(&obs.sky[0]),
+            "Few at 610 m (Cumulonimbus)"
+        );
+
+        let imperial = Units::imperial();
+        assert_eq!(imperial.format_wind(wind), "240° at 18 mph");
+        assert_eq!(imperial.format_visibility(visibility), "more than 6.2 SM");
+        assert_eq!(imperial.format_pressure(1008.0), "29.77 inHg");
+        assert_eq!(
+            PressureUnit::MillimetersOfMercury.from_hpa(1008.0).round(),
+            756.0
+        );
+    }
+
+    #[test]
+    fn formats_bounds_and_distances() {
+        let obs = observation("KDEN 171753Z 00000KT P6SM SKC 20/M02 A3001");
+        assert_eq!(
+            obs.wind
+                .as_ref()
+                .map(|w| Units::default().format_wind(w))
+                .unwrap(),
+            "Calm"
+        );
+        assert_eq!(
+            Units::default().format_visibility(obs.visibility.as_ref().unwrap()),
+            "more than 6 SM"
+        );
+        assert_eq!(Units::default().format_distance(18.52), "10 nm");
+        assert_eq!(Units::imperial().format_distance(5.0), "3.1 mi");
+        assert_eq!(Units::metric().format_distance(120.4), "120 km");
+    }
+
+    #[test]
+    fn formats_numbers_without_trailing_zeros() {
+        assert_eq!(format_number(10.0), "10");
+        assert_eq!(format_number(0.25), "0.25");
+        assert_eq!(format_number(1.5), "1.5");
+        assert_eq!(format_number(2.999), "3");
+    }
+}
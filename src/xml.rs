@@ -0,0 +1,173 @@
+//! The XML format of the aviationweather.gov data API (`format=xml`), which
+//! the bulk cache files share, and detection of XML METAR documents.
+
+use crate::iwxxm;
+use crate::units::HPA_PER_INHG;
+use crate::{CloudReport, MetarData, Result, TafData, WeatherError};
+use chrono::{DateTime, FixedOffset};
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// METARs from an API XML response
+pub fn parse_metars(text: &str) -> Result<Vec<MetarData>> {
+    let records = xml_records(text, "METAR")?;
+    Ok(records.into_iter().filter_map(Record::into_metar).collect())
+}
+
+/// TAFs from an API XML response
+pub fn parse_tafs(text: &str) -> Result<Vec<TafData>> {
+    let records = xml_records(text, "TAF")?;
+    Ok(records.into_iter().filter_map(Record::into_taf).collect())
+}
+
+/// METARs from an XML document of either kind: an API response, or IWXXM
+pub fn parse_metar_document(text: &str) -> Result<Vec<MetarData>> {
+    if iwxxm::is_iwxxm(text) {
+        iwxxm::parse_metars(text)
+    } else {
+        parse_metars(text)
+    }
+}
+
+/// One report's fields under the names the XML responses and the bulk CSV
+/// files share. Sky conditions repeat, so they are kept apart as (cover,
+/// base) pairs.
+#[derive(Debug, Default)]
+pub(crate) struct Record {
+    pub fields: HashMap<String, String>,
+    pub sky: Vec<(String, Option<u32>)>,
+}
+
+impl Record {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .get(name)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn number<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.get(name)?.parse().ok()
+    }
+
+    fn time(&self, name: &str) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(self.get(name)?).ok()
+    }
+
+    pub fn into_metar(self) -> Option<MetarData> {
+        let obs_time = self.time("observation_time");
+        Some(MetarData {
+            icao_id: self.get("station_id")?.to_uppercase(),
+            raw_ob: self.get("raw_text")?.to_string(),
+            obs_time: obs_time.map(|t| t.timestamp()),
+            report_time: obs_time.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+            temp: self.number("temp_c"),
+            dewp: self.number("dewpoint_c"),
+            wdir: self.number("wind_dir_degrees"),
+            wspd: self.number("wind_speed_kt"),
+            wgst: self.number("wind_gust_kt"),
+            visib: self.number("visibility_statute_mi"),
+            altim: self
+                .number::<f64>("altim_in_hg")
+                .map(|inhg| inhg * HPA_PER_INHG),
+            slp: self.number("sea_level_pressure_mb"),
+            wx_string: self.get("wx_string").map(str::to_string),
+            pres_tend: self.number("three_hr_pressure_tendency_mb"),
+            max_t: self.number("maxT_c"),
+            min_t: self.number("minT_c"),
+            max_t24: self.number("maxT24hr_c"),
+            min_t24: self.number("minT24hr_c"),
+            precip: self.number("precip_in"),
+            pcp3hr: self.number("pcp3hr_in"),
+            pcp6hr: self.number("pcp6hr_in"),
+            pcp24hr: self.number("pcp24hr_in"),
+            snow: self.number("snow_in"),
+            vert_vis: self.number("vert_vis_ft"),
+            metar_type: self.get("metar_type").map(str::to_string),
+            lat: self.number("latitude"),
+            lon: self.number("longitude"),
+            elev: self.number("elevation_m"),
+            flt_cat: self.get("flight_category").map(str::to_string),
+            clouds: self
+                .sky
+                .into_iter()
+                .map(|(cover, base)| CloudReport { cover, base })
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    pub fn into_taf(self) -> Option<TafData> {
+        Some(TafData {
+            icao_id: self.get("station_id")?.to_uppercase(),
+            raw_taf: self.get("raw_text")?.to_string(),
+            issue_time: self.get("issue_time").map(str::to_string),
+            bulletin_time: self.get("bulletin_time").map(str::to_string),
+            valid_time_from: self.time("valid_time_from").map(|t| t.timestamp()),
+            valid_time_to: self.time("valid_time_to").map(|t| t.timestamp()),
+            remarks: self.get("remarks").map(str::to_string),
+            lat: self.number("latitude"),
+            lon: self.number("longitude"),
+            elev: self.number("elevation_m"),
+            ..Default::default()
+        })
+    }
+}
+
+/// The `element` (`METAR` or `TAF`) elements of an XML document. Fields are
+/// the text of leaf elements, at any depth; sky conditions are
+/// `<sky_condition>` attributes.
+pub(crate) fn xml_records(text: &str, element: &str) -> Result<Vec<Record>> {
+    let mut reader = quick_xml::Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    let mut records = Vec::new();
+    let mut current: Option<Record> = None;
+    let mut name = String::new();
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| WeatherError::invalid_data("XML", e))?;
+        match event {
+            Event::Start(start) => {
+                name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+                if name == element {
+                    current = Some(Record::default());
+                }
+            }
+            Event::Empty(empty) if empty.name().as_ref() == b"sky_condition" => {
+                let Some(record) = current.as_mut() else {
+                    continue;
+                };
+                let mut cover = None;
+                let mut base = None;
+                for attribute in empty.attributes().flatten() {
+                    let value = attribute
+                        .unescape_value()
+                        .map_err(|e| WeatherError::invalid_data("XML", e))?;
+                    match attribute.key.as_ref() {
+                        b"sky_cover" => cover = Some(value.into_owned()),
+                        b"cloud_base_ft_agl" => base = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+                record.sky.extend(cover.map(|cover| (cover, base)));
+            }
+            Event::Text(text) => {
+                if let Some(record) = current.as_mut() {
+                    let value = text
+                        .unescape()
+                        .map_err(|e| WeatherError::invalid_data("XML", e))?;
+                    record.fields.insert(name.clone(), value.into_owned());
+                }
+            }
+            Event::End(end) if end.name().as_ref() == element.as_bytes() => {
+                records.extend(current.take());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(records)
+}